@@ -0,0 +1,70 @@
+//! 池子状态检查工具
+//!
+//! 用法：amm-inspect <config.json> <vault_x.json> <vault_y.json> <mint_lp.json> [--now <unix 时间戳>]
+//!
+//! 每个 JSON 文件为 `solana account <地址> --output json` 导出的账户，
+//! 也接受只包含 `{"data": ["<base64>", "base64"]}` 的对象
+
+use std::{
+    env, fs, process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use blueshift_amm_client::inspect_pool;
+use serde_json::Value;
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {error}");
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    let now = match args.iter().position(|arg| arg == "--now") {
+        Some(index) => {
+            let value = args.get(index + 1).ok_or("--now requires a value")?;
+            let now = value.parse::<i64>().map_err(|_| format!("invalid --now: {value}"))?;
+            args.drain(index..index + 2);
+            now
+        }
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|error| error.to_string())?
+            .as_secs() as i64,
+    };
+
+    let [config, vault_x, vault_y, mint_lp] = args.as_slice() else {
+        return Err("usage: amm-inspect <config.json> <vault_x.json> <vault_y.json> <mint_lp.json> [--now <ts>]".into());
+    };
+
+    let report = inspect_pool(
+        &read_account(config)?,
+        &read_account(vault_x)?,
+        &read_account(vault_y)?,
+        &read_account(mint_lp)?,
+        now,
+    )
+    .map_err(|error| error.to_string())?;
+
+    println!("{report}");
+    Ok(())
+}
+
+/// 读取账户导出文件中的数据
+fn read_account(path: &str) -> Result<Vec<u8>, String> {
+    let text = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    let json: Value = serde_json::from_str(&text).map_err(|error| format!("{path}: {error}"))?;
+
+    let data = json
+        .get("account")
+        .unwrap_or(&json)
+        .get("data")
+        .and_then(|data| data.get(0))
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("{path}: missing account data"))?;
+
+    STANDARD.decode(data).map_err(|error| format!("{path}: {error}"))
+}
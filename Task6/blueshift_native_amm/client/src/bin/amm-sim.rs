@@ -0,0 +1,127 @@
+//! 离线池子模拟工具
+//!
+//! 用法：amm-sim <trades.csv|trades.json> --reserve-x <数量> --reserve-y <数量> --fee <基点>
+//!       [--weights <起始 X 权重>,<结束 X 权重>,<开始时间>,<结束时间>]
+//!       [--dynamic-fee <下限>,<上限>,<波动率比例>,<半衰期>]
+//!       [--price-limits <单笔上限>,<单 slot 上限>]
+//!
+//! 交易流为 CSV（表头 timestamp,side,amount[,slot]）或 JSON 对象数组；
+//! 逐步结果以 CSV 输出到标准输出，汇总输出到标准错误
+
+use std::{env, fs, process};
+use blueshift_amm_client::sim::{simulate, DynamicFee, SimParams, StepReport, Trade};
+use blueshift_native_amm::instructions::initialize::WeightSchedule;
+
+const USAGE: &str = "usage: amm-sim <trades.csv|trades.json> --reserve-x <n> --reserve-y <n> --fee <bps> \
+    [--weights <start_wx>,<end_wx>,<start_at>,<end_at>] [--dynamic-fee <min>,<max>,<factor>,<decay>] \
+    [--price-limits <impact>,<slot_move>]";
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {error}");
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    let reserve_x = take_option(&mut args, "--reserve-x")?.ok_or(USAGE)?;
+    let reserve_y = take_option(&mut args, "--reserve-y")?.ok_or(USAGE)?;
+    let fee = take_option(&mut args, "--fee")?.ok_or(USAGE)?;
+    let weights = take_option(&mut args, "--weights")?;
+    let dynamic_fee = take_option(&mut args, "--dynamic-fee")?;
+    let price_limits = take_option(&mut args, "--price-limits")?;
+
+    let [path] = args.as_slice() else {
+        return Err(USAGE.into());
+    };
+
+    let weight_schedule = match weights {
+        Some(value) => {
+            let [start_weight_x, end_weight_x, start_at, end_at] = parse_list(&value, "--weights")?;
+            Some(WeightSchedule {
+                start_weight_x: start_weight_x as u16,
+                end_weight_x: end_weight_x as u16,
+                start_at,
+                end_at,
+            })
+        }
+        None => None,
+    };
+    let dynamic_fee = match dynamic_fee {
+        Some(value) => {
+            let [min_fee, max_fee, volatility_factor, decay_period] = parse_list(&value, "--dynamic-fee")?;
+            Some(DynamicFee {
+                min_fee: min_fee as u16,
+                max_fee: max_fee as u16,
+                volatility_factor: volatility_factor as u16,
+                decay_period: decay_period as u64,
+            })
+        }
+        None => None,
+    };
+    let [max_price_impact, max_slot_price_move] = match price_limits {
+        Some(value) => parse_list(&value, "--price-limits")?,
+        None => [0, 0],
+    };
+
+    let params = SimParams {
+        reserve_x: parse_number(&reserve_x, "--reserve-x")?,
+        reserve_y: parse_number(&reserve_y, "--reserve-y")?,
+        fee: parse_number(&fee, "--fee")?,
+        dynamic_fee,
+        weight_schedule,
+        max_price_impact: max_price_impact as u16,
+        max_slot_price_move: max_slot_price_move as u16,
+    };
+
+    let text = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    let trades = if text.trim_start().starts_with('[') {
+        Trade::parse_json(&text)
+    } else {
+        Trade::parse_csv(&text)
+    }
+    .map_err(|error| format!("{path}: {error}"))?;
+
+    let reports = simulate(&params, &trades).map_err(|error| error.to_string())?;
+
+    println!("{}", StepReport::CSV_HEADER);
+    for report in &reports {
+        println!("{}", report.to_csv());
+    }
+
+    if let Some(last) = reports.last() {
+        let rejected = reports.iter().filter(|report| report.rejected.is_some()).count();
+        eprintln!("trades:           {} ({rejected} rejected)", reports.len());
+        eprintln!("final reserves:   {} x / {} y", last.reserve_x, last.reserve_y);
+        eprintln!("fees accrued:     {} x / {} y", last.fees_x, last.fees_y);
+        eprintln!("lp pnl vs hold:   {:.4} y", last.pnl_vs_hold);
+        eprintln!("impermanent loss: {:.4}%", last.impermanent_loss * 100.0);
+    }
+
+    Ok(())
+}
+
+/// 取出 `--name value` 形式的参数
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    let Some(index) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    let value = args.get(index + 1).ok_or_else(|| format!("{name} requires a value"))?.clone();
+    args.drain(index..index + 2);
+    Ok(Some(value))
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, name: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid {name}: {value}"))
+}
+
+/// 解析逗号分隔的 N 个整数
+fn parse_list<const N: usize>(value: &str, name: &str) -> Result<[i64; N], String> {
+    let values = value
+        .split(',')
+        .map(|item| parse_number::<i64>(item.trim(), name))
+        .collect::<Result<Vec<_>, _>>()?;
+    values.try_into().map_err(|_| format!("{name} expects {N} comma-separated values"))
+}
@@ -0,0 +1,234 @@
+//! 池子账户解码与状态检查
+
+use core::fmt;
+use blueshift_native_amm::{AmmError, AmmState, Config};
+use solana_address::Address;
+
+/// 代币账户中 mint 字段的偏移
+const TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
+/// 代币账户中 amount 字段的偏移
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+/// 代币账户最小大小
+const TOKEN_ACCOUNT_LEN: usize = 165;
+/// Mint 账户中 supply 字段的偏移
+const MINT_SUPPLY_OFFSET: usize = 36;
+/// Mint 账户中 decimals 字段的偏移
+const MINT_DECIMALS_OFFSET: usize = 44;
+/// 标准 Mint 账户大小
+const MINT_LEN: usize = 82;
+
+/// 检查失败的原因
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InspectError {
+    InvalidConfig,          // 不是 Config 账户
+    OutdatedConfig,         // Config 布局过旧，需要先执行 MigrateConfig
+    InvalidVault,           // 金库不是代币账户
+    VaultMintMismatch,      // 金库的 mint 与 Config 不一致
+    InvalidMint,            // LP Mint 不是 Mint 账户
+}
+
+impl fmt::Display for InspectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::InvalidConfig => "not a config account",
+            Self::OutdatedConfig => "config account uses an outdated layout, run migrate_config first",
+            Self::InvalidVault => "vault is not a token account",
+            Self::VaultMintMismatch => "vault mint does not match config",
+            Self::InvalidMint => "lp mint is not a mint account",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for InspectError {}
+
+/// 池子状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolState {
+    Uninitialized,
+    Initialized,
+    Disabled,
+    WithdrawOnly,
+    Unknown(u8),
+}
+
+impl From<u8> for PoolState {
+    fn from(state: u8) -> Self {
+        match state {
+            s if s == AmmState::Uninitialized as u8 => Self::Uninitialized,
+            s if s == AmmState::Initialized as u8 => Self::Initialized,
+            s if s == AmmState::Disabled as u8 => Self::Disabled,
+            s if s == AmmState::WithdrawOnly as u8 => Self::WithdrawOnly,
+            s => Self::Unknown(s),
+        }
+    }
+}
+
+/// 解码后的 Config
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigInfo {
+    pub state: PoolState,
+    pub seed: u64,
+    pub authority: Option<Address>,     // None 表示不可变
+    pub guardian: Option<Address>,      // 暂停权限，None 表示未设置
+    pub mint_x: Address,
+    pub mint_y: Address,
+    pub fee: u16,                       // 基础费率（基点）
+    pub dynamic_fee: bool,
+    pub permissioned: bool,
+    pub virtual_orders: bool,
+    pub limit_orders: bool,
+    pub lbp: bool,
+    pub oracle: Option<Address>,        // 参考价格源，None 表示未开启参考价格保护
+    pub reserved_x: u64,                // 金库中属于订单的 X
+    pub reserved_y: u64,                // 金库中属于订单的 Y
+}
+
+/// 解码 Config 账户数据
+pub fn decode_config(data: &[u8]) -> Result<ConfigInfo, InspectError> {
+    let config = load_config(data)?;
+    let authority = Address::new_from_array(config.authority);
+
+    Ok(ConfigInfo {
+        state: config.state.into(),
+        seed: config.seed(),
+        authority: (authority != Address::default()).then_some(authority),
+        guardian: (config.guardian != [0u8; 32]).then(|| Address::new_from_array(config.guardian)),
+        mint_x: config.mint_x_address(),
+        mint_y: config.mint_y_address(),
+        fee: config.fee(),
+        dynamic_fee: config.is_dynamic_fee(),
+        permissioned: config.is_permissioned(),
+        virtual_orders: config.has_virtual_orders(),
+        limit_orders: config.has_limit_orders(),
+        lbp: config.is_lbp(),
+        oracle: config.has_oracle().then(|| Address::new_from_array(config.oracle)),
+        reserved_x: config.reserved_x(),
+        reserved_y: config.reserved_y(),
+    })
+}
+
+/// 池子状态报告
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolReport {
+    pub config: ConfigInfo,
+    pub reserve_x: u64,         // 金库余额扣除属于订单的部分
+    pub reserve_y: u64,
+    pub weight_x: u64,          // now 时刻的权重（基点）
+    pub weight_y: u64,
+    pub spot_price: Option<f64>, // 每单位 X 对应的 Y（最小单位），储备为 0 时为 None
+    pub lp_supply: u64,
+    pub lp_decimals: u8,
+    pub effective_fee: u16,     // now 时刻生效的费率（基点）
+}
+
+/// 根据 Config、两个金库和 LP Mint 的账户数据生成池子状态报告
+///
+/// now 为计算动态费用和 LBP 权重所用的时间（unix 时间戳）
+pub fn inspect_pool(
+    config_data: &[u8],
+    vault_x_data: &[u8],
+    vault_y_data: &[u8],
+    mint_lp_data: &[u8],
+    now: i64,
+) -> Result<PoolReport, InspectError> {
+    let info = decode_config(config_data)?;
+    let config = load_config(config_data)?;
+
+    let balance_x = vault_amount(vault_x_data, &info.mint_x)?;
+    let balance_y = vault_amount(vault_y_data, &info.mint_y)?;
+    let (reserve_x, reserve_y) = config.reserves(balance_x, balance_y);
+    let (weight_x, weight_y) = config.weights(now);
+
+    if mint_lp_data.len() < MINT_LEN {
+        return Err(InspectError::InvalidMint);
+    }
+    let lp_supply = read_u64(mint_lp_data, MINT_SUPPLY_OFFSET);
+    let lp_decimals = mint_lp_data[MINT_DECIMALS_OFFSET];
+
+    // 加权现货价格：(y / wy) / (x / wx)
+    let spot_price = (reserve_x != 0 && reserve_y != 0).then(|| {
+        (reserve_y as f64 * weight_x as f64) / (reserve_x as f64 * weight_y as f64)
+    });
+
+    Ok(PoolReport {
+        effective_fee: config.effective_fee(now),
+        config: info,
+        reserve_x,
+        reserve_y,
+        weight_x,
+        weight_y,
+        spot_price,
+        lp_supply,
+        lp_decimals,
+    })
+}
+
+impl fmt::Display for PoolReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let config = &self.config;
+        writeln!(f, "state:          {:?}", config.state)?;
+        match &config.authority {
+            Some(authority) => writeln!(f, "authority:      {authority}")?,
+            None => writeln!(f, "authority:      (immutable)")?,
+        }
+        if let Some(guardian) = &config.guardian {
+            writeln!(f, "guardian:       {guardian}")?;
+        }
+        writeln!(f, "seed:           {}", config.seed)?;
+        writeln!(f, "mint x:         {}", config.mint_x)?;
+        writeln!(f, "mint y:         {}", config.mint_y)?;
+        writeln!(
+            f,
+            "fee:            {} bps{}",
+            self.effective_fee,
+            if config.dynamic_fee { format!(" (dynamic, base {} bps)", config.fee) } else { String::new() },
+        )?;
+        writeln!(f, "reserve x:      {} (+{} reserved for orders)", self.reserve_x, config.reserved_x)?;
+        writeln!(f, "reserve y:      {} (+{} reserved for orders)", self.reserve_y, config.reserved_y)?;
+        if config.lbp {
+            writeln!(f, "weights:        {} / {} bps", self.weight_x, self.weight_y)?;
+        }
+        match self.spot_price {
+            Some(price) => writeln!(f, "spot price:     {price} y per x")?,
+            None => writeln!(f, "spot price:     -")?,
+        }
+        if let Some(oracle) = &config.oracle {
+            writeln!(f, "oracle:         {oracle}")?;
+        }
+        writeln!(f, "lp supply:      {} (decimals {})", self.lp_supply, self.lp_decimals)?;
+        write!(
+            f,
+            "features:       permissioned={} twamm={} limit_orders={}",
+            config.permissioned, config.virtual_orders, config.limit_orders,
+        )
+    }
+}
+
+/// 加载 Config，区分旧布局和无效账户
+fn load_config(data: &[u8]) -> Result<&Config, InspectError> {
+    Config::load(data).map_err(|error| {
+        if error == AmmError::ConfigOutdated.into() {
+            InspectError::OutdatedConfig
+        } else {
+            InspectError::InvalidConfig
+        }
+    })
+}
+
+/// 读取金库余额并验证 mint
+fn vault_amount(data: &[u8], mint: &Address) -> Result<u64, InspectError> {
+    if data.len() < TOKEN_ACCOUNT_LEN {
+        return Err(InspectError::InvalidVault);
+    }
+    if data[TOKEN_ACCOUNT_MINT_OFFSET..TOKEN_ACCOUNT_MINT_OFFSET + 32] != mint.as_ref()[..] {
+        return Err(InspectError::VaultMintMismatch);
+    }
+
+    Ok(read_u64(data, TOKEN_ACCOUNT_AMOUNT_OFFSET))
+}
+
+#[inline(always)]
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
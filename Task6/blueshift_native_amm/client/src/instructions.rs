@@ -0,0 +1,405 @@
+//! 指令构建
+
+use blueshift_native_amm::{instructions::initialize::WeightSchedule, AmmState, POOLS_PER_PAGE};
+use solana_address::Address;
+use solana_instruction::{AccountMeta, Instruction};
+use crate::pda::*;
+
+pub use blueshift_native_amm::instructions::metadata::MetadataField;
+
+/// 指令编号
+pub mod discriminator {
+    pub const INITIALIZE: u8 = 0;
+    pub const DEPOSIT: u8 = 1;
+    pub const WITHDRAW: u8 = 2;
+    pub const SWAP: u8 = 3;
+    pub const SET_POOL_STATE: u8 = 23;
+    pub const MIGRATE_CONFIG: u8 = 24;
+    pub const UPDATE_LP_METADATA: u8 = 25;
+}
+
+/// 一个池子的全部相关地址
+///
+/// mint_x 必须小于 mint_y；规范池子的 seed 等于费率档位（基点）。
+/// 池子开启的可选功能决定 Deposit / Withdraw / Swap 需要追加的附加账户
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pool {
+    pub program_id: Address,
+    pub seed: u64,
+    pub mint_x: Address,
+    pub mint_y: Address,
+    pub token_program_x: Address,   // X 代币的 Token 程序（Token 或 Token-2022）
+    pub token_program_y: Address,   // Y 代币的 Token 程序（Token 或 Token-2022）
+    pub token_program_lp: Address,  // LP Mint 的 Token 程序（带元数据的池子为 Token-2022）
+    pub config: Address,
+    pub config_bump: u8,
+    pub mint_lp: Address,
+    pub lp_bump: u8,
+    pub vault_x: Address,           // config 的 X 关联代币账户
+    pub vault_y: Address,           // config 的 Y 关联代币账户
+    pub virtual_orders: bool,       // 已创建长期订单池
+    pub limit_orders: bool,         // 已创建限价单簿
+    pub permissioned: bool,         // 许可池子
+    pub oracle: Option<Address>,    // 参考价格源（设置了参考价格保护的池子）
+}
+
+impl Pool {
+    /// 派生池子的 Config、LP Mint 和金库地址
+    pub fn new(
+        program_id: &Address,
+        seed: u64,
+        mint_x: &Address,
+        mint_y: &Address,
+        token_program_x: &Address,
+        token_program_y: &Address,
+    ) -> Self {
+        let (config, config_bump) = config_address(program_id, seed, mint_x, mint_y);
+        let (mint_lp, lp_bump) = mint_lp_address(program_id, &config);
+
+        Self {
+            program_id: *program_id,
+            seed,
+            mint_x: *mint_x,
+            mint_y: *mint_y,
+            token_program_x: *token_program_x,
+            token_program_y: *token_program_y,
+            token_program_lp: TOKEN_PROGRAM_ID,
+            config,
+            config_bump,
+            mint_lp,
+            lp_bump,
+            vault_x: associated_token_address(&config, mint_x, token_program_x),
+            vault_y: associated_token_address(&config, mint_y, token_program_y),
+            virtual_orders: false,
+            limit_orders: false,
+            permissioned: false,
+            oracle: None,
+        }
+    }
+
+    /// 设置池子已创建长期订单池
+    pub fn with_virtual_orders(mut self, enabled: bool) -> Self {
+        self.virtual_orders = enabled;
+        self
+    }
+
+    /// 设置池子已创建限价单簿
+    pub fn with_limit_orders(mut self, enabled: bool) -> Self {
+        self.limit_orders = enabled;
+        self
+    }
+
+    /// 设置池子为许可池子
+    pub fn with_permissioned(mut self, enabled: bool) -> Self {
+        self.permissioned = enabled;
+        self
+    }
+
+    /// 设置池子的参考价格源（Config.oracle）
+    pub fn with_oracle(mut self, oracle: Option<Address>) -> Self {
+        self.oracle = oracle;
+        self
+    }
+
+    /// 设置 LP Mint 的 Token 程序；初始化时附带 LP 元数据的池子为 Token-2022
+    pub fn with_token_program_lp(mut self, token_program_lp: &Address) -> Self {
+        self.token_program_lp = *token_program_lp;
+        self
+    }
+
+    /// 用户的 X、Y、LP 代币账户（关联代币账户）
+    pub fn user_token_accounts(&self, user: &Address) -> (Address, Address, Address) {
+        (
+            associated_token_address(user, &self.mint_x, &self.token_program_x),
+            associated_token_address(user, &self.mint_y, &self.token_program_y),
+            associated_token_address(user, &self.mint_lp, &self.token_program_lp),
+        )
+    }
+
+    /// 附加账户：长期订单池、限价单簿和参考价格源（仅 Swap）、白名单条目（Withdraw 不需要）
+    fn trailing_accounts(&self, user: &Address, is_swap: bool, is_withdraw: bool) -> Vec<AccountMeta> {
+        let mut accounts = Vec::new();
+        if self.virtual_orders {
+            accounts.push(AccountMeta::new(order_pool_address(&self.program_id, &self.config).0, false));
+        }
+        if is_swap && self.limit_orders {
+            accounts.push(AccountMeta::new(order_book_address(&self.program_id, &self.config).0, false));
+        }
+        if let (true, Some(oracle)) = (is_swap, &self.oracle) {
+            accounts.push(AccountMeta::new_readonly(*oracle, false));
+        }
+        if self.permissioned && !is_withdraw {
+            accounts.push(AccountMeta::new_readonly(
+                allow_entry_address(&self.program_id, &self.config, user).0,
+                false,
+            ));
+        }
+        accounts
+    }
+}
+
+/// 新池子所在的注册表页
+pub fn registry_page_for(pool_count: u64) -> u32 {
+    (pool_count / POOLS_PER_PAGE as u64) as u32
+}
+
+/// Initialize 参数
+#[derive(Clone, Debug)]
+pub struct InitializeArgs {
+    pub fee: u16,                               // 费率（基点），必须是允许的档位且等于 seed
+    pub authority: Option<Address>,             // 池子管理权限，None 表示不可变；只有设置管理权限可以指定
+    pub registry_page: u32,                     // 当前注册表页，见 `registry_page_for`
+    pub weight_schedule: Option<WeightSchedule>, // LBP 权重计划
+    pub lp_metadata: Option<LpMetadata>,        // LP 代币元数据，设置时 LP Mint 使用 Token-2022
+}
+
+/// LP 代币元数据
+///
+/// 名称最长 32 字节，符号最长 16 字节，URI 最长 200 字节
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LpMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+impl LpMetadata {
+    /// 按交易对生成元数据：名称 "X-Y LP"，符号 "X-Y"
+    pub fn for_pair(symbol_x: &str, symbol_y: &str, uri: &str) -> Self {
+        Self {
+            name: format!("{symbol_x}-{symbol_y} LP"),
+            symbol: format!("{symbol_x}-{symbol_y}"),
+            uri: uri.to_string(),
+        }
+    }
+}
+
+/// Deposit 参数
+#[derive(Clone, Copy, Debug)]
+pub struct DepositArgs {
+    pub amount: u64,        // 铸造的 LP 数量
+    pub max_x: u64,         // 最多存入的 X
+    pub max_y: u64,         // 最多存入的 Y
+    pub expiration: i64,    // 过期时间（unix 时间戳）
+}
+
+/// Withdraw 参数
+#[derive(Clone, Copy, Debug)]
+pub struct WithdrawArgs {
+    pub amount: u64,        // 销毁的 LP 数量
+    pub min_x: u64,         // 最少取回的 X
+    pub min_y: u64,         // 最少取回的 Y
+    pub expiration: i64,    // 过期时间（unix 时间戳）
+}
+
+/// Swap 参数
+#[derive(Clone, Copy, Debug)]
+pub struct SwapArgs {
+    pub is_x: bool,         // true = X 换 Y
+    pub amount: u64,        // 输入数量
+    pub min: u64,           // 最小输出数量
+    pub expiration: i64,    // 过期时间（unix 时间戳）
+}
+
+/// 构建 Initialize 指令
+///
+/// 指令数据为完整的 109 字节（带 LBP 权重计划时 129 字节），
+/// authority 为 None 时写入零地址。附带 LP 元数据时权重计划位置写入全零（非 LBP 池子），
+/// 其后依次为带 1 字节长度前缀的名称、符号和 URI，LP Mint 由 Token-2022 创建，
+/// 之后的 Deposit / Withdraw 需要使用 `Pool::with_token_program_lp(&TOKEN_2022_ID)`
+pub fn initialize(pool: &Pool, initializer: &Address, args: &InitializeArgs) -> Instruction {
+    let (settings, _) = settings_address(&pool.program_id);
+    let (registry_page, registry_bump) = registry_page_address(&pool.program_id, args.registry_page);
+
+    let mut data = Vec::with_capacity(130);
+    data.push(discriminator::INITIALIZE);
+    data.extend_from_slice(&pool.seed.to_le_bytes());
+    data.extend_from_slice(&args.fee.to_le_bytes());
+    data.extend_from_slice(pool.mint_x.as_ref());
+    data.extend_from_slice(pool.mint_y.as_ref());
+    data.push(pool.config_bump);
+    data.push(pool.lp_bump);
+    data.extend_from_slice(args.authority.unwrap_or_default().as_ref());
+    data.push(registry_bump);
+    if let Some(schedule) = &args.weight_schedule {
+        data.extend_from_slice(&schedule.start_weight_x.to_le_bytes());
+        data.extend_from_slice(&schedule.end_weight_x.to_le_bytes());
+        data.extend_from_slice(&schedule.start_at.to_le_bytes());
+        data.extend_from_slice(&schedule.end_at.to_le_bytes());
+    } else if args.lp_metadata.is_some() {
+        data.extend_from_slice(&[0u8; 20]);
+    }
+    let lp_token_program = match &args.lp_metadata {
+        Some(metadata) => {
+            for value in [&metadata.name, &metadata.symbol, &metadata.uri] {
+                data.push(value.len() as u8);
+                data.extend_from_slice(value.as_bytes());
+            }
+            TOKEN_2022_ID
+        }
+        None => TOKEN_PROGRAM_ID,
+    };
+
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.mint_lp, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(lp_token_program, false),
+            AccountMeta::new(settings, false),
+            AccountMeta::new(registry_page, false),
+        ],
+        data,
+    }
+}
+
+/// 构建 Deposit 指令，使用用户的关联代币账户
+pub fn deposit(pool: &Pool, user: &Address, args: &DepositArgs) -> Instruction {
+    let mut data = Vec::with_capacity(33);
+    data.push(discriminator::DEPOSIT);
+    data.extend_from_slice(&args.amount.to_le_bytes());
+    data.extend_from_slice(&args.max_x.to_le_bytes());
+    data.extend_from_slice(&args.max_y.to_le_bytes());
+    data.extend_from_slice(&args.expiration.to_le_bytes());
+
+    Instruction {
+        program_id: pool.program_id,
+        accounts: liquidity_accounts(pool, user, false),
+        data,
+    }
+}
+
+/// 构建 Withdraw 指令，使用用户的关联代币账户
+pub fn withdraw(pool: &Pool, user: &Address, args: &WithdrawArgs) -> Instruction {
+    let mut data = Vec::with_capacity(33);
+    data.push(discriminator::WITHDRAW);
+    data.extend_from_slice(&args.amount.to_le_bytes());
+    data.extend_from_slice(&args.min_x.to_le_bytes());
+    data.extend_from_slice(&args.min_y.to_le_bytes());
+    data.extend_from_slice(&args.expiration.to_le_bytes());
+
+    Instruction {
+        program_id: pool.program_id,
+        accounts: liquidity_accounts(pool, user, true),
+        data,
+    }
+}
+
+/// 构建 Swap 指令，使用用户的关联代币账户
+pub fn swap(pool: &Pool, user: &Address, args: &SwapArgs) -> Instruction {
+    let (user_x_ata, user_y_ata, _) = pool.user_token_accounts(user);
+
+    let mut data = Vec::with_capacity(26);
+    data.push(discriminator::SWAP);
+    data.push(args.is_x as u8);
+    data.extend_from_slice(&args.amount.to_le_bytes());
+    data.extend_from_slice(&args.min.to_le_bytes());
+    data.extend_from_slice(&args.expiration.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(pool.config, false),
+        AccountMeta::new(pool.vault_x, false),
+        AccountMeta::new(pool.vault_y, false),
+        AccountMeta::new(user_x_ata, false),
+        AccountMeta::new(user_y_ata, false),
+        AccountMeta::new_readonly(pool.token_program_x, false),
+        AccountMeta::new_readonly(pool.token_program_y, false),
+        AccountMeta::new_readonly(pool.mint_x, false),
+        AccountMeta::new_readonly(pool.mint_y, false),
+    ];
+    accounts.extend(pool.trailing_accounts(user, true, false));
+
+    Instruction {
+        program_id: pool.program_id,
+        accounts,
+        data,
+    }
+}
+
+/// 构建 SetPoolState 指令
+///
+/// signer 为池子管理权限或暂停权限；暂停权限只能切换到 Disabled / WithdrawOnly。
+/// 已创建长期订单池的池子追加订单池和两个金库，切换前先结算长期订单
+pub fn set_pool_state(pool: &Pool, signer: &Address, state: AmmState) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*signer, true),
+        AccountMeta::new(pool.config, false),
+    ];
+    if pool.virtual_orders {
+        accounts.push(AccountMeta::new(order_pool_address(&pool.program_id, &pool.config).0, false));
+        accounts.push(AccountMeta::new_readonly(pool.vault_x, false));
+        accounts.push(AccountMeta::new_readonly(pool.vault_y, false));
+    }
+
+    Instruction {
+        program_id: pool.program_id,
+        accounts,
+        data: vec![discriminator::SET_POOL_STATE, state as u8],
+    }
+}
+
+/// 构建 MigrateConfig 指令
+///
+/// 把旧布局的 Config 升级为当前版本，payer 支付扩容所需租金；已是当前版本时不做修改
+pub fn migrate_config(pool: &Pool, payer: &Address) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: vec![discriminator::MIGRATE_CONFIG],
+    }
+}
+
+/// 构建 UpdateLpMetadata 指令
+///
+/// authority 为池子管理权限，同时支付元数据扩容的租金；只适用于初始化时附带元数据的池子
+pub fn update_lp_metadata(pool: &Pool, authority: &Address, field: MetadataField, value: &str) -> Instruction {
+    let mut data = vec![discriminator::UPDATE_LP_METADATA, pool.lp_bump, field as u8, value.len() as u8];
+    data.extend_from_slice(value.as_bytes());
+
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(pool.mint_lp, false),
+            AccountMeta::new_readonly(TOKEN_2022_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Deposit / Withdraw 共用的账户列表
+fn liquidity_accounts(pool: &Pool, user: &Address, is_withdraw: bool) -> Vec<AccountMeta> {
+    let (user_x_ata, user_y_ata, user_lp_ata) = pool.user_token_accounts(user);
+    let config = if pool.virtual_orders {
+        AccountMeta::new(pool.config, false)
+    } else {
+        AccountMeta::new_readonly(pool.config, false)
+    };
+
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        config,
+        AccountMeta::new(pool.mint_lp, false),
+        AccountMeta::new(pool.vault_x, false),
+        AccountMeta::new(pool.vault_y, false),
+        AccountMeta::new(user_x_ata, false),
+        AccountMeta::new(user_y_ata, false),
+        AccountMeta::new(user_lp_ata, false),
+        AccountMeta::new_readonly(pool.token_program_lp, false),
+        AccountMeta::new_readonly(pool.token_program_x, false),
+        AccountMeta::new_readonly(pool.token_program_y, false),
+        AccountMeta::new_readonly(pool.mint_x, false),
+        AccountMeta::new_readonly(pool.mint_y, false),
+    ];
+    accounts.extend(pool.trailing_accounts(user, false, is_withdraw));
+    accounts
+}
@@ -0,0 +1,18 @@
+//! Blueshift Native AMM 客户端
+//!
+//! 构建 Initialize / Deposit / Withdraw / Swap 指令：自动派生 Config、LP Mint、
+//! 金库和用户代币账户地址，按程序要求的顺序排列账户并打包指令数据。
+//! 数量计算请使用 `blueshift_amm_math` 中与链上一致的报价函数。
+//! `inspect` 模块解码池子账户并生成状态报告，`amm-inspect` 命令行工具基于它实现；
+//! `sim` 模块用与程序相同的数学离线重放交易流，`amm-sim` 命令行工具基于它实现
+
+pub mod inspect;
+pub mod instructions;
+pub mod pda;
+pub mod sim;
+
+pub use inspect::*;
+pub use instructions::*;
+pub use pda::*;
+pub use solana_address::Address;
+pub use solana_instruction::{AccountMeta, Instruction};
@@ -0,0 +1,70 @@
+//! 地址派生
+
+use blueshift_native_amm::{
+    instructions::token::TOKEN_2022_PROGRAM_ID,
+    AllowListEntry,
+    AmmSettings,
+    LimitOrderBook,
+    RegistryPage,
+    VirtualOrderPool,
+};
+use solana_address::Address;
+
+/// 系统程序 ID
+pub const SYSTEM_PROGRAM_ID: Address = Address::from_str_const("11111111111111111111111111111111");
+/// Token 程序 ID
+pub const TOKEN_PROGRAM_ID: Address = Address::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+/// Token-2022 程序 ID
+pub const TOKEN_2022_ID: Address = TOKEN_2022_PROGRAM_ID;
+/// Associated Token 程序 ID
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Address =
+    Address::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Config PDA：["config", seed (u64 LE), mint_x, mint_y]
+pub fn config_address(program_id: &Address, seed: u64, mint_x: &Address, mint_y: &Address) -> (Address, u8) {
+    Address::find_program_address(
+        &[b"config", &seed.to_le_bytes(), mint_x.as_ref(), mint_y.as_ref()],
+        program_id,
+    )
+}
+
+/// LP Mint PDA：["mint_lp", config]
+pub fn mint_lp_address(program_id: &Address, config: &Address) -> (Address, u8) {
+    Address::find_program_address(&[b"mint_lp", config.as_ref()], program_id)
+}
+
+/// 全局设置 PDA：["settings"]
+pub fn settings_address(program_id: &Address) -> (Address, u8) {
+    Address::find_program_address(&[AmmSettings::SEED], program_id)
+}
+
+/// 注册表页 PDA：["registry", page (u32 LE)]
+pub fn registry_page_address(program_id: &Address, page: u32) -> (Address, u8) {
+    Address::find_program_address(&[RegistryPage::SEED, &page.to_le_bytes()], program_id)
+}
+
+/// 长期订单池 PDA：["twamm", config]
+pub fn order_pool_address(program_id: &Address, config: &Address) -> (Address, u8) {
+    Address::find_program_address(&[VirtualOrderPool::SEED, config.as_ref()], program_id)
+}
+
+/// 限价单簿 PDA：["orders", config]
+pub fn order_book_address(program_id: &Address, config: &Address) -> (Address, u8) {
+    Address::find_program_address(&[LimitOrderBook::SEED, config.as_ref()], program_id)
+}
+
+/// 白名单条目 PDA：["allow", config, wallet]
+pub fn allow_entry_address(program_id: &Address, config: &Address, wallet: &Address) -> (Address, u8) {
+    Address::find_program_address(&[AllowListEntry::SEED, config.as_ref(), wallet.as_ref()], program_id)
+}
+
+/// 关联代币账户地址：[owner, token_program, mint]
+///
+/// 池子的金库即 config 的关联代币账户
+pub fn associated_token_address(owner: &Address, mint: &Address, token_program: &Address) -> Address {
+    Address::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
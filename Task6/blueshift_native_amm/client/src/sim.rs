@@ -0,0 +1,347 @@
+//! 离线池子模拟
+//!
+//! 不需要验证节点，用程序的 `Config`（动态费用、LBP 权重、价格影响熔断）和
+//! `blueshift_amm_math` 的报价函数按 Swap 处理函数的顺序重放交易流，
+//! 逐步输出储备、累计费用、LP 相对持有的盈亏和无常损失
+
+use core::fmt;
+use blueshift_native_amm::{
+    instructions::initialize::WeightSchedule,
+    math::{price_change_bps, quote_swap, BPS_DENOMINATOR},
+    Config,
+};
+use serde_json::Value;
+use solana_address::Address;
+
+/// 模拟失败的原因
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimError {
+    EmptyReserve,               // 初始储备为 0
+    InvalidFee,                 // 费率超过 10000 基点
+    InvalidWeightSchedule,      // LBP 权重计划无效
+    InvalidDynamicFee,          // 动态费用参数无效
+    InvalidTrade(String),       // 交易流中无法解析的记录
+}
+
+impl fmt::Display for SimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyReserve => f.write_str("initial reserves must be non-zero"),
+            Self::InvalidFee => f.write_str("fee exceeds 10000 bps"),
+            Self::InvalidWeightSchedule => f.write_str("invalid weight schedule"),
+            Self::InvalidDynamicFee => f.write_str("invalid dynamic fee parameters"),
+            Self::InvalidTrade(record) => write!(f, "invalid trade: {record}"),
+        }
+    }
+}
+
+impl std::error::Error for SimError {}
+
+/// 动态费用参数，含义与 UpdateConfig 相同
+#[derive(Clone, Copy, Debug)]
+pub struct DynamicFee {
+    pub min_fee: u16,           // 费用下限（基点）
+    pub max_fee: u16,           // 费用上限（基点）
+    pub volatility_factor: u16, // 波动率计入费用的比例（基点）
+    pub decay_period: u64,      // 波动率半衰期（秒）
+}
+
+/// 池子参数
+#[derive(Clone, Copy, Debug)]
+pub struct SimParams {
+    pub reserve_x: u64,
+    pub reserve_y: u64,
+    pub fee: u16,                               // 基础费率（基点）
+    pub dynamic_fee: Option<DynamicFee>,
+    pub weight_schedule: Option<WeightSchedule>, // LBP 权重计划，None 为普通恒定乘积池子
+    pub max_price_impact: u16,                  // 单笔交换最大价格影响（基点，0 表示不限制）
+    pub max_slot_price_move: u16,               // 单个 slot 内累计价格变化上限（基点，0 表示不限制）
+}
+
+/// 交易流中的一笔交换
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Trade {
+    pub timestamp: i64,     // unix 时间戳，决定动态费用衰减和 LBP 权重
+    pub slot: Option<u64>,  // 所在 slot，未给出时每笔交易单独一个 slot
+    pub is_x: bool,         // true = 输入 X 换 Y
+    pub amount: u64,        // 输入数量
+}
+
+impl Trade {
+    /// 解析 CSV 交易流
+    ///
+    /// 第一行为表头，必须包含 timestamp、side、amount 列，可选 slot 列；
+    /// side 为输入代币（x 或 y）
+    pub fn parse_csv(text: &str) -> Result<Vec<Trade>, SimError> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let header: Vec<&str> = lines
+            .next()
+            .ok_or_else(|| SimError::InvalidTrade("missing header".into()))?
+            .split(',')
+            .map(str::trim)
+            .collect();
+        let column = |name: &str| header.iter().position(|column| *column == name);
+        let missing = |name: &str| SimError::InvalidTrade(format!("missing column {name}"));
+        let timestamp = column("timestamp").ok_or_else(|| missing("timestamp"))?;
+        let side = column("side").ok_or_else(|| missing("side"))?;
+        let amount = column("amount").ok_or_else(|| missing("amount"))?;
+        let slot = column("slot");
+
+        lines
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+                let field = |index: usize| fields.get(index).copied().unwrap_or_default();
+                let invalid = || SimError::InvalidTrade(line.into());
+
+                Ok(Trade {
+                    timestamp: field(timestamp).parse().map_err(|_| invalid())?,
+                    slot: match slot.map(field) {
+                        None | Some("") => None,
+                        Some(slot) => Some(slot.parse().map_err(|_| invalid())?),
+                    },
+                    is_x: parse_side(field(side)).ok_or_else(invalid)?,
+                    amount: field(amount).parse().map_err(|_| invalid())?,
+                })
+            })
+            .collect()
+    }
+
+    /// 解析 JSON 交易流：对象数组，字段与 CSV 列相同
+    pub fn parse_json(text: &str) -> Result<Vec<Trade>, SimError> {
+        let json: Value = serde_json::from_str(text).map_err(|error| SimError::InvalidTrade(error.to_string()))?;
+        let records = json
+            .as_array()
+            .ok_or_else(|| SimError::InvalidTrade("expected an array of trades".into()))?;
+
+        records
+            .iter()
+            .map(|record| {
+                let invalid = || SimError::InvalidTrade(record.to_string());
+                let number = |name: &str| {
+                    let value = record.get(name)?;
+                    value.as_u64().or_else(|| value.as_str()?.parse().ok())
+                };
+
+                Ok(Trade {
+                    timestamp: record.get("timestamp").and_then(Value::as_i64).ok_or_else(invalid)?,
+                    slot: number("slot"),
+                    is_x: record
+                        .get("side")
+                        .and_then(Value::as_str)
+                        .and_then(parse_side)
+                        .ok_or_else(invalid)?,
+                    amount: number("amount").ok_or_else(invalid)?,
+                })
+            })
+            .collect()
+    }
+}
+
+fn parse_side(side: &str) -> Option<bool> {
+    match side.to_ascii_lowercase().as_str() {
+        "x" => Some(true),
+        "y" => Some(false),
+        _ => None,
+    }
+}
+
+/// 每一步的结果
+///
+/// 价值均以 Y 的最小单位计，按该步结束时池子的加权现货价格估值
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepReport {
+    pub step: usize,
+    pub trade: Trade,
+    pub rejected: Option<&'static str>, // 程序会拒绝该交换时的原因，储备不变
+    pub fee_bps: u16,                   // 该步生效的费率
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,                       // 从输入中扣除的费用（输入代币）
+    pub reserve_x: u64,
+    pub reserve_y: u64,
+    pub weight_x: u64,
+    pub spot_price: f64,                // 每单位 X 对应的 Y
+    pub fees_x: u64,                    // 累计费用
+    pub fees_y: u64,
+    pub lp_value: f64,                  // 池子全部储备的价值
+    pub hold_value: f64,                // 持有初始储备不入池的价值
+    pub pnl_vs_hold: f64,               // lp_value - hold_value
+    pub impermanent_loss: f64,          // 不计费用的无常损失（比例，负数表示损失）
+}
+
+impl StepReport {
+    /// CSV 表头，与 `to_csv` 的列一一对应
+    pub const CSV_HEADER: &'static str = "step,timestamp,side,amount_in,amount_out,fee,fee_bps,status,\
+        reserve_x,reserve_y,weight_x,spot_price,fees_x,fees_y,lp_value,hold_value,pnl_vs_hold,impermanent_loss";
+
+    /// 输出一行 CSV
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.step,
+            self.trade.timestamp,
+            if self.trade.is_x { "x" } else { "y" },
+            self.amount_in,
+            self.amount_out,
+            self.fee,
+            self.fee_bps,
+            self.rejected.unwrap_or("ok"),
+            self.reserve_x,
+            self.reserve_y,
+            self.weight_x,
+            self.spot_price,
+            self.fees_x,
+            self.fees_y,
+            self.lp_value,
+            self.hold_value,
+            self.pnl_vs_hold,
+            self.impermanent_loss,
+        )
+    }
+}
+
+/// 池子模拟器
+///
+/// 模拟器持有一个内存中的 Config，单个 LP 拥有池子的全部储备
+pub struct Simulator {
+    config: [u8; Config::ACCOUNT_LEN],
+    reserve_x: u64,
+    reserve_y: u64,
+    initial_x: u64,
+    initial_y: u64,
+    fees_x: u64,
+    fees_y: u64,
+    steps: usize,
+}
+
+impl Simulator {
+    /// 按参数创建池子
+    pub fn new(params: &SimParams) -> Result<Self, SimError> {
+        if params.reserve_x == 0 || params.reserve_y == 0 {
+            return Err(SimError::EmptyReserve);
+        }
+        if params.fee as u64 > BPS_DENOMINATOR {
+            return Err(SimError::InvalidFee);
+        }
+
+        let mut config = [0u8; Config::ACCOUNT_LEN];
+        let state = Config::init(&mut config).unwrap();
+        state.set_inner(
+            params.fee as u64,
+            &Address::default(),
+            &Address::default(),
+            &Address::default(),
+            params.fee,
+            0,
+        );
+        if let Some(schedule) = &params.weight_schedule {
+            state
+                .set_weight_schedule(schedule.start_weight_x, schedule.end_weight_x, schedule.start_at, schedule.end_at)
+                .map_err(|_| SimError::InvalidWeightSchedule)?;
+        }
+        if let Some(dynamic) = &params.dynamic_fee {
+            state
+                .set_dynamic_fee(true, dynamic.min_fee, dynamic.max_fee, dynamic.volatility_factor, dynamic.decay_period)
+                .map_err(|_| SimError::InvalidDynamicFee)?;
+        }
+        state.set_price_limits(params.max_price_impact, params.max_slot_price_move);
+
+        Ok(Self {
+            config,
+            reserve_x: params.reserve_x,
+            reserve_y: params.reserve_y,
+            initial_x: params.reserve_x,
+            initial_y: params.reserve_y,
+            fees_x: 0,
+            fees_y: 0,
+            steps: 0,
+        })
+    }
+
+    /// 按 Swap 处理函数的顺序执行一笔交换并生成报告
+    ///
+    /// 报价失败或触发价格影响熔断的交换被拒绝，储备不变
+    pub fn step(&mut self, trade: &Trade) -> StepReport {
+        let step = self.steps;
+        self.steps += 1;
+        let now = trade.timestamp;
+        let slot = trade.slot.unwrap_or(step as u64);
+
+        let config = Config::load_mut(&mut self.config).unwrap();
+        let fee_bps = config.effective_fee(now);
+        let (weight_x, weight_y) = config.weights(now);
+        let (reserve_in, reserve_out, weight_in, weight_out) = if trade.is_x {
+            (self.reserve_x, self.reserve_y, weight_x, weight_y)
+        } else {
+            (self.reserve_y, self.reserve_x, weight_y, weight_x)
+        };
+
+        let mut rejected = None;
+        let mut quote = None;
+        match quote_swap(reserve_in, reserve_out, weight_in, weight_out, trade.amount, fee_bps) {
+            Ok(swap) if swap.amount_out == 0 => rejected = Some("ZeroOutput"),
+            Ok(swap) => {
+                let (new_x, new_y) = if trade.is_x {
+                    (self.reserve_x + trade.amount, self.reserve_y - swap.amount_out)
+                } else {
+                    (self.reserve_x - swap.amount_out, self.reserve_y + trade.amount)
+                };
+                let price_change = price_change_bps(self.reserve_x, self.reserve_y, new_x, new_y);
+                if config.check_price_impact(slot, price_change).is_err() {
+                    rejected = Some("PriceImpactExceeded");
+                } else {
+                    config.record_price_move(now, price_change);
+                    self.reserve_x = new_x;
+                    self.reserve_y = new_y;
+                    if trade.is_x {
+                        self.fees_x += swap.fee;
+                    } else {
+                        self.fees_y += swap.fee;
+                    }
+                    quote = Some(swap);
+                }
+            }
+            Err(error) => rejected = Some(error.name()),
+        }
+
+        // 加权现货价格：(y / wy) / (x / wx)
+        let (x, y) = (self.reserve_x as f64, self.reserve_y as f64);
+        let (wx, wy) = (weight_x as f64 / BPS_DENOMINATOR as f64, weight_y as f64 / BPS_DENOMINATOR as f64);
+        let spot_price = (y * wx) / (x * wy);
+
+        let lp_value = x * spot_price + y;
+        let hold_value = self.initial_x as f64 * spot_price + self.initial_y as f64;
+
+        // 不计费用的池子保持 x^wx * y^wy 不变，按当前价格重新平衡后的价值
+        let invariant = (self.initial_x as f64).powf(wx) * (self.initial_y as f64).powf(wy);
+        let ratio = spot_price * wy / wx; // 平衡时 y / x
+        let balanced_x = invariant / ratio.powf(wy);
+        let impermanent_loss = (balanced_x * spot_price + balanced_x * ratio) / hold_value - 1.0;
+
+        StepReport {
+            step,
+            trade: *trade,
+            rejected,
+            fee_bps,
+            amount_in: quote.map_or(0, |_| trade.amount),
+            amount_out: quote.map_or(0, |swap| swap.amount_out),
+            fee: quote.map_or(0, |swap| swap.fee),
+            reserve_x: self.reserve_x,
+            reserve_y: self.reserve_y,
+            weight_x,
+            spot_price,
+            fees_x: self.fees_x,
+            fees_y: self.fees_y,
+            lp_value,
+            hold_value,
+            pnl_vs_hold: lp_value - hold_value,
+            impermanent_loss,
+        }
+    }
+}
+
+/// 依次执行全部交易
+pub fn simulate(params: &SimParams, trades: &[Trade]) -> Result<Vec<StepReport>, SimError> {
+    let mut simulator = Simulator::new(params)?;
+    Ok(trades.iter().map(|trade| simulator.step(trade)).collect())
+}
@@ -0,0 +1,151 @@
+//! 池子检查测试
+//!
+//! 用程序的 Config 布局构造账户数据，检查解码结果、储备（扣除订单部分）、
+//! 现货价格、LP 供应量和错误类型
+
+use blueshift_amm_client::{decode_config, inspect_pool, Address, InspectError, PoolState};
+use blueshift_native_amm::{AmmState, Config};
+
+/// 测试使用的 unix 时间戳
+const NOW: i64 = 1_700_000_000;
+
+fn mint_x() -> Address {
+    Address::new_from_array([1; 32])
+}
+
+fn mint_y() -> Address {
+    Address::new_from_array([2; 32])
+}
+
+fn authority() -> Address {
+    Address::new_from_array([7; 32])
+}
+
+/// 当前版本的 Config 账户数据
+fn config_data(authority: &Address, configure: impl FnOnce(&mut Config)) -> Vec<u8> {
+    let mut data = vec![0u8; Config::ACCOUNT_LEN];
+    let config = Config::init(&mut data).unwrap();
+    config.set_inner(30, authority, &mint_x(), &mint_y(), 30, 254);
+    configure(config);
+    data
+}
+
+/// 代币账户数据：mint 在偏移 0，amount 在偏移 64
+fn token_account(mint: &Address, amount: u64) -> Vec<u8> {
+    let mut data = vec![0u8; 165];
+    data[..32].copy_from_slice(mint.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data
+}
+
+/// Mint 账户数据：supply 在偏移 36，decimals 在偏移 44
+fn mint_account(supply: u64, decimals: u8) -> Vec<u8> {
+    let mut data = vec![0u8; 82];
+    data[36..44].copy_from_slice(&supply.to_le_bytes());
+    data[44] = decimals;
+    data
+}
+
+#[test]
+fn decodes_config_fields() {
+    let data = config_data(&authority(), |config| {
+        config.state = AmmState::WithdrawOnly as u8;
+        config.permissioned = 1;
+        config.virtual_orders = 1;
+        config.guardian = [8; 32];
+    });
+
+    let info = decode_config(&data).unwrap();
+    assert_eq!(info.state, PoolState::WithdrawOnly);
+    assert_eq!((info.seed, info.fee), (30, 30));
+    assert_eq!(info.authority, Some(authority()));
+    assert_eq!(info.guardian, Some(Address::new_from_array([8; 32])));
+    assert_eq!((info.mint_x, info.mint_y), (mint_x(), mint_y()));
+    assert!(info.permissioned && info.virtual_orders);
+    assert!(!info.limit_orders && !info.lbp && !info.dynamic_fee);
+    assert_eq!(info.oracle, None);
+
+    // 全零的管理权限表示不可变
+    let info = decode_config(&config_data(&Address::default(), |_| {})).unwrap();
+    assert_eq!(info.authority, None);
+    assert_eq!(info.guardian, None);
+}
+
+#[test]
+fn report_excludes_reserved_order_funds() {
+    let config = config_data(&authority(), |config| config.adjust_reserved(true, 500_000, 0).unwrap());
+    let report = inspect_pool(
+        &config,
+        &token_account(&mint_x(), 10_500_000),
+        &token_account(&mint_y(), 40_000_000),
+        &mint_account(20_000_000, 6),
+        NOW,
+    )
+    .unwrap();
+
+    assert_eq!((report.reserve_x, report.reserve_y), (10_000_000, 40_000_000));
+    assert_eq!(report.config.reserved_x, 500_000);
+    assert_eq!((report.weight_x, report.weight_y), (5_000, 5_000));
+    assert_eq!(report.spot_price, Some(4.0));
+    assert_eq!((report.lp_supply, report.lp_decimals), (20_000_000, 6));
+    assert_eq!(report.effective_fee, 30);
+
+    let text = report.to_string();
+    assert!(text.contains("state:          Initialized"));
+    assert!(text.contains("reserve x:      10000000 (+500000 reserved for orders)"));
+}
+
+#[test]
+fn report_uses_lbp_weights_at_now() {
+    let config = config_data(&authority(), |config| {
+        config.set_weight_schedule(9_000, 5_000, NOW, NOW + 1_000).unwrap();
+    });
+    let vault_x = token_account(&mint_x(), 1_000_000);
+    let vault_y = token_account(&mint_y(), 1_000_000);
+    let mint_lp = mint_account(0, 9);
+
+    // 发售过半时 X 权重为 7000：价格 = (y / wy) / (x / wx)
+    let report = inspect_pool(&config, &vault_x, &vault_y, &mint_lp, NOW + 500).unwrap();
+    assert!(report.config.lbp);
+    assert_eq!((report.weight_x, report.weight_y), (7_000, 3_000));
+    let price = report.spot_price.unwrap();
+    assert!((price - 7.0 / 3.0).abs() < 1e-12, "price {price}");
+
+    // 储备为 0 时没有现货价格
+    let empty = token_account(&mint_y(), 0);
+    assert_eq!(inspect_pool(&config, &vault_x, &empty, &mint_lp, NOW).unwrap().spot_price, None);
+}
+
+#[test]
+fn rejects_invalid_accounts() {
+    let config = config_data(&authority(), |_| {});
+    let vault_x = token_account(&mint_x(), 1);
+    let vault_y = token_account(&mint_y(), 1);
+    let mint_lp = mint_account(1, 6);
+
+    assert_eq!(decode_config(&[0xFF; 16]), Err(InspectError::InvalidConfig));
+    assert_eq!(
+        inspect_pool(&config, &vault_y, &vault_x, &mint_lp, NOW),
+        Err(InspectError::VaultMintMismatch)
+    );
+    assert_eq!(
+        inspect_pool(&config, &vault_x[..100], &vault_y, &mint_lp, NOW),
+        Err(InspectError::InvalidVault)
+    );
+    assert_eq!(
+        inspect_pool(&config, &vault_x, &vault_y, &mint_lp[..40], NOW),
+        Err(InspectError::InvalidMint)
+    );
+}
+
+#[test]
+fn reports_outdated_config() {
+    // 最早的无版本布局：state 开头且比当前 Config 短，需要先执行 MigrateConfig
+    let mut legacy = config_data(&authority(), |_| {})[Config::HEADER_LEN..].to_vec();
+    legacy.truncate(Config::LEGACY_MIN_LEN);
+    assert_eq!(decode_config(&legacy), Err(InspectError::OutdatedConfig));
+
+    // 与当前 Config 等长的无版本布局可以直接读取
+    let unversioned = config_data(&authority(), |_| {})[Config::HEADER_LEN..].to_vec();
+    assert_eq!(decode_config(&unversioned).unwrap().mint_y, mint_y());
+}
@@ -0,0 +1,266 @@
+//! 指令构建测试
+//!
+//! 用程序自身的 `try_from_bytes` 解析客户端打包的指令数据，并按程序文档中的
+//! 账户顺序逐个核对地址、可写和签名标记，保证两边的布局一致
+
+use blueshift_amm_client::{
+    instructions::{self, discriminator, DepositArgs, InitializeArgs, LpMetadata, Pool, SwapArgs, WithdrawArgs},
+    pda::*,
+    AccountMeta, Address, Instruction,
+};
+use blueshift_native_amm::{
+    deposit::DepositInstructionData,
+    initialize::{InitializeInstructionData, WeightSchedule},
+    set_pool_state::SetPoolStateInstructionData,
+    swap::SwapInstructionData,
+    withdraw::WithdrawInstructionData,
+    AmmState, ID,
+};
+
+const FEE: u16 = 30;
+
+/// mint_x 必须小于 mint_y
+fn pool() -> Pool {
+    let mint_x = Address::new_from_array([1; 32]);
+    let mint_y = Address::new_from_array([2; 32]);
+    Pool::new(&ID, FEE as u64, &mint_x, &mint_y, &TOKEN_PROGRAM_ID, &TOKEN_2022_ID)
+}
+
+fn user() -> Address {
+    Address::new_from_array([9; 32])
+}
+
+/// 账户列表的 (地址, 可写, 签名)
+fn metas(instruction: &Instruction) -> Vec<(Address, bool, bool)> {
+    instruction
+        .accounts
+        .iter()
+        .map(|AccountMeta { pubkey, is_writable, is_signer }| (*pubkey, *is_writable, *is_signer))
+        .collect()
+}
+
+#[test]
+fn pool_derives_canonical_addresses() {
+    let pool = pool();
+    let (config, config_bump) = Address::find_program_address(
+        &[b"config", &(FEE as u64).to_le_bytes(), pool.mint_x.as_ref(), pool.mint_y.as_ref()],
+        &ID,
+    );
+    assert_eq!((pool.config, pool.config_bump), (config, config_bump));
+    assert_eq!((pool.mint_lp, pool.lp_bump), Address::find_program_address(&[b"mint_lp", config.as_ref()], &ID));
+
+    // 金库为 config 的关联代币账户，两侧可以使用不同的 Token 程序
+    assert_eq!(pool.vault_x, associated_token_address(&config, &pool.mint_x, &TOKEN_PROGRAM_ID));
+    assert_eq!(pool.vault_y, associated_token_address(&config, &pool.mint_y, &TOKEN_2022_ID));
+}
+
+#[test]
+fn initialize_matches_program_layout() {
+    let pool = pool();
+    let user = user();
+    let authority = Address::new_from_array([7; 32]);
+    let instruction = instructions::initialize(
+        &pool,
+        &user,
+        &InitializeArgs {
+            fee: FEE,
+            authority: Some(authority),
+            registry_page: 3,
+            weight_schedule: Some(WeightSchedule {
+                start_weight_x: 9000,
+                end_weight_x: 5000,
+                start_at: 1_700_000_000,
+                end_at: 1_700_086_400,
+            }),
+            lp_metadata: None,
+        },
+    );
+    assert_eq!(instruction.program_id, ID);
+    assert_eq!(instruction.data[0], discriminator::INITIALIZE);
+    assert_eq!(instruction.data.len(), 1 + 129);
+
+    let (registry_page, registry_bump) = registry_page_address(&ID, 3);
+    let data = InitializeInstructionData::try_from_bytes(&instruction.data[1..]).unwrap();
+    assert_eq!((data.seed, data.fee), (FEE as u64, FEE));
+    assert_eq!((data.mint_x, data.mint_y), (pool.mint_x, pool.mint_y));
+    assert_eq!((data.config_bump, data.lp_bump), (pool.config_bump, pool.lp_bump));
+    assert_eq!(data.authority, authority);
+    assert_eq!(data.registry_bump, Some(registry_bump));
+    let schedule = data.weight_schedule.unwrap();
+    assert_eq!((schedule.start_weight_x, schedule.end_weight_x), (9000, 5000));
+    assert_eq!((schedule.start_at, schedule.end_at), (1_700_000_000, 1_700_086_400));
+    assert!(data.lp_metadata.is_none());
+
+    assert_eq!(
+        metas(&instruction),
+        vec![
+            (user, true, true),
+            (pool.config, true, false),
+            (pool.mint_lp, true, false),
+            (SYSTEM_PROGRAM_ID, false, false),
+            (TOKEN_PROGRAM_ID, false, false),
+            (settings_address(&ID).0, true, false),
+            (registry_page, true, false),
+        ]
+    );
+}
+
+#[test]
+fn initialize_with_metadata_uses_token_2022() {
+    let pool = pool();
+    let instruction = instructions::initialize(
+        &pool,
+        &user(),
+        &InitializeArgs {
+            fee: FEE,
+            authority: None,
+            registry_page: 0,
+            weight_schedule: None,
+            lp_metadata: Some(LpMetadata::for_pair("SOL", "USDC", "https://example.com/lp.json")),
+        },
+    );
+
+    // 权重计划位置全零，其后为带长度前缀的元数据
+    let data = InitializeInstructionData::try_from_bytes(&instruction.data[1..]).unwrap();
+    assert_eq!(data.authority, Address::default());
+    assert!(data.weight_schedule.is_none());
+    let metadata = data.lp_metadata.unwrap();
+    assert_eq!(metadata.name, b"SOL-USDC LP");
+    assert_eq!(metadata.symbol, b"SOL-USDC");
+    assert_eq!(metadata.uri, b"https://example.com/lp.json");
+    assert_eq!(instruction.accounts[4].pubkey, TOKEN_2022_ID);
+}
+
+/// Deposit / Withdraw 的 13 个固定账户
+fn liquidity_metas(pool: &Pool, user: &Address, config_writable: bool) -> Vec<(Address, bool, bool)> {
+    let (user_x, user_y, user_lp) = pool.user_token_accounts(user);
+    vec![
+        (*user, true, true),
+        (pool.config, config_writable, false),
+        (pool.mint_lp, true, false),
+        (pool.vault_x, true, false),
+        (pool.vault_y, true, false),
+        (user_x, true, false),
+        (user_y, true, false),
+        (user_lp, true, false),
+        (pool.token_program_lp, false, false),
+        (pool.token_program_x, false, false),
+        (pool.token_program_y, false, false),
+        (pool.mint_x, false, false),
+        (pool.mint_y, false, false),
+    ]
+}
+
+#[test]
+fn deposit_and_withdraw_match_program_layout() {
+    let pool = pool();
+    let user = user();
+
+    let deposit = instructions::deposit(
+        &pool,
+        &user,
+        &DepositArgs { amount: 1_000, max_x: 2_000, max_y: 3_000, expiration: 1_700_000_000 },
+    );
+    assert_eq!(deposit.data[0], discriminator::DEPOSIT);
+    let data = DepositInstructionData::try_from_bytes(&deposit.data[1..]).unwrap();
+    assert_eq!((data.amount, data.max_x, data.max_y, data.expiration), (1_000, 2_000, 3_000, 1_700_000_000));
+    assert_eq!(metas(&deposit), liquidity_metas(&pool, &user, false));
+
+    let withdraw = instructions::withdraw(
+        &pool,
+        &user,
+        &WithdrawArgs { amount: 500, min_x: 1, min_y: 2, expiration: -1 },
+    );
+    assert_eq!(withdraw.data[0], discriminator::WITHDRAW);
+    let data = WithdrawInstructionData::try_from_bytes(&withdraw.data[1..]).unwrap();
+    assert_eq!((data.amount, data.min_x, data.min_y, data.expiration), (500, 1, 2, -1));
+    assert_eq!(metas(&withdraw), liquidity_metas(&pool, &user, false));
+}
+
+#[test]
+fn liquidity_trailing_accounts_follow_pool_features() {
+    let pool = pool().with_virtual_orders(true).with_limit_orders(true).with_permissioned(true);
+    let user = user();
+    let order_pool = order_pool_address(&ID, &pool.config).0;
+    let allow_entry = allow_entry_address(&ID, &pool.config, &user).0;
+    let args = DepositArgs { amount: 1, max_x: 1, max_y: 1, expiration: 0 };
+
+    // 长期订单池在前、白名单条目在后；限价单簿只属于 Swap，Withdraw 不需要白名单条目
+    let mut expected = liquidity_metas(&pool, &user, true);
+    expected.push((order_pool, true, false));
+    expected.push((allow_entry, false, false));
+    assert_eq!(metas(&instructions::deposit(&pool, &user, &args)), expected);
+
+    let args = WithdrawArgs { amount: 1, min_x: 0, min_y: 0, expiration: 0 };
+    let mut expected = liquidity_metas(&pool, &user, true);
+    expected.push((order_pool, true, false));
+    assert_eq!(metas(&instructions::withdraw(&pool, &user, &args)), expected);
+}
+
+#[test]
+fn swap_matches_program_layout() {
+    let oracle = Address::new_from_array([5; 32]);
+    let pool = pool();
+    let user = user();
+    let (user_x, user_y, _) = pool.user_token_accounts(&user);
+    let args = SwapArgs { is_x: false, amount: 25_000, min: 99, expiration: 1_700_000_000 };
+
+    let instruction = instructions::swap(&pool, &user, &args);
+    assert_eq!(instruction.data[0], discriminator::SWAP);
+    assert_eq!(instruction.data.len(), 1 + 25);
+    let data = SwapInstructionData::try_from_bytes(&instruction.data[1..]).unwrap();
+    assert_eq!((data.is_x, data.amount, data.min, data.expiration), (false, 25_000, 99, 1_700_000_000));
+
+    let fixed = vec![
+        (user, true, true),
+        (pool.config, true, false),
+        (pool.vault_x, true, false),
+        (pool.vault_y, true, false),
+        (user_x, true, false),
+        (user_y, true, false),
+        (TOKEN_PROGRAM_ID, false, false),
+        (TOKEN_2022_ID, false, false),
+        (pool.mint_x, false, false),
+        (pool.mint_y, false, false),
+    ];
+    assert_eq!(metas(&instruction), fixed);
+
+    // 附加账户顺序：长期订单池、限价单簿、参考价格源、白名单条目
+    let pool = pool
+        .with_virtual_orders(true)
+        .with_limit_orders(true)
+        .with_oracle(Some(oracle))
+        .with_permissioned(true);
+    let mut expected = fixed;
+    expected.extend([
+        (order_pool_address(&ID, &pool.config).0, true, false),
+        (order_book_address(&ID, &pool.config).0, true, false),
+        (oracle, false, false),
+        (allow_entry_address(&ID, &pool.config, &user).0, false, false),
+    ]);
+    assert_eq!(metas(&instructions::swap(&pool, &user, &args)), expected);
+}
+
+#[test]
+fn set_pool_state_matches_program_layout() {
+    let pool = pool();
+    let guardian = Address::new_from_array([6; 32]);
+
+    let instruction = instructions::set_pool_state(&pool, &guardian, AmmState::WithdrawOnly);
+    assert_eq!(instruction.data[0], discriminator::SET_POOL_STATE);
+    let data = SetPoolStateInstructionData::try_from_bytes(&instruction.data[1..]).unwrap();
+    assert_eq!(data.state, AmmState::WithdrawOnly as u8);
+    assert_eq!(metas(&instruction), vec![(guardian, false, true), (pool.config, true, false)]);
+
+    // 已创建长期订单池的池子追加订单池和两个金库，切换前先结算
+    let pool = pool.with_virtual_orders(true);
+    let instruction = instructions::set_pool_state(&pool, &guardian, AmmState::Initialized);
+    assert_eq!(
+        metas(&instruction)[2..],
+        [
+            (order_pool_address(&ID, &pool.config).0, true, false),
+            (pool.vault_x, false, false),
+            (pool.vault_y, false, false),
+        ]
+    );
+}
@@ -0,0 +1,168 @@
+//! 离线模拟测试
+//!
+//! 在固定交易流上逐笔用 `blueshift_amm_math::quote_swap` 手动重放，
+//! 检查模拟器每一步的输出、费用和储备与报价库一致，以及交易流解析
+
+use blueshift_amm_client::sim::{simulate, SimError, SimParams, Trade};
+use blueshift_native_amm::{
+    instructions::initialize::WeightSchedule,
+    math::{quote_swap, BPS_DENOMINATOR},
+};
+
+/// 测试开始时的 unix 时间戳
+const NOW: i64 = 1_700_000_000;
+
+fn params() -> SimParams {
+    SimParams {
+        reserve_x: 1_000_000_000,
+        reserve_y: 4_000_000_000,
+        fee: 30,
+        dynamic_fee: None,
+        weight_schedule: None,
+        max_price_impact: 0,
+        max_slot_price_move: 0,
+    }
+}
+
+fn trade(offset: i64, is_x: bool, amount: u64) -> Trade {
+    Trade { timestamp: NOW + offset, slot: None, is_x, amount }
+}
+
+/// 固定交易流：两侧交替、大小不一
+fn trades() -> Vec<Trade> {
+    vec![
+        trade(0, true, 1_000_000),
+        trade(12, false, 25_000_000),
+        trade(30, true, 123_456_789),
+        trade(31, false, 7),
+        trade(60, false, 400_000_000),
+        trade(90, true, 50_000_000),
+    ]
+}
+
+#[test]
+fn replay_matches_math_quotes() {
+    let params = params();
+    let trades = trades();
+    let reports = simulate(&params, &trades).unwrap();
+    assert_eq!(reports.len(), trades.len());
+
+    let half = BPS_DENOMINATOR / 2;
+    let (mut reserve_x, mut reserve_y) = (params.reserve_x, params.reserve_y);
+    let (mut fees_x, mut fees_y) = (0, 0);
+    for (trade, report) in trades.iter().zip(&reports) {
+        let (reserve_in, reserve_out) = if trade.is_x { (reserve_x, reserve_y) } else { (reserve_y, reserve_x) };
+        let quote = quote_swap(reserve_in, reserve_out, half, half, trade.amount, params.fee).unwrap();
+        if trade.is_x {
+            reserve_x += trade.amount;
+            reserve_y -= quote.amount_out;
+            fees_x += quote.fee;
+        } else {
+            reserve_y += trade.amount;
+            reserve_x -= quote.amount_out;
+            fees_y += quote.fee;
+        }
+
+        assert_eq!(report.rejected, None, "step {}", report.step);
+        assert_eq!(report.fee_bps, params.fee);
+        assert_eq!((report.amount_in, report.amount_out, report.fee), (trade.amount, quote.amount_out, quote.fee));
+        assert_eq!((report.reserve_x, report.reserve_y), (reserve_x, reserve_y));
+        assert_eq!((report.fees_x, report.fees_y), (fees_x, fees_y));
+        assert_eq!(report.spot_price, reserve_y as f64 / reserve_x as f64);
+    }
+
+    // 费用留在池子中：按最终价格估值时 LP 相对持有的盈亏高于无常损失
+    let last = reports.last().unwrap();
+    assert!(last.impermanent_loss < 0.0);
+    assert!(last.pnl_vs_hold / last.hold_value > last.impermanent_loss);
+}
+
+#[test]
+fn lbp_replay_uses_weights_at_trade_time() {
+    let params = SimParams {
+        weight_schedule: Some(WeightSchedule {
+            start_weight_x: 9_000,
+            end_weight_x: 5_000,
+            start_at: NOW,
+            end_at: NOW + 1_000,
+        }),
+        ..params()
+    };
+    let trades = [trade(0, false, 10_000_000), trade(500, false, 10_000_000), trade(2_000, true, 1_000_000)];
+    let reports = simulate(&params, &trades).unwrap();
+
+    let (mut reserve_x, mut reserve_y) = (params.reserve_x, params.reserve_y);
+    for ((trade, report), weight_x) in trades.iter().zip(&reports).zip([9_000, 7_000, 5_000]) {
+        let weight_y = BPS_DENOMINATOR - weight_x;
+        let quote = if trade.is_x {
+            quote_swap(reserve_x, reserve_y, weight_x, weight_y, trade.amount, params.fee).unwrap()
+        } else {
+            quote_swap(reserve_y, reserve_x, weight_y, weight_x, trade.amount, params.fee).unwrap()
+        };
+        if trade.is_x {
+            reserve_x += trade.amount;
+            reserve_y -= quote.amount_out;
+        } else {
+            reserve_y += trade.amount;
+            reserve_x -= quote.amount_out;
+        }
+
+        assert_eq!(report.weight_x, weight_x);
+        assert_eq!(report.amount_out, quote.amount_out);
+        assert_eq!((report.reserve_x, report.reserve_y), (reserve_x, reserve_y));
+    }
+}
+
+#[test]
+fn rejected_trades_leave_reserves_unchanged() {
+    let params = SimParams { max_price_impact: 100, ..params() };
+    let trades = [trade(0, true, 1_000_000), trade(1, true, 100_000_000), trade(2, true, 0)];
+    let reports = simulate(&params, &trades).unwrap();
+
+    assert_eq!(reports[0].rejected, None);
+    // 约 20% 的价格变化超过 1% 的单笔上限
+    assert_eq!(reports[1].rejected, Some("PriceImpactExceeded"));
+    assert!(reports[2].rejected.is_some());
+    for report in &reports[1..] {
+        assert_eq!((report.amount_in, report.amount_out, report.fee), (0, 0, 0));
+        assert_eq!((report.reserve_x, report.reserve_y), (reports[0].reserve_x, reports[0].reserve_y));
+        assert_eq!(report.fees_x, reports[0].fees_x);
+    }
+}
+
+#[test]
+fn rejects_invalid_params() {
+    let trades = trades();
+    assert_eq!(simulate(&SimParams { reserve_y: 0, ..params() }, &trades).unwrap_err(), SimError::EmptyReserve);
+    assert_eq!(simulate(&SimParams { fee: 10_001, ..params() }, &trades).unwrap_err(), SimError::InvalidFee);
+    let schedule = WeightSchedule { start_weight_x: 9_950, end_weight_x: 5_000, start_at: NOW, end_at: NOW + 1 };
+    assert_eq!(
+        simulate(&SimParams { weight_schedule: Some(schedule), ..params() }, &trades).unwrap_err(),
+        SimError::InvalidWeightSchedule
+    );
+}
+
+#[test]
+fn parses_csv_and_json_streams() {
+    let csv = "timestamp, side, amount, slot\n\
+        1700000000, x, 1000000, 5\n\
+        \n\
+        1700000012, Y, 25000000,\n";
+    let expected = vec![
+        Trade { timestamp: NOW, slot: Some(5), is_x: true, amount: 1_000_000 },
+        Trade { timestamp: NOW + 12, slot: None, is_x: false, amount: 25_000_000 },
+    ];
+    assert_eq!(Trade::parse_csv(csv).unwrap(), expected);
+
+    // 数量可以写成字符串，避免大整数在 JSON 中丢失精度
+    let json = r#"[
+        {"timestamp": 1700000000, "side": "x", "amount": 1000000, "slot": 5},
+        {"timestamp": 1700000012, "side": "y", "amount": "25000000"}
+    ]"#;
+    assert_eq!(Trade::parse_json(json).unwrap(), expected);
+
+    assert!(matches!(Trade::parse_csv("timestamp,amount\n1,2"), Err(SimError::InvalidTrade(_))));
+    assert!(matches!(Trade::parse_csv("timestamp,side,amount\n1,z,2"), Err(SimError::InvalidTrade(_))));
+    assert!(matches!(Trade::parse_json(r#"{"timestamp": 1}"#), Err(SimError::InvalidTrade(_))));
+    assert!(matches!(Trade::parse_json(r#"[{"side": "x", "amount": 1}]"#), Err(SimError::InvalidTrade(_))));
+}
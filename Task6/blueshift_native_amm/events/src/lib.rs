@@ -0,0 +1,164 @@
+//! Blueshift AMM 事件
+//!
+//! 程序在 Swap / Deposit / Withdraw 结束时通过 `sol_log_data` 输出紧凑的二进制事件，
+//! 本 crate 同时定义编码和解码，程序与索引器共用同一份布局。
+//! 关闭默认的 `std` feature 即为 `no_std`，只保留编码/解码；
+//! `std` feature 额外提供从交易日志中解析事件的 `parse_logs`
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+mod logs;
+
+#[cfg(feature = "std")]
+pub use logs::*;
+
+/// 事件类型标记，位于事件数据的第一个字节
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    Swap = 0u8,         // 交换
+    Deposit = 1u8,      // 存入流动性
+    Withdraw = 2u8,     // 提取流动性
+}
+
+/// 交换事件
+///
+/// 布局（小端）：kind (1) | pool (32) | user (32) | is_x (1) | amount_in (8) |
+/// amount_out (8) | fee (8) | reserve_x (8) | reserve_y (8)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapEvent {
+    pub pool: [u8; 32],     // 池子 Config 地址
+    pub user: [u8; 32],     // 交换用户
+    pub is_x: bool,         // true = X 换 Y，false = Y 换 X
+    pub amount_in: u64,     // 金库实际收到的输入数量
+    pub amount_out: u64,    // 金库转出的输出数量
+    pub fee: u64,           // 从输入中扣除的费用
+    pub reserve_x: u64,     // 交换后的 X 储备（不含属于订单的部分）
+    pub reserve_y: u64,     // 交换后的 Y 储备（不含属于订单的部分）
+}
+
+/// 流动性事件（Deposit / Withdraw 共用）
+///
+/// 布局（小端）：kind (1) | pool (32) | user (32) | lp_amount (8) |
+/// amount_x (8) | amount_y (8) | reserve_x (8) | reserve_y (8)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiquidityEvent {
+    pub pool: [u8; 32],     // 池子 Config 地址
+    pub user: [u8; 32],     // 存入/提取用户
+    pub lp_amount: u64,     // 铸造/销毁的 LP 数量
+    pub amount_x: u64,      // 存入/取回的 X 数量
+    pub amount_y: u64,      // 存入/取回的 Y 数量
+    pub reserve_x: u64,     // 操作后的 X 储备（不含属于订单的部分）
+    pub reserve_y: u64,     // 操作后的 Y 储备（不含属于订单的部分）
+}
+
+/// AMM 事件
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmEvent {
+    Swap(SwapEvent),
+    Deposit(LiquidityEvent),
+    Withdraw(LiquidityEvent),
+}
+
+impl SwapEvent {
+    /// 编码后的长度（字节）
+    pub const LEN: usize = 1 + 32 + 32 + 1 + 8 * 5;
+
+    /// 编码为事件数据
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+        data[0] = EventKind::Swap as u8;
+        data[1..33].copy_from_slice(&self.pool);
+        data[33..65].copy_from_slice(&self.user);
+        data[65] = self.is_x as u8;
+        data[66..74].copy_from_slice(&self.amount_in.to_le_bytes());
+        data[74..82].copy_from_slice(&self.amount_out.to_le_bytes());
+        data[82..90].copy_from_slice(&self.fee.to_le_bytes());
+        data[90..98].copy_from_slice(&self.reserve_x.to_le_bytes());
+        data[98..106].copy_from_slice(&self.reserve_y.to_le_bytes());
+        data
+    }
+
+    /// 从事件数据解析（不含类型标记检查）
+    fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN {
+            return None;
+        }
+
+        Some(Self {
+            pool: data[1..33].try_into().unwrap(),
+            user: data[33..65].try_into().unwrap(),
+            is_x: match data[65] {
+                0 => false,
+                1 => true,
+                _ => return None,
+            },
+            amount_in: read_u64(data, 66),
+            amount_out: read_u64(data, 74),
+            fee: read_u64(data, 82),
+            reserve_x: read_u64(data, 90),
+            reserve_y: read_u64(data, 98),
+        })
+    }
+}
+
+impl LiquidityEvent {
+    /// 编码后的长度（字节）
+    pub const LEN: usize = 1 + 32 + 32 + 8 * 5;
+
+    /// 编码为事件数据，kind 为 Deposit 或 Withdraw
+    pub fn to_bytes(&self, kind: EventKind) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+        data[0] = kind as u8;
+        data[1..33].copy_from_slice(&self.pool);
+        data[33..65].copy_from_slice(&self.user);
+        data[65..73].copy_from_slice(&self.lp_amount.to_le_bytes());
+        data[73..81].copy_from_slice(&self.amount_x.to_le_bytes());
+        data[81..89].copy_from_slice(&self.amount_y.to_le_bytes());
+        data[89..97].copy_from_slice(&self.reserve_x.to_le_bytes());
+        data[97..105].copy_from_slice(&self.reserve_y.to_le_bytes());
+        data
+    }
+
+    /// 从事件数据解析（不含类型标记检查）
+    fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN {
+            return None;
+        }
+
+        Some(Self {
+            pool: data[1..33].try_into().unwrap(),
+            user: data[33..65].try_into().unwrap(),
+            lp_amount: read_u64(data, 65),
+            amount_x: read_u64(data, 73),
+            amount_y: read_u64(data, 81),
+            reserve_x: read_u64(data, 89),
+            reserve_y: read_u64(data, 97),
+        })
+    }
+}
+
+impl AmmEvent {
+    /// 从事件数据解析，类型标记或长度不符时返回 None
+    pub fn try_from_bytes(data: &[u8]) -> Option<Self> {
+        match *data.first()? {
+            kind if kind == EventKind::Swap as u8 => SwapEvent::from_bytes(data).map(Self::Swap),
+            kind if kind == EventKind::Deposit as u8 => LiquidityEvent::from_bytes(data).map(Self::Deposit),
+            kind if kind == EventKind::Withdraw as u8 => LiquidityEvent::from_bytes(data).map(Self::Withdraw),
+            _ => None,
+        }
+    }
+
+    /// 事件所属的池子
+    pub fn pool(&self) -> &[u8; 32] {
+        match self {
+            Self::Swap(event) => &event.pool,
+            Self::Deposit(event) | Self::Withdraw(event) => &event.pool,
+        }
+    }
+}
+
+#[inline(always)]
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
@@ -0,0 +1,47 @@
+//! 从交易日志中解析事件
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crate::AmmEvent;
+
+/// `sol_log_data` 输出的日志前缀
+const DATA_PREFIX: &str = "Program data: ";
+
+/// 从交易的日志消息中解析 AMM 事件
+///
+/// 按 `Program <id> invoke` / `Program <id> success|failed` 跟踪调用栈，
+/// 只解析 program_id（base58）自身输出的 `Program data:` 行，
+/// 因此 CPI 中其他程序的日志不会被误认。无法解码的数据会被跳过
+pub fn parse_logs<S: AsRef<str>>(program_id: &str, logs: &[S]) -> Vec<AmmEvent> {
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs.iter().map(AsRef::as_ref) {
+        if let Some(data) = line.strip_prefix(DATA_PREFIX) {
+            if stack.last() == Some(&program_id) {
+                events.extend(
+                    data.split_whitespace()
+                        .filter_map(|field| STANDARD.decode(field).ok())
+                        .filter_map(|bytes| AmmEvent::try_from_bytes(&bytes)),
+                );
+            }
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        let mut words = rest.split_whitespace();
+        let (Some(id), Some(status)) = (words.next(), words.next()) else {
+            continue;
+        };
+        match status {
+            "invoke" => stack.push(id),
+            "success" | "failed:" => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
@@ -0,0 +1,172 @@
+//! 事件编码 / 解码测试
+//!
+//! 检查每种事件编码后能原样解码、截断或类型标记未知的数据被拒绝，
+//! 以及 `parse_logs` 只从本程序输出的 `Program data:` 行中解析事件
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use blueshift_amm_events::{parse_logs, AmmEvent, EventKind, LiquidityEvent, SwapEvent};
+
+/// AMM 程序 ID（base58）
+const PROGRAM_ID: &str = "22222222222222222222222222222222222222222222";
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const COMPUTE_BUDGET_ID: &str = "ComputeBudget111111111111111111111111111111";
+/// 其他会输出 `Program data:` 的程序（例如聚合器）
+const OTHER_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+fn swap_event() -> SwapEvent {
+    SwapEvent {
+        pool: [1; 32],
+        user: [2; 32],
+        is_x: true,
+        amount_in: 1_000_000,
+        amount_out: 3_987_999,
+        fee: 3_000,
+        reserve_x: 101_000_000,
+        reserve_y: u64::MAX,
+    }
+}
+
+fn liquidity_event() -> LiquidityEvent {
+    LiquidityEvent {
+        pool: [3; 32],
+        user: [4; 32],
+        lp_amount: 1_000,
+        amount_x: 1_001,
+        amount_y: 0,
+        reserve_x: u64::MAX,
+        reserve_y: 42,
+    }
+}
+
+/// 全部事件及其编码
+fn all_events() -> Vec<(AmmEvent, Vec<u8>)> {
+    let swap_y = SwapEvent { is_x: false, ..swap_event() };
+    vec![
+        (AmmEvent::Swap(swap_event()), swap_event().to_bytes().to_vec()),
+        (AmmEvent::Swap(swap_y), swap_y.to_bytes().to_vec()),
+        (AmmEvent::Deposit(liquidity_event()), liquidity_event().to_bytes(EventKind::Deposit).to_vec()),
+        (AmmEvent::Withdraw(liquidity_event()), liquidity_event().to_bytes(EventKind::Withdraw).to_vec()),
+    ]
+}
+
+/// `sol_log_data` 输出的日志行
+fn data_line(bytes: &[u8]) -> String {
+    format!("Program data: {}", STANDARD.encode(bytes))
+}
+
+#[test]
+fn events_round_trip() {
+    for (event, bytes) in all_events() {
+        assert_eq!(AmmEvent::try_from_bytes(&bytes), Some(event));
+    }
+}
+
+#[test]
+fn layout_is_fixed() {
+    // 字段偏移是索引器依赖的公开布局
+    let bytes = swap_event().to_bytes();
+    assert_eq!(bytes.len(), SwapEvent::LEN);
+    assert_eq!(bytes[0], EventKind::Swap as u8);
+    assert_eq!(&bytes[1..33], &[1; 32]);
+    assert_eq!(&bytes[33..65], &[2; 32]);
+    assert_eq!(bytes[65], 1);
+    assert_eq!(bytes[66..74], 1_000_000u64.to_le_bytes());
+    assert_eq!(bytes[98..106], u64::MAX.to_le_bytes());
+
+    let bytes = liquidity_event().to_bytes(EventKind::Withdraw);
+    assert_eq!(bytes.len(), LiquidityEvent::LEN);
+    assert_eq!(bytes[0], EventKind::Withdraw as u8);
+    assert_eq!(bytes[65..73], 1_000u64.to_le_bytes());
+    assert_eq!(bytes[97..105], 42u64.to_le_bytes());
+}
+
+#[test]
+fn rejects_truncated_and_extended_data() {
+    for (_, bytes) in all_events() {
+        for len in 0..bytes.len() {
+            assert_eq!(AmmEvent::try_from_bytes(&bytes[..len]), None, "len {len}");
+        }
+
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert_eq!(AmmEvent::try_from_bytes(&extended), None);
+    }
+}
+
+#[test]
+fn rejects_unknown_kind_and_invalid_fields() {
+    for (_, bytes) in all_events() {
+        for kind in [3u8, 0x7f, 0xff] {
+            let mut data = bytes.clone();
+            data[0] = kind;
+            assert_eq!(AmmEvent::try_from_bytes(&data), None, "kind {kind}");
+        }
+    }
+
+    // 类型标记与长度不匹配：交换事件标记为存入
+    let mut data = swap_event().to_bytes();
+    data[0] = EventKind::Deposit as u8;
+    assert_eq!(AmmEvent::try_from_bytes(&data), None);
+
+    // is_x 只能是 0 或 1
+    let mut data = swap_event().to_bytes();
+    data[65] = 2;
+    assert_eq!(AmmEvent::try_from_bytes(&data), None);
+}
+
+#[test]
+fn parse_logs_reads_program_data_lines() {
+    // 一笔 Swap 交易的完整日志：计算预算指令、Token 程序 CPI，最后输出交换事件
+    let logs = vec![
+        format!("Program {COMPUTE_BUDGET_ID} invoke [1]"),
+        format!("Program {COMPUTE_BUDGET_ID} success"),
+        format!("Program {PROGRAM_ID} invoke [1]"),
+        format!("Program {TOKEN_PROGRAM_ID} invoke [2]"),
+        "Program log: Instruction: Transfer".to_string(),
+        format!("Program {TOKEN_PROGRAM_ID} consumed 4645 of 189385 compute units"),
+        format!("Program {TOKEN_PROGRAM_ID} success"),
+        format!("Program {TOKEN_PROGRAM_ID} invoke [2]"),
+        "Program log: Instruction: Transfer".to_string(),
+        format!("Program {TOKEN_PROGRAM_ID} consumed 4736 of 181024 compute units"),
+        format!("Program {TOKEN_PROGRAM_ID} success"),
+        data_line(&swap_event().to_bytes()),
+        format!("Program {PROGRAM_ID} consumed 23918 of 199700 compute units"),
+        format!("Program {PROGRAM_ID} success"),
+    ];
+
+    assert_eq!(parse_logs(PROGRAM_ID, &logs), vec![AmmEvent::Swap(swap_event())]);
+    assert_eq!(parse_logs(TOKEN_PROGRAM_ID, &logs), vec![]);
+}
+
+#[test]
+fn parse_logs_follows_the_call_stack() {
+    let deposit = liquidity_event().to_bytes(EventKind::Deposit);
+    let withdraw = liquidity_event().to_bytes(EventKind::Withdraw);
+    let logs = vec![
+        // 其他程序输出的同样格式的数据不属于本程序
+        format!("Program {OTHER_PROGRAM_ID} invoke [1]"),
+        data_line(&deposit),
+        // 通过 CPI 调用本程序：只取本程序自身的输出
+        format!("Program {PROGRAM_ID} invoke [2]"),
+        data_line(&swap_event().to_bytes()),
+        format!("Program {PROGRAM_ID} success"),
+        data_line(&withdraw),
+        format!("Program {OTHER_PROGRAM_ID} success"),
+        // 顶层直接调用：多个字段、无法解码的数据被跳过
+        format!("Program {PROGRAM_ID} invoke [1]"),
+        format!("Program data: {} not-base64!", STANDARD.encode(withdraw)),
+        data_line(&[EventKind::Swap as u8, 0, 0]),
+        format!("Program {PROGRAM_ID} success"),
+        // 调用栈已经结束后的数据行不属于任何程序
+        data_line(&deposit),
+    ];
+
+    assert_eq!(
+        parse_logs(PROGRAM_ID, &logs),
+        vec![AmmEvent::Swap(swap_event()), AmmEvent::Withdraw(liquidity_event())]
+    );
+    assert_eq!(
+        parse_logs(OTHER_PROGRAM_ID, &logs),
+        vec![AmmEvent::Deposit(liquidity_event()), AmmEvent::Withdraw(liquidity_event())]
+    );
+}
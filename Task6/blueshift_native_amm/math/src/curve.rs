@@ -0,0 +1,340 @@
+//! 恒定乘积曲线计算（x * y = k），以及 LBP 使用的加权版本（x^wx * y^wy = k）
+
+use core::cmp::Ordering;
+use crate::error::{mul_div_floor, MathError};
+
+/// 基点分母
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// 从输入中扣除费用，返回 (扣费后的输入, 费用数量)
+///
+/// 扣费后的输入向下取整，即费用向上取整
+fn deduct_fee(amount_in: u64, fee_bps: u16) -> Result<(u128, u64), MathError> {
+    if fee_bps as u64 > BPS_DENOMINATOR {
+        return Err(MathError::InvalidFee);
+    }
+
+    let amount_in_after_fee = mul_div_floor(
+        amount_in as u128,
+        (BPS_DENOMINATOR - fee_bps as u64) as u128,
+        BPS_DENOMINATOR as u128,
+    )
+    .ok_or(MathError::FeeOverflow)?;
+    let fee_amount = (amount_in as u128)
+        .checked_sub(amount_in_after_fee)
+        .ok_or(MathError::FeeOverflow)?;
+
+    Ok((amount_in_after_fee, fee_amount as u64))
+}
+
+/// 计算交换输出
+///
+/// 先从输入中扣除费用，再按恒定乘积曲线计算输出，结果向下取整。
+/// 返回 (输出数量, 费用数量)
+pub fn swap_output(reserve_in: u64, reserve_out: u64, amount_in: u64, fee_bps: u16) -> Result<(u64, u64), MathError> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(MathError::EmptyReserve);
+    }
+
+    let (amount_in_after_fee, fee_amount) = deduct_fee(amount_in, fee_bps)?;
+
+    let denominator = (reserve_in as u128)
+        .checked_add(amount_in_after_fee)
+        .ok_or(MathError::SwapOverflow)?;
+    let amount_out =
+        mul_div_floor(reserve_out as u128, amount_in_after_fee, denominator).ok_or(MathError::SwapOverflow)?;
+
+    Ok((u64::try_from(amount_out).map_err(|_| MathError::SwapOutputOverflow)?, fee_amount))
+}
+
+/// 计算两组储备之间 Y/X 价格变化的幅度（基点）
+///
+/// 价格 p = y / x，变化幅度为 |p1 - p0| / p0，等价于
+/// |y1 * x0 - y0 * x1| / (y0 * x1)，全程使用 u128 避免精度损失
+pub fn price_change_bps(x0: u64, y0: u64, x1: u64, y1: u64) -> u64 {
+    let before = y0 as u128 * x1 as u128;
+    let after = y1 as u128 * x0 as u128;
+    if before == 0 {
+        return if after == 0 { 0 } else { u64::MAX };
+    }
+
+    let diff = before.abs_diff(after);
+    let bps = match diff.checked_mul(BPS_DENOMINATOR as u128) {
+        Some(scaled) => scaled / before,
+        None => diff / (before / BPS_DENOMINATOR as u128).max(1),
+    };
+
+    u64::try_from(bps).unwrap_or(u64::MAX)
+}
+
+/// 定点数精度（1.0 = 10^18）
+const WAD: u128 = 1_000_000_000_000_000_000;
+/// ln(2) * 10^18
+const LN_2_WAD: u128 = 693_147_180_559_945_309;
+/// 幂运算结果额外加上的误差余量，使输出始终向下取整
+const POW_ERROR_MARGIN: u128 = 1_000;
+
+/// 计算加权池子的交换输出
+///
+/// 加权恒定乘积：x^wx * y^wy = k，输出为
+/// reserve_out * (1 - (reserve_in / (reserve_in + amount_in))^(weight_in / weight_out))。
+/// 权重相等时与 `swap_output` 完全一致；否则使用定点数幂运算，
+/// 每一步都向有利于池子的方向取整。返回 (输出数量, 费用数量)
+pub fn weighted_swap_output(
+    reserve_in: u64,
+    reserve_out: u64,
+    weight_in: u64,
+    weight_out: u64,
+    amount_in: u64,
+    fee_bps: u16,
+) -> Result<(u64, u64), MathError> {
+    if weight_in == 0 || weight_out == 0 {
+        return Err(MathError::InvalidWeight);
+    }
+    if weight_in == weight_out {
+        return swap_output(reserve_in, reserve_out, amount_in, fee_bps);
+    }
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(MathError::EmptyReserve);
+    }
+
+    let (amount_in_after_fee, fee_amount) = deduct_fee(amount_in, fee_bps)?;
+
+    // base = reserve_in / (reserve_in + amount_in)，取 1 / base 向下取整
+    let denominator = (reserve_in as u128)
+        .checked_add(amount_in_after_fee)
+        .ok_or(MathError::SwapOverflow)?;
+    let inverse_base = mul_div_floor(denominator, WAD, reserve_in as u128).ok_or(MathError::PowOverflow)?;
+
+    // base^exponent = 2^(-exponent * log2(1 / base))
+    let log = log2_wad(inverse_base)
+        .and_then(|log| mul_div_floor(log, weight_in as u128, weight_out as u128))
+        .ok_or(MathError::PowOverflow)?;
+    let power = exp2_neg_wad(log)
+        .and_then(|power| power.checked_add(POW_ERROR_MARGIN))
+        .ok_or(MathError::PowOverflow)?
+        .min(WAD);
+
+    let amount_out = mul_div_floor(reserve_out as u128, WAD - power, WAD).ok_or(MathError::SwapOverflow)?;
+
+    Ok((u64::try_from(amount_out).map_err(|_| MathError::SwapOutputOverflow)?, fee_amount))
+}
+
+/// 比较加权池子的 Y/X 现货价格与 price / precision
+///
+/// 现货价格为 (y / wy) / (x / wx)，即比较 y * wx * precision 与 price * x * wy；
+/// 右侧溢出时说明限价远高于现货价格
+pub fn compare_spot_price(
+    reserve_x: u64,
+    reserve_y: u64,
+    weight_x: u64,
+    weight_y: u64,
+    price: u128,
+    precision: u128,
+) -> Ordering {
+    let pool = (reserve_y as u128 * weight_x as u128).saturating_mul(precision);
+    match price
+        .checked_mul(reserve_x as u128)
+        .and_then(|target| target.checked_mul(weight_y as u128))
+    {
+        Some(target) => pool.cmp(&target),
+        None => Ordering::Less,
+    }
+}
+
+/// 参考价格的定点精度：price = 每个 X 最小单位对应的 Y 最小单位数量 * PRICE_SCALE
+pub const PRICE_SCALE: u128 = 1_000_000_000;
+
+/// 交换成交价相对参考价格的偏离（基点）
+///
+/// 成交价按 Y/X 计：X 换 Y 为 amount_out / amount_in，Y 换 X 为 amount_in / amount_out，
+/// 包含费用和价格影响。输出为 0 或参考价格为 0 时返回 u64::MAX
+pub fn execution_deviation_bps(is_x: bool, amount_in: u64, amount_out: u64, price: u64) -> u64 {
+    if amount_in == 0 || amount_out == 0 || price == 0 {
+        return u64::MAX;
+    }
+
+    // 两个 u64 相乘再乘以 10^9 仍在 u128 范围内
+    let execution = if is_x {
+        amount_out as u128 * PRICE_SCALE / amount_in as u128
+    } else {
+        amount_in as u128 * PRICE_SCALE / amount_out as u128
+    };
+    let deviation = execution.abs_diff(price as u128) * BPS_DENOMINATOR as u128 / price as u128;
+    u64::try_from(deviation).unwrap_or(u64::MAX)
+}
+
+/// 对数比较时扣除的误差余量（10^-9），使限价成交数量偏小
+const LOG_ERROR_MARGIN: u128 = 1_000_000_000;
+
+/// 计算限价单最多可以从曲线成交的输入数量（含费用）
+///
+/// 输入侧扣费后的边际价格为 (reserve_out / weight_out) / (reserve_in / weight_in) * (1 - fee)，
+/// 要求成交后仍不低于 limit_num / limit_den。沿（加权）曲线把 reserve_in 放大 r 倍时
+/// 边际价格变为原来的 r^(-(weight_in + weight_out) / weight_out)，因此
+/// r <= (spot / limit)^(weight_out / (weight_in + weight_out))，权重相等时即平方根。
+/// 费用留在池子中只会让实际的边际价格更高，结果向下取整，超出 u64 时返回 u64::MAX。
+/// 扣费后价格未越过限价时返回 0
+pub fn limit_fill_input(
+    reserve_in: u64,
+    reserve_out: u64,
+    weight_in: u64,
+    weight_out: u64,
+    limit_num: u128,
+    limit_den: u128,
+    fee_bps: u16,
+) -> Result<u64, MathError> {
+    if weight_in == 0 || weight_out == 0 {
+        return Err(MathError::InvalidWeight);
+    }
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(MathError::EmptyReserve);
+    }
+    if fee_bps as u64 > BPS_DENOMINATOR {
+        return Err(MathError::InvalidFee);
+    }
+    if limit_num == 0 || limit_den == 0 || fee_bps as u64 == BPS_DENOMINATOR {
+        return Ok(0);
+    }
+
+    // log2(spot * (1 - fee) / limit)，逐项取对数避免乘积溢出
+    let log = |n: u128| log2_int_wad(n).ok_or(MathError::PowOverflow);
+    let pool = log(reserve_out as u128)?
+        + log(weight_in as u128)?
+        + log(limit_den)?
+        + log((BPS_DENOMINATOR - fee_bps as u64) as u128)?;
+    let target = log(reserve_in as u128)?
+        + log(weight_out as u128)?
+        + log(limit_num)?
+        + log(BPS_DENOMINATOR as u128)?;
+    let Some(ratio) = pool.checked_sub(target) else {
+        return Ok(0);
+    };
+
+    // log2(r) 扣除误差余量后拆成整数和小数部分
+    let exponent = mul_div_floor(ratio, weight_out as u128, (weight_in + weight_out) as u128)
+        .ok_or(MathError::PowOverflow)?;
+    let Some(exponent) = exponent.checked_sub(LOG_ERROR_MARGIN) else {
+        return Ok(0);
+    };
+    let integer = exponent / WAD;
+    if integer >= 64 {
+        return Ok(u64::MAX);
+    }
+
+    // 2^frac = 1 / 2^(-frac)，exp2_neg_wad 向上取整，因此结果向下取整
+    let power = exp2_neg_wad(exponent % WAD)
+        .map(|inverse| WAD * WAD / inverse)
+        .ok_or(MathError::PowOverflow)?;
+    let scaled = mul_div_floor(reserve_in as u128, power, WAD).ok_or(MathError::PowOverflow)?;
+    let new_reserve_in = scaled.checked_shl(integer as u32).filter(|value| value >> integer == scaled);
+
+    Ok(match new_reserve_in {
+        Some(value) => u64::try_from(value - reserve_in as u128).unwrap_or(u64::MAX),
+        None => u64::MAX,
+    })
+}
+
+/// 检查成交后（加权）恒定乘积没有变小
+///
+/// 权重相等时直接比较 x * y；否则比较 wx * log2(x) + wy * log2(y)，
+/// 对数向下取整，允许 LOG_ERROR_MARGIN 以内的定点误差
+pub fn check_invariant(
+    reserve_x: u64,
+    reserve_y: u64,
+    new_reserve_x: u64,
+    new_reserve_y: u64,
+    weight_x: u64,
+    weight_y: u64,
+) -> Result<(), MathError> {
+    if weight_x == weight_y {
+        let before = reserve_x as u128 * reserve_y as u128;
+        let after = new_reserve_x as u128 * new_reserve_y as u128;
+        return if after >= before { Ok(()) } else { Err(MathError::InvariantDecreased) };
+    }
+    if new_reserve_x >= reserve_x && new_reserve_y >= reserve_y {
+        return Ok(());
+    }
+    if new_reserve_x == 0 || new_reserve_y == 0 {
+        return Err(MathError::InvariantDecreased);
+    }
+
+    let weighted_log = |x: u64, y: u64| -> Result<u128, MathError> {
+        let log = |n: u64| log2_int_wad(n as u128).ok_or(MathError::PowOverflow);
+        Ok(log(x)? * weight_x as u128 + log(y)? * weight_y as u128)
+    };
+    let before = weighted_log(reserve_x, reserve_y)?;
+    let after = weighted_log(new_reserve_x, new_reserve_y)?;
+    let margin = LOG_ERROR_MARGIN * (weight_x + weight_y) as u128;
+    if after.saturating_add(margin) >= before {
+        Ok(())
+    } else {
+        Err(MathError::InvariantDecreased)
+    }
+}
+
+/// 以 10^18 为精度计算整数 n 的 log2，要求 n >= 1，结果向下取整
+///
+/// 先取最高位得到整数部分，再把 n 规整到 [1, 2) 的 60 位定点数计算小数部分
+fn log2_int_wad(n: u128) -> Option<u128> {
+    if n == 0 {
+        return None;
+    }
+
+    let integer = 127 - n.leading_zeros();
+    let mantissa = if integer >= 60 { n >> (integer - 60) } else { n << (60 - integer) };
+    let fraction = log2_wad((mantissa * WAD) >> 60)?;
+
+    Some(integer as u128 * WAD + fraction)
+}
+
+/// 以 10^18 为精度计算 log2(x)，要求 x >= 1.0，结果向下取整
+fn log2_wad(mut x: u128) -> Option<u128> {
+    if x < WAD {
+        return None;
+    }
+
+    // 整数部分
+    let mut integer = 0u128;
+    while x >= 2 * WAD {
+        x /= 2;
+        integer += 1;
+    }
+
+    // 小数部分：反复平方，每次超过 2 即得到一位二进制小数
+    let mut result = integer * WAD;
+    let mut bit = WAD / 2;
+    for _ in 0..60 {
+        x = x.checked_mul(x)? / WAD;
+        if x >= 2 * WAD {
+            x /= 2;
+            result += bit;
+        }
+        bit /= 2;
+    }
+
+    Some(result)
+}
+
+/// 以 10^18 为精度计算 2^(-x)，x >= 0
+fn exp2_neg_wad(x: u128) -> Option<u128> {
+    let integer = x / WAD;
+    if integer >= 64 {
+        return Some(0);
+    }
+
+    // 2^(-frac) = 1 / e^(frac * ln2)，frac * ln2 < 0.7，泰勒级数快速收敛
+    let t = (x % WAD) * LN_2_WAD / WAD;
+    let mut term = WAD;
+    let mut exp = WAD;
+    for k in 1..30u128 {
+        term = term.checked_mul(t)? / WAD / k;
+        if term == 0 {
+            break;
+        }
+        exp = exp.checked_add(term)?;
+    }
+
+    // 向上取整，使幂运算结果偏大、输出偏小
+    Some((WAD * WAD).div_ceil(exp) >> integer)
+}
@@ -0,0 +1,94 @@
+//! 数学计算错误
+//!
+//! 每种失败对应一个固定的错误码，程序以 `ProgramError::Custom(code)` 返回，
+//! 监控和客户端可以据此区分具体是哪一步计算失败
+
+/// 数学计算错误，取值即错误码，发布后不得修改
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    EmptyReserve = 0x100,         // 储备为 0，无法定价
+    InvalidFee = 0x101,           // 费率超过 10000 基点
+    InvalidWeight = 0x102,        // 权重为 0
+    FeeOverflow = 0x103,          // 扣除费用时溢出
+    SwapOverflow = 0x104,         // 恒定乘积计算溢出
+    SwapOutputOverflow = 0x105,   // 交换输出超出 u64
+    PowOverflow = 0x106,          // 加权池子的幂运算溢出
+    DepositOverflow = 0x107,      // 存入数量计算溢出或超出 u64
+    WithdrawOverflow = 0x108,     // 提取数量计算溢出或超出 u64
+    ZeroLpSupply = 0x109,         // LP 供应量为 0
+    LpExceedsSupply = 0x10a,      // 销毁的 LP 超过供应量
+    OrderRateOverflow = 0x10b,    // 订单卖出速率计算溢出
+    OrderProceedsOverflow = 0x10c, // 订单成交所得计算溢出
+    InvariantDecreased = 0x10d,   // 成交后恒定乘积（加权时为 x^wx * y^wy）变小
+    ReserveOverflow = 0x10e,      // 储备加上输入后超出 u64
+}
+
+impl MathError {
+    /// 全部错误，按错误码排列
+    pub const ALL: [MathError; 15] = [
+        MathError::EmptyReserve,
+        MathError::InvalidFee,
+        MathError::InvalidWeight,
+        MathError::FeeOverflow,
+        MathError::SwapOverflow,
+        MathError::SwapOutputOverflow,
+        MathError::PowOverflow,
+        MathError::DepositOverflow,
+        MathError::WithdrawOverflow,
+        MathError::ZeroLpSupply,
+        MathError::LpExceedsSupply,
+        MathError::OrderRateOverflow,
+        MathError::OrderProceedsOverflow,
+        MathError::InvariantDecreased,
+        MathError::ReserveOverflow,
+    ];
+
+    /// 错误码
+    #[inline(always)]
+    pub const fn code(self) -> u32 {
+        self as u32
+    }
+
+    /// 根据错误码查找错误
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+
+    /// 错误名称
+    pub const fn name(self) -> &'static str {
+        match self {
+            MathError::EmptyReserve => "EmptyReserve",
+            MathError::InvalidFee => "InvalidFee",
+            MathError::InvalidWeight => "InvalidWeight",
+            MathError::FeeOverflow => "FeeOverflow",
+            MathError::SwapOverflow => "SwapOverflow",
+            MathError::SwapOutputOverflow => "SwapOutputOverflow",
+            MathError::PowOverflow => "PowOverflow",
+            MathError::DepositOverflow => "DepositOverflow",
+            MathError::WithdrawOverflow => "WithdrawOverflow",
+            MathError::ZeroLpSupply => "ZeroLpSupply",
+            MathError::LpExceedsSupply => "LpExceedsSupply",
+            MathError::OrderRateOverflow => "OrderRateOverflow",
+            MathError::OrderProceedsOverflow => "OrderProceedsOverflow",
+            MathError::InvariantDecreased => "InvariantDecreased",
+            MathError::ReserveOverflow => "ReserveOverflow",
+        }
+    }
+}
+
+/// 计算 a * b / c，向下取整；乘法溢出或 c 为 0 时返回 None
+#[inline(always)]
+pub fn mul_div_floor(a: u128, b: u128, c: u128) -> Option<u128> {
+    a.checked_mul(b)?.checked_div(c)
+}
+
+/// 计算 a * b / c，向上取整；乘法溢出或 c 为 0 时返回 None
+#[inline(always)]
+pub fn mul_div_ceil(a: u128, b: u128, c: u128) -> Option<u128> {
+    if c == 0 {
+        return None;
+    }
+
+    Some(a.checked_mul(b)?.div_ceil(c))
+}
@@ -0,0 +1,14 @@
+//! Blueshift AMM 池子数学
+//!
+//! 链上程序和链下客户端共用同一份实现，保证客户端报价与链上结算逐位一致。
+//! 所有结果都向有利于池子的方向取整：交换输出和提取数量向下取整，
+//! 存入数量向上取整。全部计算使用 u128 和带检查的运算，失败时返回 `MathError`
+#![no_std]
+
+pub mod curve;
+pub mod error;
+pub mod quote;
+
+pub use curve::*;
+pub use error::*;
+pub use quote::*;
@@ -0,0 +1,141 @@
+//! 交换、存入、提取报价
+//!
+//! 程序的 Swap / Deposit / Withdraw 直接调用这些函数结算，
+//! 客户端用同样的输入即可得到与链上完全一致的结果
+
+use crate::curve::weighted_swap_output;
+use crate::error::{mul_div_ceil, mul_div_floor, MathError};
+
+/// 交换报价
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub amount_out: u64,    // 输出数量（向下取整）
+    pub fee: u64,           // 从输入中扣除的费用
+}
+
+/// 存入报价
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepositQuote {
+    pub amount_x: u64,      // 需要存入的 X（向上取整）
+    pub amount_y: u64,      // 需要存入的 Y（向上取整）
+}
+
+/// 提取报价
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WithdrawQuote {
+    pub amount_x: u64,      // 可以取回的 X（向下取整）
+    pub amount_y: u64,      // 可以取回的 Y（向下取整）
+}
+
+/// 计算交换输出
+///
+/// 储备为扣除订单资金后的有效储备，权重为交换时刻两侧的权重（基点），
+/// 普通池子两侧权重相等。先从输入中扣除费用，再按（加权）恒定乘积曲线
+/// 计算输出，输出向下取整、费用向上取整
+pub fn quote_swap(
+    reserve_in: u64,
+    reserve_out: u64,
+    weight_in: u64,
+    weight_out: u64,
+    amount_in: u64,
+    fee_bps: u16,
+) -> Result<SwapQuote, MathError> {
+    let (amount_out, fee) =
+        weighted_swap_output(reserve_in, reserve_out, weight_in, weight_out, amount_in, fee_bps)?;
+
+    Ok(SwapQuote { amount_out, fee })
+}
+
+/// 计算铸造 lp_amount 个 LP 需要存入的数量
+///
+/// 池子尚无 LP 时为首次存入，按 max_x / max_y 全额存入并由存入者决定初始价格；
+/// 否则按 LP 占比向上取整：amount = ceil(reserve * lp_amount / lp_supply)。
+/// 结果可能超过 max_x / max_y，滑点检查由调用方完成
+pub fn quote_deposit(
+    reserve_x: u64,
+    reserve_y: u64,
+    lp_supply: u64,
+    lp_amount: u64,
+    max_x: u64,
+    max_y: u64,
+) -> Result<DepositQuote, MathError> {
+    if lp_supply == 0 {
+        return Ok(DepositQuote {
+            amount_x: max_x,
+            amount_y: max_y,
+        });
+    }
+    if reserve_x == 0 || reserve_y == 0 {
+        return Err(MathError::EmptyReserve);
+    }
+
+    let share = |reserve: u64| {
+        mul_div_ceil(reserve as u128, lp_amount as u128, lp_supply as u128)
+            .and_then(|amount| u64::try_from(amount).ok())
+            .ok_or(MathError::DepositOverflow)
+    };
+
+    Ok(DepositQuote {
+        amount_x: share(reserve_x)?,
+        amount_y: share(reserve_y)?,
+    })
+}
+
+/// 按金库实际收到的数量计算可以铸造的 LP
+///
+/// 转账费用（Token-2022）会使金库收到的数量少于报价，LP 按两侧实际收到数量的
+/// 较小占比向下取整：lp = min(floor(received_x * lp_supply / reserve_x), floor(received_y * lp_supply / reserve_y))，
+/// 且不超过请求的 lp_amount。储备为存入前的有效储备
+pub fn deposit_lp_amount(
+    reserve_x: u64,
+    reserve_y: u64,
+    lp_supply: u64,
+    lp_amount: u64,
+    received_x: u64,
+    received_y: u64,
+) -> Result<u64, MathError> {
+    if lp_supply == 0 {
+        return Err(MathError::ZeroLpSupply);
+    }
+    if reserve_x == 0 || reserve_y == 0 {
+        return Err(MathError::EmptyReserve);
+    }
+
+    let share = |received: u64, reserve: u64| {
+        mul_div_floor(received as u128, lp_supply as u128, reserve as u128)
+            .ok_or(MathError::DepositOverflow)
+    };
+
+    let lp = share(received_x, reserve_x)?
+        .min(share(received_y, reserve_y)?)
+        .min(lp_amount as u128);
+    Ok(lp as u64)
+}
+
+/// 计算销毁 lp_amount 个 LP 可以取回的数量
+///
+/// 按 LP 占比向下取整：amount = floor(reserve * lp_amount / lp_supply)
+pub fn quote_withdraw(
+    reserve_x: u64,
+    reserve_y: u64,
+    lp_supply: u64,
+    lp_amount: u64,
+) -> Result<WithdrawQuote, MathError> {
+    if lp_supply == 0 {
+        return Err(MathError::ZeroLpSupply);
+    }
+    if lp_amount > lp_supply {
+        return Err(MathError::LpExceedsSupply);
+    }
+
+    let share = |reserve: u64| {
+        mul_div_floor(reserve as u128, lp_amount as u128, lp_supply as u128)
+            .and_then(|amount| u64::try_from(amount).ok())
+            .ok_or(MathError::WithdrawOverflow)
+    };
+
+    Ok(WithdrawQuote {
+        amount_x: share(reserve_x)?,
+        amount_y: share(reserve_y)?,
+    })
+}
@@ -0,0 +1,223 @@
+//! 池子不变量的性质测试
+//!
+//! 用随机的 Deposit / Withdraw / Swap 序列驱动与程序相同的报价函数，
+//! 模型按处理函数的顺序结算（报价、滑点检查、更新储备和 LP 供应量）。
+//! 一个被动 LP 在开始时存入且从不操作，一个交易者随机操作，检查：
+//! - 交换后 k = x * y 不减少，输出不超过扣费后曲线给出的数量
+//! - 每份 LP 对应的 sqrt(k) 不减少，即被动 LP 的份额价值不减少
+//! - 代币守恒，交易者平仓后拿走的净数量不超过起始储备所在曲线允许的范围
+
+use blueshift_amm_math::{quote_deposit, quote_swap, quote_withdraw};
+use proptest::prelude::*;
+
+/// 普通池子两侧权重相等
+const EQUAL_WEIGHT: u64 = 5000;
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Deposit { lp_bps: u64 },            // 按当前 LP 供应量的比例铸造
+    Withdraw { lp_bps: u64 },           // 按交易者持有 LP 的比例销毁
+    Swap { is_x: bool, amount_bps: u64 }, // 按交易者输入侧余额的比例交换
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Pool {
+    reserve_x: u64,
+    reserve_y: u64,
+    lp_supply: u64,
+    fee: u16,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Trader {
+    x: u64,
+    y: u64,
+    lp: u64,
+}
+
+impl Pool {
+    fn k(&self) -> u128 {
+        self.reserve_x as u128 * self.reserve_y as u128
+    }
+}
+
+/// 按处理函数的流程执行一个操作；被程序拒绝的操作返回 false 且不改变状态
+fn apply(pool: &mut Pool, trader: &mut Trader, op: Op) -> bool {
+    match op {
+        Op::Deposit { lp_bps } => {
+            let amount = (pool.lp_supply as u128 * lp_bps as u128 / 10_000) as u64;
+            if amount == 0 {
+                return false;
+            }
+            let Ok(quote) = quote_deposit(pool.reserve_x, pool.reserve_y, pool.lp_supply, amount, trader.x, trader.y)
+            else {
+                return false;
+            };
+            // 滑点检查：max_x / max_y 为交易者的全部余额
+            if quote.amount_x > trader.x || quote.amount_y > trader.y {
+                return false;
+            }
+            trader.x -= quote.amount_x;
+            trader.y -= quote.amount_y;
+            trader.lp += amount;
+            pool.reserve_x += quote.amount_x;
+            pool.reserve_y += quote.amount_y;
+            pool.lp_supply += amount;
+        }
+        Op::Withdraw { lp_bps } => {
+            let amount = (trader.lp as u128 * lp_bps as u128 / 10_000) as u64;
+            if amount == 0 {
+                return false;
+            }
+            let quote = quote_withdraw(pool.reserve_x, pool.reserve_y, pool.lp_supply, amount).unwrap();
+            trader.x += quote.amount_x;
+            trader.y += quote.amount_y;
+            trader.lp -= amount;
+            pool.reserve_x -= quote.amount_x;
+            pool.reserve_y -= quote.amount_y;
+            pool.lp_supply -= amount;
+        }
+        Op::Swap { is_x, amount_bps } => {
+            let (balance_in, reserve_in, reserve_out) = if is_x {
+                (trader.x, pool.reserve_x, pool.reserve_y)
+            } else {
+                (trader.y, pool.reserve_y, pool.reserve_x)
+            };
+            let amount_in = (balance_in as u128 * amount_bps as u128 / 10_000) as u64;
+            if amount_in == 0 {
+                return false;
+            }
+            let Ok(quote) = quote_swap(reserve_in, reserve_out, EQUAL_WEIGHT, EQUAL_WEIGHT, amount_in, pool.fee)
+            else {
+                return false;
+            };
+            // 程序要求 min >= 1，输出为零的交换会因滑点失败
+            if quote.amount_out == 0 {
+                return false;
+            }
+
+            // 输出不超过扣费后的输入在曲线上对应的数量
+            let net_in = (amount_in - quote.fee) as u128;
+            let curve_out = reserve_out as u128 * net_in / (reserve_in as u128 + net_in);
+            assert!(quote.amount_out as u128 <= curve_out, "{quote:?} exceeds curve output {curve_out}");
+
+            if is_x {
+                trader.x -= amount_in;
+                trader.y += quote.amount_out;
+                pool.reserve_x += amount_in;
+                pool.reserve_y -= quote.amount_out;
+            } else {
+                trader.y -= amount_in;
+                trader.x += quote.amount_out;
+                pool.reserve_y += amount_in;
+                pool.reserve_x -= quote.amount_out;
+            }
+        }
+    }
+    true
+}
+
+/// 256 位乘积 (高 128 位, 低 128 位)
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    let mask = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & mask);
+    let (b_hi, b_lo) = (b >> 64, b & mask);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let cross = (lo_lo >> 64) + (hi_lo & mask) + (lo_hi & mask);
+    let lo = (cross << 64) | (lo_lo & mask);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+    (hi, lo)
+}
+
+/// 每份 LP 对应的 sqrt(k) 不减少：k_before / supply_before² <= k_after / supply_after²
+fn share_value_kept(before: &Pool, after: &Pool) -> bool {
+    let supply_before = before.lp_supply as u128 * before.lp_supply as u128;
+    let supply_after = after.lp_supply as u128 * after.lp_supply as u128;
+    mul_wide(before.k(), supply_after) <= mul_wide(after.k(), supply_before)
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (1u64..=20_000).prop_map(|lp_bps| Op::Deposit { lp_bps }),
+        (1u64..=10_000).prop_map(|lp_bps| Op::Withdraw { lp_bps }),
+        (any::<bool>(), 1u64..=10_000).prop_map(|(is_x, amount_bps)| Op::Swap { is_x, amount_bps }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn random_sequences_cannot_extract_value(
+        reserve_x in 1_000u64..1_000_000_000_000,
+        reserve_y in 1_000u64..1_000_000_000_000,
+        passive_lp in 1_000u64..1_000_000_000_000,
+        fee in 0u16..=1_000,
+        trader_x in 0u64..10_000_000_000_000,
+        trader_y in 0u64..10_000_000_000_000,
+        ops in prop::collection::vec(op_strategy(), 1..64),
+    ) {
+        // 被动 LP 首次存入后从不操作
+        let start = Pool { reserve_x, reserve_y, lp_supply: passive_lp, fee };
+        let mut pool = start;
+        let mut trader = Trader { x: trader_x, y: trader_y, lp: 0 };
+        let total_x = pool.reserve_x as u128 + trader.x as u128;
+        let total_y = pool.reserve_y as u128 + trader.y as u128;
+
+        let close_out = Op::Withdraw { lp_bps: 10_000 };
+        for op in ops.into_iter().chain([close_out]) {
+            let before = pool;
+            if !apply(&mut pool, &mut trader, op) {
+                continue;
+            }
+
+            if let Op::Swap { .. } = op {
+                prop_assert!(pool.k() >= before.k(), "k decreased: {before:?} -> {pool:?}");
+            }
+            prop_assert!(share_value_kept(&before, &pool), "LP share value decreased: {before:?} -> {pool:?}");
+            prop_assert_eq!(pool.reserve_x as u128 + trader.x as u128, total_x);
+            prop_assert_eq!(pool.reserve_y as u128 + trader.y as u128, total_y);
+        }
+
+        // 平仓后池子只属于被动 LP；交易者拿走的净数量只能沿起始曲线或曲线内侧移动储备
+        prop_assert_eq!(trader.lp, 0);
+        prop_assert_eq!(pool.lp_supply, passive_lp);
+        prop_assert!(pool.k() >= start.k(), "trader extracted value: {start:?} -> {pool:?}");
+    }
+
+    #[test]
+    fn swap_round_trip_never_profits(
+        reserve_x in 1_000u64..1_000_000_000_000,
+        reserve_y in 1_000u64..1_000_000_000_000,
+        fee in 0u16..=1_000,
+        amount in 1u64..1_000_000_000_000,
+    ) {
+        // X 换 Y 后把收到的 Y 全部换回 X，换回的 X 不超过投入
+        let Ok(out) = quote_swap(reserve_x, reserve_y, EQUAL_WEIGHT, EQUAL_WEIGHT, amount, fee) else {
+            return Ok(());
+        };
+        prop_assume!(out.amount_out > 0);
+
+        let back = quote_swap(
+            reserve_y - out.amount_out,
+            reserve_x + amount,
+            EQUAL_WEIGHT,
+            EQUAL_WEIGHT,
+            out.amount_out,
+            fee,
+        );
+        if let Ok(back) = back {
+            prop_assert!(back.amount_out <= amount, "round trip returned {} for {amount}", back.amount_out);
+        }
+    }
+}
+
+#[test]
+fn mul_wide_matches_checked_products() {
+    assert_eq!(mul_wide(u64::MAX as u128, u64::MAX as u128), (0, u64::MAX as u128 * u64::MAX as u128));
+    assert_eq!(mul_wide(1 << 127, 4), (2, 0));
+    assert_eq!(mul_wide(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+}
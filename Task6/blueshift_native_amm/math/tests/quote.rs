@@ -0,0 +1,136 @@
+//! 报价结果的固定值测试
+//!
+//! 程序和客户端共用这些函数，任何改动导致结果变化都会让链上与链下报价不一致
+
+use blueshift_amm_math::{
+    check_invariant, deposit_lp_amount, execution_deviation_bps, limit_fill_input, quote_deposit, quote_swap,
+    quote_withdraw, DepositQuote, MathError, SwapQuote, WithdrawQuote, PRICE_SCALE,
+};
+
+#[test]
+fn swap_constant_product() {
+    // 扣除 0.3% 费用后按 x * y = k 计算，输出向下取整
+    assert_eq!(
+        quote_swap(1_000_000, 1_000_000, 5000, 5000, 10_000, 30),
+        Ok(SwapQuote { amount_out: 9871, fee: 30 })
+    );
+    assert_eq!(
+        quote_swap(1_000_000, 2_000_000, 5000, 5000, 10_000, 0),
+        Ok(SwapQuote { amount_out: 19801, fee: 0 })
+    );
+}
+
+#[test]
+fn swap_weighted() {
+    // 输入侧权重更高时，同样的输入换到更多输出
+    assert_eq!(
+        quote_swap(1_000_000, 1_000_000, 8000, 2000, 10_000, 30),
+        Ok(SwapQuote { amount_out: 38905, fee: 30 })
+    );
+    assert_eq!(
+        quote_swap(1_000_000, 1_000_000, 2000, 8000, 10_000, 30),
+        Ok(SwapQuote { amount_out: 2477, fee: 30 })
+    );
+}
+
+#[test]
+fn swap_empty_reserve() {
+    assert_eq!(quote_swap(0, 1_000_000, 5000, 5000, 10_000, 30), Err(MathError::EmptyReserve));
+}
+
+#[test]
+fn deposit_rounds_up() {
+    assert_eq!(
+        quote_deposit(1_000_001, 2_000_003, 1_000_000, 333, u64::MAX, u64::MAX),
+        Ok(DepositQuote { amount_x: 334, amount_y: 667 })
+    );
+}
+
+#[test]
+fn deposit_overflow() {
+    // 结果超出 u64 时返回错误而不是截断
+    assert_eq!(
+        quote_deposit(u64::MAX, u64::MAX, 1, 2, u64::MAX, u64::MAX),
+        Err(MathError::DepositOverflow)
+    );
+}
+
+#[test]
+fn deposit_lp_follows_received_amounts() {
+    // 金库收到完整报价时铸造请求的数量
+    assert_eq!(deposit_lp_amount(1_000_001, 2_000_003, 1_000_000, 333, 334, 667), Ok(333));
+    // X 侧被扣除 1% 转账费用：按收到较少的一侧向下取整
+    assert_eq!(deposit_lp_amount(1_000_000, 2_000_000, 1_000_000, 10_000, 9_900, 20_000), Ok(9_900));
+    assert_eq!(deposit_lp_amount(1_000_000, 2_000_000, 1_000_000, 10_000, 10_000, 19_999), Ok(9_999));
+    assert_eq!(deposit_lp_amount(0, 1, 1, 1, 1, 1), Err(MathError::EmptyReserve));
+    assert_eq!(deposit_lp_amount(1, 1, 0, 1, 1, 1), Err(MathError::ZeroLpSupply));
+}
+
+#[test]
+fn swap_large_reserves() {
+    // 接近 u64 上限的储备也不会溢出
+    assert_eq!(
+        quote_swap(u64::MAX, u64::MAX, 5000, 5000, u64::MAX, 0),
+        Ok(SwapQuote { amount_out: u64::MAX / 2, fee: 0 })
+    );
+    assert_eq!(quote_swap(1, 1, 5000, 5000, 1, 10_001), Err(MathError::InvalidFee));
+}
+
+#[test]
+fn deposit_first_uses_max() {
+    assert_eq!(
+        quote_deposit(0, 0, 0, 1_000, 500, 2_000),
+        Ok(DepositQuote { amount_x: 500, amount_y: 2_000 })
+    );
+}
+
+#[test]
+fn withdraw_rounds_down() {
+    assert_eq!(
+        quote_withdraw(1_000_001, 2_000_003, 1_000_000, 333),
+        Ok(WithdrawQuote { amount_x: 333, amount_y: 666 })
+    );
+}
+
+#[test]
+fn withdraw_more_than_supply() {
+    assert_eq!(quote_withdraw(1_000, 1_000, 100, 101), Err(MathError::LpExceedsSupply));
+}
+
+#[test]
+fn execution_deviation() {
+    // 参考价格 4 Y/X：X 换 Y 成交 3.9486，Y 换 X 成交 4.2，均向下取整
+    let price = 4 * PRICE_SCALE as u64;
+    assert_eq!(execution_deviation_bps(true, 10_000, 39_486, price), 128);
+    assert_eq!(execution_deviation_bps(false, 42_000, 10_000, price), 500);
+    assert_eq!(execution_deviation_bps(true, 10_000, 0, price), u64::MAX);
+    assert_eq!(execution_deviation_bps(true, 10_000, 40_000, 0), u64::MAX);
+}
+
+#[test]
+fn limit_fill_depth() {
+    // 价格从 1 降到 0.25：x 需要放大到 2 倍，误差余量使结果略小
+    let depth = limit_fill_input(1_000_000, 1_000_000, 5000, 5000, 1, 4, 0).unwrap();
+    assert!((999_990..1_000_000).contains(&depth), "depth {depth}");
+
+    // 费用抵消了价差时无法成交
+    assert_eq!(limit_fill_input(1_000_000, 1_000_000, 5000, 5000, 999, 1000, 30), Ok(0));
+    assert_eq!(limit_fill_input(1_000_000, 1_000_000, 5000, 5000, 2, 1, 0), Ok(0));
+
+    // 按深度成交后扣费边际价格仍不低于限价
+    let depth = limit_fill_input(1_000_000, 1_000_000, 8000, 2000, 3, 1, 30).unwrap();
+    let quote = quote_swap(1_000_000, 1_000_000, 8000, 2000, depth, 30).unwrap();
+    let (x, y) = (1_000_000 + depth as u128, 1_000_000 - quote.amount_out as u128);
+    assert!(y * 8000 * 9970 >= 3 * x * 2000 * 10_000);
+}
+
+#[test]
+fn invariant_check() {
+    assert_eq!(check_invariant(1_000, 1_000, 1_002, 999, 5000, 5000), Ok(()));
+    assert_eq!(check_invariant(1_000, 1_000, 1_000, 999, 5000, 5000), Err(MathError::InvariantDecreased));
+    assert_eq!(check_invariant(1_000_000, 1_000_000, 1_010_000, 970_000, 8000, 2000), Ok(()));
+    assert_eq!(
+        check_invariant(1_000_000, 1_000_000, 1_010_000, 950_000, 8000, 2000),
+        Err(MathError::InvariantDecreased)
+    );
+}
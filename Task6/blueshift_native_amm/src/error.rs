@@ -0,0 +1,166 @@
+use pinocchio::error::ProgramError;
+use blueshift_amm_math::MathError;
+
+/// AMM 错误
+///
+/// 以 `ProgramError::Custom(code)` 返回，取值即错误码，发布后不得修改；
+/// 新增错误只能追加在末尾。数学计算错误见 `MathError`（0x100 起）
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmError {
+    InvalidFee = 0,             // 费率超过 10000 基点或不在允许的档位中
+    InvalidSeed = 1,            // seed 与费率档位不一致
+    InvalidMintOrder = 2,       // mint_x 必须小于 mint_y
+    InvalidWeightSchedule = 3,  // LBP 权重计划无效
+    InvalidAmount = 4,          // 数量为 0
+    PoolNotInitialized = 5,     // 池子未初始化
+    PoolDisabled = 6,           // 池子已禁用或仅限提取
+    Expired = 7,                // 指令已过期
+    SlippageExceeded = 8,       // 超出滑点限制
+    PriceImpactExceeded = 9,    // 触发价格影响熔断
+    SaleInProgress = 10,        // LBP 发售结束前只有池子管理权限可以存入
+    InvalidMint = 11,           // Mint 与池子不一致
+    InvalidVault = 12,          // 金库不是规范的关联代币账户
+    InvalidLpMint = 13,         // LP Mint 不属于该池子
+    InvalidTokenAccount = 14,   // 代币账户数据无效
+    WalletNotAllowed = 15,      // 许可池子中用户不在白名单
+    InvalidOracle = 16,         // 价格源账户与 Config 不一致或价格无效
+    OracleStale = 17,           // 价格源超过最长有效期未更新
+    OracleDeviationExceeded = 18, // 成交价偏离参考价格超过上限
+    ConfigOutdated = 19,        // Config 账户布局过旧，需要先执行 MigrateConfig
+    VaultBalanceDecreased = 20, // 转入后金库余额反而减少
+    InvalidSettings = 21,       // 全局设置账户地址与种子不一致
+    InvalidRegistryPage = 22,   // 注册表页不是当前页、地址不一致或新建时缺少 bump
+    RegistryFull = 23,          // 注册表页码超出范围或当前页已满
+    NonCanonicalBump = 24,      // PDA bump 不是规范 bump
+    InvalidOrderPool = 25,      // 长期订单池不属于该池子
+    InvalidOrderBook = 26,      // 限价单簿不属于该池子
+    InvalidNativeMint = 27,     // 原生 SOL 模式传入的 wSOL Mint 无效
+    ReservedOverflow = 28,      // 属于订单的代币数量溢出或不足
+    InvalidConfig = 29,         // Config 账户数据无法识别或长度不足
+    OrderTooSmall = 30,         // 订单数量低于最小值
+    OrderSlotsFull = 31,        // 订单槽位已满
+    InvalidFarm = 32,           // farm 账户地址与种子不一致
+    InvalidStakePosition = 33,  // 质押仓位不属于该 farm 或用户
+    InsufficientStake = 34,     // 取回数量超过仓位的质押数量
+    RewardOverflow = 35,        // 奖励或质押数量溢出
+}
+
+impl AmmError {
+    /// 全部错误，按错误码排列
+    pub const ALL: [AmmError; 36] = [
+        AmmError::InvalidFee,
+        AmmError::InvalidSeed,
+        AmmError::InvalidMintOrder,
+        AmmError::InvalidWeightSchedule,
+        AmmError::InvalidAmount,
+        AmmError::PoolNotInitialized,
+        AmmError::PoolDisabled,
+        AmmError::Expired,
+        AmmError::SlippageExceeded,
+        AmmError::PriceImpactExceeded,
+        AmmError::SaleInProgress,
+        AmmError::InvalidMint,
+        AmmError::InvalidVault,
+        AmmError::InvalidLpMint,
+        AmmError::InvalidTokenAccount,
+        AmmError::WalletNotAllowed,
+        AmmError::InvalidOracle,
+        AmmError::OracleStale,
+        AmmError::OracleDeviationExceeded,
+        AmmError::ConfigOutdated,
+        AmmError::VaultBalanceDecreased,
+        AmmError::InvalidSettings,
+        AmmError::InvalidRegistryPage,
+        AmmError::RegistryFull,
+        AmmError::NonCanonicalBump,
+        AmmError::InvalidOrderPool,
+        AmmError::InvalidOrderBook,
+        AmmError::InvalidNativeMint,
+        AmmError::ReservedOverflow,
+        AmmError::InvalidConfig,
+        AmmError::OrderTooSmall,
+        AmmError::OrderSlotsFull,
+        AmmError::InvalidFarm,
+        AmmError::InvalidStakePosition,
+        AmmError::InsufficientStake,
+        AmmError::RewardOverflow,
+    ];
+
+    /// 错误码
+    #[inline(always)]
+    pub const fn code(self) -> u32 {
+        self as u32
+    }
+
+    /// 根据错误码查找错误
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+
+    /// 错误名称
+    pub const fn name(self) -> &'static str {
+        match self {
+            AmmError::InvalidFee => "InvalidFee",
+            AmmError::InvalidSeed => "InvalidSeed",
+            AmmError::InvalidMintOrder => "InvalidMintOrder",
+            AmmError::InvalidWeightSchedule => "InvalidWeightSchedule",
+            AmmError::InvalidAmount => "InvalidAmount",
+            AmmError::PoolNotInitialized => "PoolNotInitialized",
+            AmmError::PoolDisabled => "PoolDisabled",
+            AmmError::Expired => "Expired",
+            AmmError::SlippageExceeded => "SlippageExceeded",
+            AmmError::PriceImpactExceeded => "PriceImpactExceeded",
+            AmmError::SaleInProgress => "SaleInProgress",
+            AmmError::InvalidMint => "InvalidMint",
+            AmmError::InvalidVault => "InvalidVault",
+            AmmError::InvalidLpMint => "InvalidLpMint",
+            AmmError::InvalidTokenAccount => "InvalidTokenAccount",
+            AmmError::WalletNotAllowed => "WalletNotAllowed",
+            AmmError::InvalidOracle => "InvalidOracle",
+            AmmError::OracleStale => "OracleStale",
+            AmmError::OracleDeviationExceeded => "OracleDeviationExceeded",
+            AmmError::ConfigOutdated => "ConfigOutdated",
+            AmmError::VaultBalanceDecreased => "VaultBalanceDecreased",
+            AmmError::InvalidSettings => "InvalidSettings",
+            AmmError::InvalidRegistryPage => "InvalidRegistryPage",
+            AmmError::RegistryFull => "RegistryFull",
+            AmmError::NonCanonicalBump => "NonCanonicalBump",
+            AmmError::InvalidOrderPool => "InvalidOrderPool",
+            AmmError::InvalidOrderBook => "InvalidOrderBook",
+            AmmError::InvalidNativeMint => "InvalidNativeMint",
+            AmmError::ReservedOverflow => "ReservedOverflow",
+            AmmError::InvalidConfig => "InvalidConfig",
+            AmmError::OrderTooSmall => "OrderTooSmall",
+            AmmError::OrderSlotsFull => "OrderSlotsFull",
+            AmmError::InvalidFarm => "InvalidFarm",
+            AmmError::InvalidStakePosition => "InvalidStakePosition",
+            AmmError::InsufficientStake => "InsufficientStake",
+            AmmError::RewardOverflow => "RewardOverflow",
+        }
+    }
+}
+
+impl From<AmmError> for ProgramError {
+    #[inline(always)]
+    fn from(error: AmmError) -> Self {
+        ProgramError::Custom(error.code())
+    }
+}
+
+/// 把数学库错误转换为 ProgramError::Custom
+///
+/// 每种计算失败都有独立的错误码（见 `MathError`），不会因溢出而 panic
+#[inline(always)]
+pub fn math_error(error: MathError) -> ProgramError {
+    ProgramError::Custom(error.code())
+}
+
+/// 链下解码：把 `ProgramError::Custom` 的错误码映射为名称
+///
+/// 同时覆盖 `AmmError` 和 `MathError`，未知错误码返回 None
+pub fn error_name(code: u32) -> Option<&'static str> {
+    AmmError::from_code(code)
+        .map(AmmError::name)
+        .or_else(|| MathError::from_code(code).map(MathError::name))
+}
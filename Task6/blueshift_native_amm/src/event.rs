@@ -0,0 +1,18 @@
+//! 通过 `sol_log_data` 输出事件，布局见 `blueshift_amm_events`
+
+pub use blueshift_amm_events::{EventKind, LiquidityEvent, SwapEvent};
+
+/// 把事件数据写入交易日志（`Program data: <base64>`）
+///
+/// 非 Solana 目标（如本地测试）下不输出
+#[inline(always)]
+pub fn emit(data: &[u8]) {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        let fields: [&[u8]; 1] = [data];
+        pinocchio::syscalls::sol_log_data(fields.as_ptr() as *const u8, fields.len() as u64);
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    core::hint::black_box(data);
+}
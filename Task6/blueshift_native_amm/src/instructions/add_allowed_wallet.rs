@@ -0,0 +1,91 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use crate::state::{AllowListEntry, Config};
+
+/// AddAllowedWallet 指令数据
+pub struct AddAllowedWalletInstructionData {
+    pub wallet: Address,
+    pub bump: u8,
+}
+
+impl AddAllowedWalletInstructionData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != 32 + 1 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut wallet = [0u8; 32];
+        wallet.copy_from_slice(&data[0..32]);
+
+        Ok(Self {
+            wallet: Address::new_from_array(wallet),
+            bump: data[32],
+        })
+    }
+}
+
+/// AddAllowedWallet 指令 - 把钱包加入许可池子的白名单
+///
+/// 账户顺序：
+/// 0. authority (signer, writable) - 池子管理权限，同时支付租金
+/// 1. config - Config 账户
+/// 2. entry (writable) - 白名单条目 PDA ["allow", config, wallet]
+/// 3. system_program - 系统程序
+pub fn add_allowed_wallet(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [authority, config, entry, _system_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 authority 是签名者
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有，且 authority 为池子管理权限
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    {
+        let config_data = config.try_borrow()?;
+        if !Config::load(&config_data)?.is_authority(authority.address()) {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+    }
+
+    // 解析指令数据
+    let instruction_data = AddAllowedWalletInstructionData::try_from_bytes(data)?;
+
+    // 创建条目 PDA（invoke_signed 会校验种子与地址是否匹配）
+    let bump_binding = [instruction_data.bump];
+    let entry_seeds = [
+        Seed::from(AllowListEntry::SEED),
+        Seed::from(config.address().as_ref()),
+        Seed::from(instruction_data.wallet.as_ref()),
+        Seed::from(&bump_binding),
+    ];
+    let entry_signers = [Signer::from(&entry_seeds)];
+
+    CreateAccount::with_minimum_balance(
+        authority,
+        entry,
+        AllowListEntry::LEN as u64,
+        program_id,
+        None,
+    )?
+    .invoke_signed(&entry_signers)?;
+
+    let mut entry_data = entry.try_borrow_mut()?;
+    let entry_state = AllowListEntry::load_mut(&mut entry_data)?;
+    entry_state.config.copy_from_slice(config.address().as_ref());
+    entry_state.wallet.copy_from_slice(instruction_data.wallet.as_ref());
+    entry_state.bump = instruction_data.bump;
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::state::{Config, LimitOrderBook, LimitOrderStatus};
+use super::helpers::check_vault;
+use super::token::TokenSide;
+
+/// ClaimLimitOrder / CancelLimitOrder 指令数据
+pub struct LimitOrderSlotData {
+    pub slot: u8,   // 订单在订单簿中的槽位
+}
+
+impl LimitOrderSlotData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        match data {
+            [slot] => Ok(Self { slot: *slot }),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+/// ClaimLimitOrder 指令 - 领取限价单的累计成交所得
+///
+/// 部分成交的订单领取后继续挂单；已全部成交（或已取消剩余部分）的订单领取后释放槽位
+///
+/// 账户顺序：
+/// 0. user (signer) - 挂单者
+/// 1. config (writable) - Config 账户
+/// 2. order_book (writable) - 限价单簿 PDA ["orders", config]
+/// 3. vault (writable) - 所得代币的金库
+/// 4. user_ata (writable) - 用户接收所得代币的账户
+/// 5. token_program - 所得代币的 Token 程序
+/// 6. mint - 所得代币的 Mint
+pub fn claim_limit_order(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    settle_order(program_id, data, accounts, false)
+}
+
+/// CancelLimitOrder 指令 - 取消挂单中的限价单，退回尚未卖出的代币
+///
+/// 账户顺序同 ClaimLimitOrder，vault / user_ata / token_program / mint 为卖出侧。
+/// 没有成交所得时释放槽位，否则订单转为已成交，等待领取所得
+pub fn cancel_limit_order(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    settle_order(program_id, data, accounts, true)
+}
+
+/// 结算订单：领取要求有成交所得，取消要求订单仍在挂单
+fn settle_order(program_id: &Address, data: &[u8], accounts: &[AccountView], cancel: bool) -> ProgramResult {
+    let [user, config, order_book, vault, user_ata, token_program, mint, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 user 是签名者
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // 解析指令数据
+    let instruction_data = LimitOrderSlotData::try_from_bytes(data)?;
+
+    // 验证订单簿
+    if !order_book.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let mut book_data = order_book.try_borrow_mut()?;
+    let book_state = LimitOrderBook::load_mut(&mut book_data)?;
+    if book_state.config != *config.address().as_array()
+        || order_book.address() != &book_state.derive_address(program_id)
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // 验证订单归属和状态
+    let order = book_state
+        .orders
+        .get_mut(instruction_data.slot as usize)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    if order.owner != *user.address().as_array() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+    let settleable = if cancel {
+        order.status == LimitOrderStatus::Open as u8
+    } else {
+        order.status != LimitOrderStatus::Empty as u8 && order.proceeds() > 0
+    };
+    if !settleable {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // 取消退回卖出侧，领取发放另一侧
+    let is_x_out = if cancel { order.is_x() } else { !order.is_x() };
+    let amount = if cancel {
+        core::mem::take(&mut order.amount)
+    } else {
+        core::mem::take(&mut order.proceeds)
+    };
+    let amount = u64::from_le_bytes(amount);
+
+    // 订单没有剩余的卖出数量和所得时释放槽位，否则保留剩余部分
+    if order.amount() == 0 {
+        order.status = if order.proceeds() == 0 {
+            LimitOrderStatus::Empty as u8
+        } else {
+            LimitOrderStatus::Filled as u8
+        };
+    }
+    drop(book_data);
+
+    // 验证转出侧并扣除属于订单的数量
+    let mut config_data = config.try_borrow_mut()?;
+    let config_state = Config::load_mut(&mut config_data)?;
+    let expected_mint = if is_x_out { &config_state.mint_x } else { &config_state.mint_y };
+    let side = TokenSide::try_new(mint, token_program, expected_mint)?;
+    check_vault(vault, config.address(), &side)?;
+    config_state.adjust_reserved(is_x_out, 0, amount)?;
+
+    // 创建 PDA 签名种子
+    let seed_bytes = config_state.seed;
+    let config_bump_binding = [config_state.config_bump];
+    let mint_x_address = config_state.mint_x_address();
+    let mint_y_address = config_state.mint_y_address();
+    drop(config_data);
+
+    let config_seeds = [
+        Seed::from(b"config"),
+        Seed::from(&seed_bytes),
+        Seed::from(mint_x_address.as_ref()),
+        Seed::from(mint_y_address.as_ref()),
+        Seed::from(&config_bump_binding),
+    ];
+    let config_signers = [Signer::from(&config_seeds)];
+
+    // 从金库转出（使用 config PDA 签名）
+    side.transfer(vault, user_ata, config, amount, &config_signers)
+}
@@ -0,0 +1,79 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::state::{Farm, StakePosition};
+use super::helpers::{check_farm, check_position, check_vault, token_amount};
+use super::token::TokenSide;
+
+/// ClaimRewards 指令 - 领取质押累积的奖励
+///
+/// 累积的奖励不超过奖励金库中尚未分配的余额，因此已结算的奖励都能足额发放
+///
+/// 账户顺序：
+/// 0. user (signer) - 用户
+/// 1. farm (writable) - Farm PDA ["farm", mint_lp, reward_mint]
+/// 2. position (writable) - 仓位 PDA ["stake", farm, user]
+/// 3. user_reward_ata (writable) - 用户的奖励代币账户
+/// 4. reward_vault (writable) - 奖励金库（farm 的奖励代币关联账户）
+/// 5. reward_mint - 奖励代币 Mint
+/// 6. token_program - 奖励 Mint 的 Token 程序
+pub fn claim_rewards(program_id: &Address, _data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [user, farm, position, user_reward_ata, reward_vault, reward_mint, token_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 user 是签名者
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 farm、仓位、奖励 Mint 和奖励金库
+    check_farm(program_id, farm)?;
+    check_position(program_id, farm, position, user)?;
+
+    let mut farm_data = farm.try_borrow_mut()?;
+    let farm_state = Farm::load_mut(&mut farm_data)?;
+    let reward_side = TokenSide::try_new(reward_mint, token_program, &farm_state.reward_mint)?;
+    check_vault(reward_vault, farm.address(), &reward_side)?;
+
+    // 推进累加器并结算奖励，质押数量不变
+    let now = Clock::get()?.unix_timestamp;
+    farm_state.update(now, token_amount(reward_vault)?)?;
+
+    let mut position_data = position.try_borrow_mut()?;
+    let position_state = StakePosition::load_mut(&mut position_data)?;
+    position_state.settle(farm_state.acc_reward_per_share(), position_state.amount())?;
+
+    // 发放全部已结算奖励；金库余额仍不足时（如带转账费用的奖励代币）未发放部分继续保留
+    let pending_rewards = position_state.pending_rewards();
+    let claimed = pending_rewards.min(token_amount(reward_vault)?);
+    position_state.pending_rewards = (pending_rewards - claimed).to_le_bytes();
+    drop(position_data);
+    farm_state.pay(claimed);
+
+    if claimed == 0 {
+        return Ok(());
+    }
+
+    // 复制签名种子后释放借用，CPI 需要使用 farm 账户
+    let mint_lp_bytes = farm_state.mint_lp;
+    let reward_mint_bytes = farm_state.reward_mint;
+    let bump_binding = [farm_state.bump];
+    drop(farm_data);
+
+    let farm_seeds = [
+        Seed::from(Farm::SEED),
+        Seed::from(&mint_lp_bytes),
+        Seed::from(&reward_mint_bytes),
+        Seed::from(&bump_binding),
+    ];
+    let farm_signers = [Signer::from(&farm_seeds)];
+
+    // 转出奖励代币（使用 farm PDA 签名）
+    reward_side.transfer(reward_vault, user_reward_ata, farm, claimed, &farm_signers)
+}
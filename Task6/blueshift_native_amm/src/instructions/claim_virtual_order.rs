@@ -0,0 +1,145 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::state::{Config, VirtualOrder, VirtualOrderPool};
+use super::helpers::{check_vault, settle_virtual_orders};
+use super::token::TokenSide;
+
+/// ClaimVirtualOrder 指令 - 领取长期订单已成交的部分
+///
+/// 订单到期后领取会同时关闭订单账户并退回实际没有卖出的部分，租金退回用户
+///
+/// 账户顺序：
+/// 0. user (signer, writable) - 订单所有者
+/// 1. config (writable) - Config 账户
+/// 2. order_pool (writable) - 长期订单池 PDA ["twamm", config]
+/// 3. order (writable) - 订单 PDA ["twamm_order", config, user, order_id]
+/// 4. vault_x (writable) - X 代币金库（config 的关联代币账户）
+/// 5. vault_y (writable) - Y 代币金库（config 的关联代币账户）
+/// 6. user_x_ata (writable) - 用户的 X 代币账户
+/// 7. user_y_ata (writable) - 用户的 Y 代币账户
+/// 8. token_program_x - X 代币的 Token 程序
+/// 9. token_program_y - Y 代币的 Token 程序
+/// 10. mint_x - 代币 X 的 Mint
+/// 11. mint_y - 代币 Y 的 Mint
+pub fn claim_virtual_order(program_id: &Address, _data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    settle_order(program_id, accounts, false)
+}
+
+/// CancelVirtualOrder 指令 - 取消长期订单
+///
+/// 领取已成交的部分并退回实际没有卖出的部分，然后关闭订单账户
+///
+/// 账户顺序同 ClaimVirtualOrder
+pub fn cancel_virtual_order(program_id: &Address, _data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    settle_order(program_id, accounts, true)
+}
+
+/// 结算订单：发放成交所得，取消或到期时退回实际没有卖出的部分并关闭账户
+fn settle_order(program_id: &Address, accounts: &[AccountView], cancel: bool) -> ProgramResult {
+    let [user, config, order_pool, order, vault_x, vault_y, user_x_ata, user_y_ata, token_program_x, token_program_y, mint_x, mint_y, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 user 是签名者
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // 验证两侧 Mint、Token 程序和金库
+    let config_data = config.try_borrow()?;
+    let config_state = Config::load(&config_data)?;
+    let side_x = TokenSide::try_new(mint_x, token_program_x, &config_state.mint_x)?;
+    let side_y = TokenSide::try_new(mint_y, token_program_y, &config_state.mint_y)?;
+    check_vault(vault_x, config.address(), &side_x)?;
+    check_vault(vault_y, config.address(), &side_y)?;
+
+    // 创建 PDA 签名种子
+    let seed_bytes = config_state.seed;
+    let config_bump_binding = [config_state.config_bump];
+    let mint_x_address = config_state.mint_x_address();
+    let mint_y_address = config_state.mint_y_address();
+    drop(config_data);
+
+    // 验证订单归属
+    if !order.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    {
+        let order_data = order.try_borrow()?;
+        let order_state = VirtualOrder::load(&order_data)?;
+        if order_state.config != *config.address().as_array()
+            || order_state.owner != *user.address().as_array()
+            || order.address() != &order_state.derive_address(program_id)
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    // 先把所有订单结算到当前时间
+    let now = Clock::get()?.unix_timestamp;
+    settle_virtual_orders(program_id, config, order_pool, vault_x, vault_y, now)?;
+
+    let (is_x, proceeds, refund, close) = {
+        let mut config_data = config.try_borrow_mut()?;
+        let mut pool_data = order_pool.try_borrow_mut()?;
+        let mut order_data = order.try_borrow_mut()?;
+        let order_state = VirtualOrder::load_mut(&mut order_data)?;
+
+        // 取消或到期时关闭订单，退回实际没有卖出的部分
+        let is_x = order_state.is_x();
+        let close = cancel || now >= order_state.end_at();
+        let (proceeds, refund) = VirtualOrderPool::load_mut(&mut pool_data)?.settle_order(
+            Config::load_mut(&mut config_data)?,
+            order_state,
+            close,
+        )?;
+
+        (is_x, proceeds, refund, close)
+    };
+
+    let config_seeds = [
+        Seed::from(b"config"),
+        Seed::from(&seed_bytes),
+        Seed::from(mint_x_address.as_ref()),
+        Seed::from(mint_y_address.as_ref()),
+        Seed::from(&config_bump_binding),
+    ];
+    let config_signers = [Signer::from(&config_seeds)];
+
+    // 从金库转出（使用 config PDA 签名）
+    let (side_in, vault_in, user_in, side_out, vault_out, user_out) = if is_x {
+        (&side_x, vault_x, user_x_ata, &side_y, vault_y, user_y_ata)
+    } else {
+        (&side_y, vault_y, user_y_ata, &side_x, vault_x, user_x_ata)
+    };
+    if proceeds > 0 {
+        side_out.transfer(vault_out, user_out, config, proceeds, &config_signers)?;
+    }
+    if refund > 0 {
+        side_in.transfer(vault_in, user_in, config, refund, &config_signers)?;
+    }
+
+    // 订单结束：关闭订单账户，租金退回用户
+    if close {
+        user.set_lamports(
+            user.lamports()
+                .checked_add(order.lamports())
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        order.set_lamports(0);
+        order.close()?;
+    }
+
+    Ok(())
+}
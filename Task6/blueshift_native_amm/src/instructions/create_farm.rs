@@ -0,0 +1,125 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use crate::error::AmmError;
+use crate::state::{Config, Farm};
+use super::token::TOKEN_2022_PROGRAM_ID;
+
+/// CreateFarm 指令数据
+pub struct CreateFarmInstructionData {
+    pub reward_rate: u64,   // 每秒释放的奖励数量
+    pub lp_bump: u8,        // LP Mint PDA ["mint_lp", config] 的 bump
+    pub farm_bump: u8,      // Farm PDA ["farm", mint_lp, reward_mint] 的 bump
+}
+
+impl CreateFarmInstructionData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        // 8 + 1 + 1 = 10
+        if data.len() != 10 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            reward_rate: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            lp_bump: data[8],
+            farm_bump: data[9],
+        })
+    }
+}
+
+/// CreateFarm 指令 - 为池子的 LP 代币创建质押奖励池
+///
+/// 创建后由任何人向奖励金库（farm PDA 的奖励代币关联账户）注入奖励；
+/// 奖励只从金库中已注入且尚未分配的余额中释放，金库耗尽后停止累积
+///
+/// 账户顺序：
+/// 0. authority (signer, writable) - 池子管理权限，同时支付租金
+/// 1. config - Config 账户
+/// 2. mint_lp - LP Token Mint PDA ["mint_lp", config]
+/// 3. reward_mint - 奖励代币 Mint（Token 或 Token-2022）
+/// 4. farm (writable) - Farm PDA ["farm", mint_lp, reward_mint]
+/// 5. system_program - 系统程序
+pub fn create_farm(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [authority, config, mint_lp, reward_mint, farm, _system_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 authority 是签名者
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 解析指令数据
+    let instruction_data = CreateFarmInstructionData::try_from_bytes(data)?;
+
+    // 验证 config 账户归本程序所有，且 authority 为池子管理权限
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    {
+        let config_data = config.try_borrow()?;
+        let config_state = Config::load(&config_data)?;
+        if !config_state.is_initialized() {
+            return Err(AmmError::PoolNotInitialized.into());
+        }
+        if !config_state.is_authority(authority.address()) {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+    }
+
+    // 验证 LP Mint 属于该池子
+    let expected_mint_lp = Address::derive_address(
+        &[b"mint_lp", config.address().as_ref()],
+        Some(instruction_data.lp_bump),
+        program_id,
+    );
+    if mint_lp.address() != &expected_mint_lp {
+        return Err(AmmError::InvalidLpMint.into());
+    }
+
+    // 奖励 Mint 必须由受支持的 Token 程序拥有
+    if !reward_mint.owned_by(&pinocchio_token::ID) && !reward_mint.owned_by(&TOKEN_2022_PROGRAM_ID) {
+        return Err(AmmError::InvalidMint.into());
+    }
+
+    // 创建 Farm PDA
+    let bump_binding = [instruction_data.farm_bump];
+    let farm_seeds = [
+        Seed::from(Farm::SEED),
+        Seed::from(mint_lp.address().as_ref()),
+        Seed::from(reward_mint.address().as_ref()),
+        Seed::from(&bump_binding),
+    ];
+    let farm_signers = [Signer::from(&farm_seeds)];
+
+    CreateAccount::with_minimum_balance(
+        authority,
+        farm,
+        Farm::LEN as u64,
+        program_id,
+        None,
+    )?
+    .invoke_signed(&farm_signers)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut farm_data = farm.try_borrow_mut()?;
+    let farm_state = Farm::load_mut(&mut farm_data)?;
+    farm_state.config.copy_from_slice(config.address().as_ref());
+    farm_state.mint_lp.copy_from_slice(mint_lp.address().as_ref());
+    farm_state.reward_mint.copy_from_slice(reward_mint.address().as_ref());
+    farm_state.reward_rate = instruction_data.reward_rate.to_le_bytes();
+    farm_state.acc_reward_per_share = [0u8; 16];
+    farm_state.last_update = now.to_le_bytes();
+    farm_state.total_staked = [0u8; 8];
+    farm_state.reward_owed = [0u8; 8];
+    farm_state.bump = instruction_data.farm_bump;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::state::Config;
+use super::helpers::settle_virtual_orders;
+
+/// ExecuteVirtualOrders 指令 - 把池子的长期订单结算到当前时间
+///
+/// 无需权限，任何人都可以调用；Swap 在定价前也会自动执行同样的结算
+///
+/// 账户顺序：
+/// 0. config (writable) - Config 账户
+/// 1. order_pool (writable) - 长期订单池 PDA ["twamm", config]
+/// 2. vault_x - X 代币金库（config 的关联代币账户）
+/// 3. vault_y - Y 代币金库（config 的关联代币账户）
+pub fn execute_virtual_orders(program_id: &Address, _data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [config, order_pool, vault_x, vault_y, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // 金库由 settle_virtual_orders 验证
+    {
+        let config_data = config.try_borrow()?;
+        if !Config::load(&config_data)?.has_virtual_orders() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    settle_virtual_orders(program_id, config, order_pool, vault_x, vault_y, now)
+}
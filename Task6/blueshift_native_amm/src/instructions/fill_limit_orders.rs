@@ -0,0 +1,43 @@
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::state::Config;
+use super::helpers::{check_pool_state, fill_crossed_orders};
+
+/// FillLimitOrders 指令 - 撮合价格已被越过的限价单
+///
+/// 无需权限，任何人都可以调用；Swap 结束时也会自动执行同样的撮合
+///
+/// 账户顺序：
+/// 0. config (writable) - Config 账户
+/// 1. order_book (writable) - 限价单簿 PDA ["orders", config]
+/// 2. vault_x - X 代币金库（config 的关联代币账户）
+/// 3. vault_y - Y 代币金库（config 的关联代币账户）
+pub fn fill_limit_orders(program_id: &Address, _data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [config, order_book, vault_x, vault_y, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // 验证池子状态，金库由 fill_crossed_orders 验证
+    {
+        let config_data = config.try_borrow()?;
+        let config_state = Config::load(&config_data)?;
+        if !config_state.has_limit_orders() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        // 暂停或仅限提取期间不撮合
+        check_pool_state(config_state, false)?;
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    fill_crossed_orders(program_id, config, order_book, vault_x, vault_y, now)
+}
@@ -0,0 +1,352 @@
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView,
+    Address,
+    ProgramResult,
+};
+use crate::error::AmmError;
+use crate::state::{AllowListEntry, AmmState, Config, Farm, LimitOrderBook, LpLock, StakePosition, VirtualOrderPool};
+use super::token::{TokenSide, TOKEN_2022_PROGRAM_ID};
+
+/// 代币账户中 mint 字段的偏移
+const TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
+/// 代币账户中 owner 字段的偏移
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+/// 代币账户中 amount 字段的偏移
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+/// 代币账户最小大小（Token-2022 带扩展的账户会更大）
+const TOKEN_ACCOUNT_LEN: usize = 165;
+/// Mint 账户中 mint_authority 字段（COption<Pubkey>）的偏移
+const MINT_AUTHORITY_OFFSET: usize = 0;
+/// Mint 账户中 supply 字段的偏移
+const MINT_SUPPLY_OFFSET: usize = 36;
+/// 标准 Mint 账户大小
+const MINT_LEN: usize = 82;
+
+/// 读取代币账户余额
+#[inline(always)]
+pub fn token_amount(account: &AccountView) -> Result<u64, ProgramError> {
+    let data = account.try_borrow()?;
+    if data.len() < TOKEN_ACCOUNT_LEN {
+        return Err(AmmError::InvalidTokenAccount.into());
+    }
+
+    Ok(u64::from_le_bytes(
+        data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+            .try_into()
+            .unwrap(),
+    ))
+}
+
+/// 读取池子储备：金库余额扣除属于订单的部分
+///
+/// 调用前金库必须已经验证；接受任意版本的 Config
+pub fn pool_reserves(
+    config: &AccountView,
+    vault_x: &AccountView,
+    vault_y: &AccountView,
+) -> Result<(u64, u64), ProgramError> {
+    let config_data = config.try_borrow()?;
+    Ok(Config::read(&config_data)?.reserves(token_amount(vault_x)?, token_amount(vault_y)?))
+}
+
+/// 读取池子 LP Mint 的供应量
+///
+/// LP Mint 必须是 Config 记录的 LP Mint PDA，由 Token 或 Token-2022 程序拥有且铸币权限为 config，
+/// 保证存入和提取按本池子的 LP 计算份额
+pub fn lp_supply(
+    program_id: &Address,
+    mint_lp: &AccountView,
+    config: &Address,
+    config_state: &Config,
+) -> Result<u64, ProgramError> {
+    if mint_lp.address() != &config_state.mint_lp_address(config, program_id) {
+        return Err(AmmError::InvalidLpMint.into());
+    }
+    if !mint_lp.owned_by(&pinocchio_token::ID) && !mint_lp.owned_by(&TOKEN_2022_PROGRAM_ID) {
+        return Err(AmmError::InvalidLpMint.into());
+    }
+
+    let data = mint_lp.try_borrow()?;
+    if data.len() < MINT_LEN {
+        return Err(AmmError::InvalidLpMint.into());
+    }
+    if data[MINT_AUTHORITY_OFFSET..MINT_AUTHORITY_OFFSET + 4] != [1, 0, 0, 0]
+        || data[MINT_AUTHORITY_OFFSET + 4..MINT_AUTHORITY_OFFSET + 36] != config.as_ref()[..]
+    {
+        return Err(AmmError::InvalidLpMint.into());
+    }
+
+    Ok(u64::from_le_bytes(
+        data[MINT_SUPPLY_OFFSET..MINT_SUPPLY_OFFSET + 8].try_into().unwrap(),
+    ))
+}
+
+/// 验证金库：由该侧的 Token 程序拥有，且是 owner 在该侧 mint 下的关联代币账户
+pub fn check_vault(vault: &AccountView, owner: &Address, side: &TokenSide) -> ProgramResult {
+    if !vault.owned_by(side.token_program.address()) {
+        return Err(AmmError::InvalidVault.into());
+    }
+
+    check_associated_vault(vault, owner, side.mint.address())
+}
+
+/// 验证金库是 owner 在该 mint 下的关联代币账户（ATA）
+///
+/// 金库地址必须等于 ATA(owner, 金库所属的 Token 程序, mint)，不能用 owner 相同的
+/// 其他代币账户替换金库来改变储备；同一 mint 只归属一个 Token 程序，
+/// 另一个 Token 程序下的同名 ATA 无法创建
+pub fn check_associated_vault(vault: &AccountView, owner: &Address, mint: &Address) -> ProgramResult {
+    let token_program = if vault.owned_by(&pinocchio_token::ID) {
+        &pinocchio_token::ID
+    } else if vault.owned_by(&TOKEN_2022_PROGRAM_ID) {
+        &TOKEN_2022_PROGRAM_ID
+    } else {
+        return Err(AmmError::InvalidVault.into());
+    };
+
+    let (expected, _) = Address::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &pinocchio_associated_token_account::ID,
+    );
+    if vault.address() != &expected {
+        return Err(AmmError::InvalidVault.into());
+    }
+
+    let data = vault.try_borrow()?;
+    if data.len() < TOKEN_ACCOUNT_LEN {
+        return Err(AmmError::InvalidVault.into());
+    }
+    if data[TOKEN_ACCOUNT_MINT_OFFSET..TOKEN_ACCOUNT_MINT_OFFSET + 32] != mint.as_ref()[..]
+        || data[TOKEN_ACCOUNT_OWNER_OFFSET..TOKEN_ACCOUNT_OWNER_OFFSET + 32] != owner.as_ref()[..]
+    {
+        return Err(AmmError::InvalidVault.into());
+    }
+
+    Ok(())
+}
+
+/// 验证池子状态
+///
+/// 未初始化和已禁用的池子拒绝所有操作；仅限提取的池子只允许 Withdraw（withdraw 为 true）
+pub fn check_pool_state(config: &Config, withdraw: bool) -> ProgramResult {
+    if config.state == AmmState::Uninitialized as u8 {
+        return Err(AmmError::PoolNotInitialized.into());
+    }
+    let active = if withdraw { config.can_withdraw() } else { config.is_initialized() };
+    if !active {
+        return Err(AmmError::PoolDisabled.into());
+    }
+
+    Ok(())
+}
+
+/// 验证 PDA bump 是规范 bump（find_program_address 返回的 bump）
+///
+/// 同一组种子可能有多个有效 bump，只接受规范 bump 才能保证每组种子只对应一个账户
+pub fn check_canonical_bump(seeds: &[&[u8]], bump: u8, program_id: &Address) -> ProgramResult {
+    let (_, canonical) = Address::find_program_address(seeds, program_id);
+    if bump != canonical {
+        return Err(AmmError::NonCanonicalBump.into());
+    }
+
+    Ok(())
+}
+
+/// 验证指令未过期，返回当前 Clock
+#[inline(always)]
+pub fn check_expiration(expiration: i64) -> Result<Clock, ProgramError> {
+    let clock = Clock::get()?;
+    if clock.unix_timestamp > expiration {
+        return Err(AmmError::Expired.into());
+    }
+
+    Ok(clock)
+}
+
+/// 许可池子：验证用户的白名单条目
+///
+/// 池子开启许可模式时，附加账户的第一个必须是用户的白名单条目 PDA；
+/// 返回去掉该条目后剩余的附加账户
+pub fn check_allowed<'a>(
+    program_id: &Address,
+    config: &AccountView,
+    permissioned: bool,
+    user: &AccountView,
+    remaining: &'a [AccountView],
+) -> Result<&'a [AccountView], ProgramError> {
+    if !permissioned {
+        return Ok(remaining);
+    }
+
+    let (entry, rest) = remaining.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    if !entry.owned_by(program_id) {
+        return Err(AmmError::WalletNotAllowed.into());
+    }
+
+    let entry_data = entry.try_borrow()?;
+    let entry_state = AllowListEntry::load(&entry_data)?;
+    if entry_state.config != *config.address().as_array()
+        || entry_state.wallet != *user.address().as_array()
+        || entry.address() != &entry_state.derive_address(program_id)
+    {
+        return Err(AmmError::WalletNotAllowed.into());
+    }
+
+    Ok(rest)
+}
+
+/// 验证 farm 账户：归本程序所有且地址与存储的种子一致
+pub fn check_farm(program_id: &Address, farm: &AccountView) -> ProgramResult {
+    if !farm.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let farm_data = farm.try_borrow()?;
+    if farm.address() != &Farm::load(&farm_data)?.derive_address(program_id) {
+        return Err(AmmError::InvalidFarm.into());
+    }
+
+    Ok(())
+}
+
+/// 验证质押仓位属于该 farm 和用户
+pub fn check_position(
+    program_id: &Address,
+    farm: &AccountView,
+    position: &AccountView,
+    user: &AccountView,
+) -> ProgramResult {
+    if !position.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let position_data = position.try_borrow()?;
+    let position_state = StakePosition::load(&position_data)?;
+    if position_state.farm != *farm.address().as_array()
+        || position_state.owner != *user.address().as_array()
+        || position.address() != &position_state.derive_address(program_id)
+    {
+        return Err(AmmError::InvalidStakePosition.into());
+    }
+
+    Ok(())
+}
+
+/// 读取 farm 奖励金库的余额
+///
+/// 奖励金库必须是 farm 在奖励 Mint 下的关联代币账户
+pub fn reward_balance(farm: &AccountView, reward_vault: &AccountView) -> Result<u64, ProgramError> {
+    let reward_mint = {
+        let farm_data = farm.try_borrow()?;
+        Address::new_from_array(Farm::load(&farm_data)?.reward_mint)
+    };
+    check_associated_vault(reward_vault, farm.address(), &reward_mint)?;
+    token_amount(reward_vault)
+}
+
+/// 验证锁仓账户归本程序所有、属于该用户且地址与存储的种子一致
+pub fn check_lock(program_id: &Address, lock: &AccountView, user: &AccountView) -> ProgramResult {
+    if !lock.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let lock_data = lock.try_borrow()?;
+    let lock_state = LpLock::load(&lock_data)?;
+    if lock_state.owner != *user.address().as_array()
+        || lock.address() != &lock_state.derive_address(program_id)
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+/// 把池子的长期订单结算到 now
+///
+/// 池子不处于正常交易状态时只推进时间，订单不卖入池子，因此领取和取消
+/// 在暂停期间仍可执行。金库必须是 config 的关联代币账户；调用前不能持有 config 的借用
+pub fn settle_virtual_orders(
+    program_id: &Address,
+    config: &AccountView,
+    order_pool: &AccountView,
+    vault_x: &AccountView,
+    vault_y: &AccountView,
+    now: i64,
+) -> ProgramResult {
+    if !order_pool.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    {
+        let config_data = config.try_borrow()?;
+        let config_state = Config::load(&config_data)?;
+        check_associated_vault(vault_x, config.address(), &config_state.mint_x_address())?;
+        check_associated_vault(vault_y, config.address(), &config_state.mint_y_address())?;
+    }
+    let balance_x = token_amount(vault_x)?;
+    let balance_y = token_amount(vault_y)?;
+
+    let mut pool_data = order_pool.try_borrow_mut()?;
+    let pool_state = VirtualOrderPool::load_mut(&mut pool_data)?;
+    if pool_state.config != *config.address().as_array()
+        || order_pool.address() != &pool_state.derive_address(program_id)
+    {
+        return Err(AmmError::InvalidOrderPool.into());
+    }
+
+    let mut config_data = config.try_borrow_mut()?;
+    pool_state.execute(Config::load_mut(&mut config_data)?, balance_x, balance_y, now)
+}
+
+/// 撮合池子中价格已被越过的限价单
+///
+/// 池子不处于正常交易状态时不撮合。金库必须是 config 的关联代币账户；调用前不能持有 config 的借用
+pub fn fill_crossed_orders(
+    program_id: &Address,
+    config: &AccountView,
+    order_book: &AccountView,
+    vault_x: &AccountView,
+    vault_y: &AccountView,
+    now: i64,
+) -> ProgramResult {
+    if !order_book.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    {
+        let config_data = config.try_borrow()?;
+        let config_state = Config::load(&config_data)?;
+        check_associated_vault(vault_x, config.address(), &config_state.mint_x_address())?;
+        check_associated_vault(vault_y, config.address(), &config_state.mint_y_address())?;
+    }
+    let balance_x = token_amount(vault_x)?;
+    let balance_y = token_amount(vault_y)?;
+
+    let mut book_data = order_book.try_borrow_mut()?;
+    let book_state = LimitOrderBook::load_mut(&mut book_data)?;
+    if book_state.config != *config.address().as_array()
+        || order_book.address() != &book_state.derive_address(program_id)
+    {
+        return Err(AmmError::InvalidOrderBook.into());
+    }
+
+    let mut config_data = config.try_borrow_mut()?;
+    book_state.fill(Config::load_mut(&mut config_data)?, balance_x, balance_y, now)
+}
+
+/// 按 Config 中的开关依次取出可选的附加账户
+///
+/// enabled 为 true 时返回第一个附加账户和剩余部分，否则原样返回
+pub fn split_optional(
+    enabled: bool,
+    remaining: &[AccountView],
+) -> Result<(Option<&AccountView>, &[AccountView]), ProgramError> {
+    if !enabled {
+        return Ok((None, remaining));
+    }
+
+    let (account, rest) = remaining.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    Ok((Some(account), rest))
+}
@@ -5,23 +5,47 @@ use pinocchio::{
     ProgramResult,
     cpi::{Seed, Signer},
 };
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::instructions::InitializeMint2;
+use crate::error::AmmError;
+use crate::state::{AmmSettings, Config, RegistryPage, POOLS_PER_PAGE};
+use super::helpers::check_canonical_bump;
+use super::metadata::{create_lp_mint_with_metadata, LpMetadata};
+
+/// LP Token 的小数位数
+const LP_DECIMALS: u8 = 6;
 
 /// Initialize 指令数据
-pub struct InitializeInstructionData {
-    pub seed: u64,
+pub struct InitializeInstructionData<'a> {
+    pub seed: u64,      // 必须等于 fee，即池子所在的费率档位
     pub fee: u16,
     pub mint_x: Address,
     pub mint_y: Address,
     pub config_bump: u8,
     pub lp_bump: u8,
     pub authority: Address,
+    pub registry_bump: Option<u8>, // 需要新建注册表页时使用
+    pub weight_schedule: Option<WeightSchedule>, // LBP 权重计划
+    pub lp_metadata: Option<LpMetadata<'a>>,     // LP 代币元数据，设置时 LP Mint 使用 Token-2022
+}
+
+/// LBP 权重计划
+#[derive(Clone, Copy, Debug)]
+pub struct WeightSchedule {
+    pub start_weight_x: u16,    // 起始时 X 的权重（基点）
+    pub end_weight_x: u16,      // 结束时 X 的权重（基点）
+    pub start_at: i64,          // 权重开始变化的时间
+    pub end_at: i64,            // 权重停止变化的时间（发售结束）
 }
 
-impl InitializeInstructionData {
+impl<'a> InitializeInstructionData<'a> {
     /// 从字节数组解析指令数据
-    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ProgramError> {
         // 最小长度：8 + 2 + 32 + 32 + 1 + 1 = 76
         // 带 authority：76 + 32 = 108
+        // 带注册表页 bump：108 + 1 = 109
+        // 带 LBP 权重计划：109 + 2 + 2 + 8 + 8 = 129（全零表示不是 LBP 池子）
+        // 带 LP 元数据：129 + name + symbol + uri（各以 1 字节长度为前缀）
         if data.len() < 76 {
             return Err(ProgramError::InvalidInstructionData);
         }
@@ -49,9 +73,27 @@ impl InitializeInstructionData {
             Address::new_from_array([0u8; 32])
         };
 
+        let registry_bump = data.get(108).copied();
+
+        let weight_schedule = if data.len() >= 129 && data[109..129] != [0u8; 20] {
+            Some(WeightSchedule {
+                start_weight_x: u16::from_le_bytes(data[109..111].try_into().unwrap()),
+                end_weight_x: u16::from_le_bytes(data[111..113].try_into().unwrap()),
+                start_at: i64::from_le_bytes(data[113..121].try_into().unwrap()),
+                end_at: i64::from_le_bytes(data[121..129].try_into().unwrap()),
+            })
+        } else {
+            None
+        };
+
+        let lp_metadata = match data.get(129..) {
+            Some(metadata) if !metadata.is_empty() => Some(LpMetadata::try_from_bytes(metadata)?),
+            _ => None,
+        };
+
         // 验证费用不超过 100% (10000 基点)
         if fee > 10_000 {
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(AmmError::InvalidFee.into());
         }
 
         Ok(Self {
@@ -62,6 +104,9 @@ impl InitializeInstructionData {
             config_bump,
             lp_bump,
             authority,
+            registry_bump,
+            weight_schedule,
+            lp_metadata,
         })
     }
 }
@@ -73,10 +118,24 @@ impl InitializeInstructionData {
 /// 1. config (writable) - Config 账户
 /// 2. mint_lp (writable) - LP Token Mint
 /// 3. system_program - 系统程序
-/// 4. token_program - Token 程序
+/// 4. token_program - LP Mint 的 Token 程序（带 LP 元数据时必须是 Token-2022）
+/// 5. settings (writable) - 全局设置 PDA ["settings"]
+/// 6. registry_page (writable) - 当前注册表页 PDA ["registry", page (u32 LE)]
+///
+/// Config PDA 种子为 ["config", fee 档位 (u64 LE), mint_x, mint_y]，
+/// 因此同一交易对在每个费率档位上恰好有一个规范池子。任何人都可以创建规范池子，
+/// 但只有设置管理权限可以为池子指定管理权限（包括 LBP 池子）；其他人创建的池子
+/// 没有管理权限，参数不可变，避免抢先创建者控制交易对的规范池子
+///
+/// 指令数据末尾附带权重计划时创建 LBP 池子：两侧权重随时间线性变化，
+/// 发售结束前只有池子管理权限可以存入流动性
+///
+/// 指令数据末尾附带 LP 元数据时，LP Mint 创建为 Token-2022 Mint，
+/// 带 MetadataPointer 和 TokenMetadata 扩展（例如名称 "X-Y LP"），
+/// 元数据更新权限为 config，池子管理权限之后可以通过 UpdateLpMetadata 修改
 pub fn initialize(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
     // 验证账户数量
-    if accounts.len() < 5 {
+    if accounts.len() < 7 {
         return Err(ProgramError::NotEnoughAccountKeys);
     }
 
@@ -85,7 +144,9 @@ pub fn initialize(program_id: &Address, data: &[u8], accounts: &[AccountView]) -
     let config = &accounts[1];
     let mint_lp = &accounts[2];
     let _system_program = &accounts[3];
-    let _token_program = &accounts[4];
+    let token_program = &accounts[4];
+    let settings = &accounts[5];
+    let registry_page = &accounts[6];
 
     // 验证 initializer 是签名者
     if !initializer.is_signer() {
@@ -95,13 +156,46 @@ pub fn initialize(program_id: &Address, data: &[u8], accounts: &[AccountView]) -
     // 解析指令数据
     let instruction_data = InitializeInstructionData::try_from_bytes(data)?;
 
-    // 验证 mint 不同
-    if instruction_data.mint_x == instruction_data.mint_y {
-        return Err(ProgramError::InvalidInstructionData);
+    // 验证 mint 按地址升序排列（同时保证两者不同），使交易对的 PDA 唯一
+    if instruction_data.mint_x.as_array() >= instruction_data.mint_y.as_array() {
+        return Err(AmmError::InvalidMintOrder.into());
     }
 
-    // 1. 创建 Config 账户（使用 PDA）
+    // 验证全局设置账户
+    if !settings.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let settings_data = settings.try_borrow()?;
+    let settings_state = AmmSettings::load(&settings_data)?;
+    if settings.address() != &settings_state.derive_address(program_id) {
+        return Err(AmmError::InvalidSettings.into());
+    }
+
+    // 费率必须是允许的档位之一，且 seed 必须等于该档位
+    if !settings_state.is_fee_allowed(instruction_data.fee) {
+        return Err(AmmError::InvalidFee.into());
+    }
+    if instruction_data.seed != instruction_data.fee as u64 {
+        return Err(AmmError::InvalidSeed.into());
+    }
+
+    // 只有设置管理权限可以为池子指定管理权限；LBP 池子发售期间依赖管理权限存入
+    let has_authority = instruction_data.authority != Address::default();
+    if has_authority && settings_state.authority != *initializer.address().as_array() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+    if instruction_data.weight_schedule.is_some() && !has_authority {
+        return Err(AmmError::InvalidWeightSchedule.into());
+    }
+    drop(settings_data);
+
+    // 1. 创建 Config 账户（使用 PDA，bump 必须是规范 bump，否则同一交易对和档位可以创建多个池子）
     let seed_bytes = instruction_data.seed.to_le_bytes();
+    check_canonical_bump(
+        &[b"config", &seed_bytes, instruction_data.mint_x.as_ref(), instruction_data.mint_y.as_ref()],
+        instruction_data.config_bump,
+        program_id,
+    )?;
     let config_bump_binding = [instruction_data.config_bump];
     let config_seeds = [
         Seed::from(b"config"),
@@ -117,44 +211,35 @@ pub fn initialize(program_id: &Address, data: &[u8], accounts: &[AccountView]) -
         from: initializer,
         to: config,
         lamports: 10_000_000, // 足够的租金豁免
-        space: 108, // Config::LEN
+        space: Config::ACCOUNT_LEN as u64,
         owner: program_id,
     }.invoke_signed(&config_signers)?;
-    
+
     // 2. 填充 Config 数据
     let mut config_data = config.try_borrow_mut()?;
-    let mut offset = 0;
-    
-    // state (1 byte) - Initialized = 1
-    config_data[offset] = 1;
-    offset += 1;
-    
-    // seed (8 bytes)
-    config_data[offset..offset+8].copy_from_slice(&instruction_data.seed.to_le_bytes());
-    offset += 8;
-    
-    // authority (32 bytes)
-    config_data[offset..offset+32].copy_from_slice(instruction_data.authority.as_ref());
-    offset += 32;
-    
-    // mint_x (32 bytes)
-    config_data[offset..offset+32].copy_from_slice(instruction_data.mint_x.as_ref());
-    offset += 32;
-    
-    // mint_y (32 bytes)
-    config_data[offset..offset+32].copy_from_slice(instruction_data.mint_y.as_ref());
-    offset += 32;
-    
-    // fee (2 bytes)
-    config_data[offset..offset+2].copy_from_slice(&instruction_data.fee.to_le_bytes());
-    offset += 2;
-    
-    // config_bump (1 byte)
-    config_data[offset] = instruction_data.config_bump;
-    
+    let config_state = Config::init(&mut config_data)?;
+    config_state.set_inner(
+        instruction_data.seed,
+        &instruction_data.authority,
+        &instruction_data.mint_x,
+        &instruction_data.mint_y,
+        instruction_data.fee,
+        instruction_data.config_bump,
+    );
+    // LP Mint 在第 3 步以 ["mint_lp", config] 签名创建，地址即规范 PDA
+    config_state.mint_lp = *mint_lp.address().as_array();
+    if let Some(schedule) = &instruction_data.weight_schedule {
+        config_state.set_weight_schedule(
+            schedule.start_weight_x,
+            schedule.end_weight_x,
+            schedule.start_at,
+            schedule.end_at,
+        )?;
+    }
     drop(config_data);
 
     // 3. 创建 LP Mint 账户（使用 PDA）
+    check_canonical_bump(&[b"mint_lp", config.address().as_ref()], instruction_data.lp_bump, program_id)?;
     let lp_bump_binding = [instruction_data.lp_bump];
     let lp_seeds = [
         Seed::from(b"mint_lp"),
@@ -163,33 +248,108 @@ pub fn initialize(program_id: &Address, data: &[u8], accounts: &[AccountView]) -
     ];
     let lp_signers = [Signer::from(&lp_seeds)];
 
-    pinocchio_system::instructions::CreateAccount {
-        from: initializer,
-        to: mint_lp,
-        lamports: 2_000_000,
-        space: 82, // Token Mint 标准大小
-        owner: &pinocchio_token::ID,
-    }.invoke_signed(&lp_signers)?;
-
-    // 手动初始化 LP Mint，直接写入数据，避免 CPI 权限问题
-    // Mint 账户布局：82 字节
-    let mut mint_data = mint_lp.try_borrow_mut()?;
-    
-    // COption::Some(mint_authority) - 1 byte (1) + 32 bytes (address)
-    mint_data[0] = 1; // COption::Some
-    mint_data[1..33].copy_from_slice(config.address().as_ref());
-    
-    // supply - 8 bytes (u64) = 0
-    mint_data[33..41].copy_from_slice(&0u64.to_le_bytes());
-    
-    // decimals - 1 byte = 6
-    mint_data[41] = 6;
-    
-    // is_initialized - 1 byte = 1
-    mint_data[42] = 1;
-    
-    // COption::None(freeze_authority) - 1 byte (0)
-    mint_data[43] = 0;
+    if let Some(lp_metadata) = &instruction_data.lp_metadata {
+        // 铸币权限和元数据更新权限都是 config PDA
+        create_lp_mint_with_metadata(
+            initializer,
+            mint_lp,
+            config,
+            token_program,
+            LP_DECIMALS,
+            lp_metadata,
+            &lp_signers,
+            &config_signers,
+        )?;
+    } else {
+        pinocchio_system::instructions::CreateAccount {
+            from: initializer,
+            to: mint_lp,
+            lamports: 2_000_000,
+            space: 82, // Token Mint 标准大小
+            owner: &pinocchio_token::ID,
+        }.invoke_signed(&lp_signers)?;
+
+        // 通过 Token 程序初始化 LP Mint，铸币权限为 config PDA
+        // 存入时按 LP 供应量计算份额，Mint 必须是 Token 程序认可的真实布局
+        InitializeMint2 {
+            mint: mint_lp,
+            decimals: LP_DECIMALS,
+            mint_authority: config.address(),
+            freeze_authority: None,
+        }.invoke()?;
+    }
+
+    // 4. 将新池子追加到注册表
+    register_pool(program_id, initializer, config, settings, registry_page, &instruction_data)?;
+
+    Ok(())
+}
+
+/// 将池子写入注册表的当前页，必要时创建新页
+fn register_pool(
+    program_id: &Address,
+    payer: &AccountView,
+    config: &AccountView,
+    settings: &AccountView,
+    registry_page: &AccountView,
+    instruction_data: &InitializeInstructionData<'_>,
+) -> ProgramResult {
+    let mut settings_data = settings.try_borrow_mut()?;
+    let settings_state = AmmSettings::load_mut(&mut settings_data)?;
+
+    let pool_count = settings_state.pool_count();
+    let page = u32::try_from(pool_count / POOLS_PER_PAGE as u64)
+        .map_err(|_| AmmError::RegistryFull)?;
+    let page_bytes = page.to_le_bytes();
+
+    // 当前页尚不存在时创建
+    if registry_page.data_len() == 0 {
+        let bump = instruction_data
+            .registry_bump
+            .ok_or(AmmError::InvalidRegistryPage)?;
+        check_canonical_bump(&[RegistryPage::SEED, &page_bytes], bump, program_id)?;
+        let bump_binding = [bump];
+        let registry_seeds = [
+            Seed::from(RegistryPage::SEED),
+            Seed::from(&page_bytes),
+            Seed::from(&bump_binding),
+        ];
+        let registry_signers = [Signer::from(&registry_seeds)];
+
+        CreateAccount::with_minimum_balance(
+            payer,
+            registry_page,
+            RegistryPage::LEN as u64,
+            program_id,
+            None,
+        )?
+        .invoke_signed(&registry_signers)?;
+
+        let mut registry_data = registry_page.try_borrow_mut()?;
+        let registry_state = RegistryPage::load_mut(&mut registry_data)?;
+        registry_state.page = page_bytes;
+        registry_state.bump = bump;
+    }
+
+    // 验证注册表页地址及页码
+    if !registry_page.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let mut registry_data = registry_page.try_borrow_mut()?;
+    let registry_state = RegistryPage::load_mut(&mut registry_data)?;
+    if registry_state.page != page_bytes
+        || registry_page.address() != &registry_state.derive_address(program_id)
+    {
+        return Err(AmmError::InvalidRegistryPage.into());
+    }
+
+    registry_state.push(
+        config.address(),
+        &instruction_data.mint_x,
+        &instruction_data.mint_y,
+        instruction_data.fee,
+    )?;
+    settings_state.pool_count = (pool_count + 1).to_le_bytes();
 
     Ok(())
 }
@@ -0,0 +1,146 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use crate::state::{AmmSettings, MAX_FEE_TIERS};
+use super::helpers::check_canonical_bump;
+
+/// BPF Upgradeable Loader 程序 ID
+pub const BPF_LOADER_UPGRADEABLE_ID: Address =
+    Address::from_str_const("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// ProgramData 账户头：状态标签 (u32) + 部署 slot (u64) + 升级权限 (Option<Pubkey>)
+const PROGRAM_DATA_HEADER_LEN: usize = 4 + 8 + 1 + 32;
+
+/// UpgradeableLoaderState::ProgramData 的状态标签
+const PROGRAM_DATA_TAG: u32 = 3;
+
+/// 费率档位列表（指令数据的公共部分）
+///
+/// 布局：count (1 字节) + count 个 u16 费率（基点）
+pub struct FeeTiersData {
+    pub fee_tiers: [u16; MAX_FEE_TIERS],
+    pub count: usize,
+}
+
+impl FeeTiersData {
+    /// 从字节数组解析费率档位列表
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        let (&count, rest) = data.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+        let count = count as usize;
+
+        if count == 0 || count > MAX_FEE_TIERS || rest.len() != count * 2 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut fee_tiers = [0u16; MAX_FEE_TIERS];
+        for (tier, bytes) in fee_tiers.iter_mut().zip(rest.chunks_exact(2)) {
+            *tier = u16::from_le_bytes([bytes[0], bytes[1]]);
+        }
+
+        Ok(Self { fee_tiers, count })
+    }
+
+    /// 已解析的费率档位
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[u16] {
+        &self.fee_tiers[..self.count]
+    }
+}
+
+/// InitializeSettings 指令数据
+pub struct InitializeSettingsInstructionData {
+    pub bump: u8,   // settings PDA 的规范 bump
+    pub fee_tiers: FeeTiersData,
+}
+
+impl InitializeSettingsInstructionData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        let (&bump, rest) = data.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+        let fee_tiers = FeeTiersData::try_from_bytes(rest)?;
+
+        Ok(Self { bump, fee_tiers })
+    }
+}
+
+/// InitializeSettings 指令 - 创建全局 AMM 设置账户
+///
+/// 只有本程序的升级权限可以执行，防止部署后被他人抢先创建设置账户
+///
+/// 账户顺序：
+/// 0. authority (signer, writable) - 程序升级权限，成为设置管理权限，同时支付租金
+/// 1. settings (writable) - 设置 PDA ["settings"]
+/// 2. system_program - 系统程序
+/// 3. program_data - 本程序的 ProgramData 账户 PDA [program_id]（BPF Upgradeable Loader）
+pub fn initialize_settings(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [authority, settings, _system_program, program_data, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 authority 是签名者
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 authority 是程序的升级权限
+    check_upgrade_authority(program_id, program_data, authority.address())?;
+
+    // 解析指令数据
+    let instruction_data = InitializeSettingsInstructionData::try_from_bytes(data)?;
+
+    // 创建 settings PDA（invoke_signed 会校验种子与地址是否匹配，bump 必须是规范 bump）
+    check_canonical_bump(&[AmmSettings::SEED], instruction_data.bump, program_id)?;
+    let bump_binding = [instruction_data.bump];
+    let settings_seeds = [
+        Seed::from(AmmSettings::SEED),
+        Seed::from(&bump_binding),
+    ];
+    let settings_signers = [Signer::from(&settings_seeds)];
+
+    CreateAccount::with_minimum_balance(
+        authority,
+        settings,
+        AmmSettings::LEN as u64,
+        program_id,
+        None,
+    )?
+    .invoke_signed(&settings_signers)?;
+
+    // 写入设置数据
+    let mut settings_data = settings.try_borrow_mut()?;
+    let settings_state = AmmSettings::load_mut(&mut settings_data)?;
+    settings_state.authority.copy_from_slice(authority.address().as_ref());
+    settings_state.set_fee_tiers(instruction_data.fee_tiers.as_slice())?;
+    settings_state.bump = instruction_data.bump;
+
+    Ok(())
+}
+
+/// 验证 authority 是程序当前的升级权限
+///
+/// 已放弃升级权限（不可升级）的程序没有任何人能通过验证
+fn check_upgrade_authority(program_id: &Address, program_data: &AccountView, authority: &Address) -> ProgramResult {
+    if !program_data.owned_by(&BPF_LOADER_UPGRADEABLE_ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let (expected, _) = Address::find_program_address(&[program_id.as_ref()], &BPF_LOADER_UPGRADEABLE_ID);
+    if program_data.address() != &expected {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let data = program_data.try_borrow()?;
+    let header = data.get(..PROGRAM_DATA_HEADER_LEN).ok_or(ProgramError::InvalidAccountData)?;
+    if header[0..4] != PROGRAM_DATA_TAG.to_le_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if header[12] != 1 || header[13..45] != *authority.as_array() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+
+    Ok(())
+}
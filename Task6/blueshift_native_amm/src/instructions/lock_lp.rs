@@ -0,0 +1,138 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use crate::error::AmmError;
+use crate::state::{Config, LpLock};
+use super::helpers::{check_lock, check_vault};
+use super::token::TokenSide;
+
+/// LockLp 指令数据
+pub struct LockLpInstructionData {
+    pub amount: u64,        // 锁定的 LP 数量
+    pub unlock_at: i64,     // 解锁时间（Unix 时间戳）
+    pub lock_bump: u8,      // 锁仓 PDA ["lock", mint_lp, user] 的 bump（首次锁定时使用）
+}
+
+impl LockLpInstructionData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        // 8 + 8 + 1 = 17
+        if data.len() != 17 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            amount,
+            unlock_at: i64::from_le_bytes(data[8..16].try_into().unwrap()),
+            lock_bump: data[16],
+        })
+    }
+}
+
+/// LockLp 指令 - 把 LP 代币锁定到用户的锁仓 PDA 直到指定时间
+///
+/// 只接受本程序池子的 LP：mint_lp 必须是 config 记录的 LP Mint，锁仓账户记录所属池子。
+/// 首次锁定时创建锁仓账户；追加锁定时解锁时间只能延后，不能提前
+///
+/// 账户顺序：
+/// 0. user (signer, writable) - 用户，首次锁定时支付租金
+/// 1. config - 池子的 Config 账户
+/// 2. lock (writable) - 锁仓 PDA ["lock", mint_lp, user]
+/// 3. user_lp_ata (writable) - 用户的 LP 代币账户
+/// 4. lock_vault (writable) - 锁仓金库（lock 的 LP 关联代币账户）
+/// 5. mint_lp - LP Token Mint（必须是 Config 记录的 LP Mint）
+/// 6. token_program - LP Mint 的 Token 程序
+/// 7. system_program - 系统程序
+pub fn lock_lp(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [user, config, lock, user_lp_ata, lock_vault, mint_lp, token_program, _system_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 user 是签名者
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有，且 mint_lp 是该池子的 LP Mint
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    {
+        let config_data = config.try_borrow()?;
+        if mint_lp.address() != &Config::read(&config_data)?.mint_lp_address(config.address(), program_id) {
+            return Err(AmmError::InvalidLpMint.into());
+        }
+    }
+
+    // 解析指令数据
+    let instruction_data = LockLpInstructionData::try_from_bytes(data)?;
+
+    // 解锁时间必须在未来
+    let now = Clock::get()?.unix_timestamp;
+    if instruction_data.unlock_at <= now {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // 首次锁定时创建锁仓账户，否则验证归属
+    if lock.data_len() == 0 {
+        let bump_binding = [instruction_data.lock_bump];
+        let lock_seeds = [
+            Seed::from(LpLock::SEED),
+            Seed::from(mint_lp.address().as_ref()),
+            Seed::from(user.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        let lock_signers = [Signer::from(&lock_seeds)];
+
+        CreateAccount::with_minimum_balance(
+            user,
+            lock,
+            LpLock::LEN as u64,
+            program_id,
+            None,
+        )?
+        .invoke_signed(&lock_signers)?;
+
+        let mut lock_data = lock.try_borrow_mut()?;
+        let lock_state = LpLock::load_mut(&mut lock_data)?;
+        lock_state.config.copy_from_slice(config.address().as_ref());
+        lock_state.owner.copy_from_slice(user.address().as_ref());
+        lock_state.mint_lp.copy_from_slice(mint_lp.address().as_ref());
+        lock_state.bump = instruction_data.lock_bump;
+    } else {
+        check_lock(program_id, lock, user)?;
+    }
+
+    // 验证 LP Mint 和锁仓金库
+    let lp_side = {
+        let lock_data = lock.try_borrow()?;
+        TokenSide::try_new(mint_lp, token_program, &LpLock::load(&lock_data)?.mint_lp)?
+    };
+    check_vault(lock_vault, lock.address(), &lp_side)?;
+
+    // 转入 LP 代币
+    lp_side.transfer(user_lp_ata, lock_vault, user, instruction_data.amount, &[])?;
+
+    // 累加锁定数量，解锁时间取较晚者
+    let mut lock_data = lock.try_borrow_mut()?;
+    let lock_state = LpLock::load_mut(&mut lock_data)?;
+    let amount = lock_state
+        .amount()
+        .checked_add(instruction_data.amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    lock_state.amount = amount.to_le_bytes();
+    lock_state.unlock_at = lock_state.unlock_at().max(instruction_data.unlock_at).to_le_bytes();
+
+    Ok(())
+}
@@ -0,0 +1,245 @@
+use pinocchio::{
+    cpi::{invoke, invoke_signed, Signer},
+    error::ProgramError,
+    instruction::{InstructionAccount, InstructionView},
+    sysvars::{rent::Rent, Sysvar},
+    AccountView,
+    ProgramResult,
+};
+use pinocchio_system::instructions::{CreateAccount, Transfer};
+use super::token::TOKEN_2022_PROGRAM_ID;
+
+/// LP 名称最大长度（字节）
+pub const MAX_NAME_LEN: usize = 32;
+/// LP 符号最大长度（字节）
+pub const MAX_SYMBOL_LEN: usize = 16;
+/// 元数据 URI 最大长度（字节）
+pub const MAX_URI_LEN: usize = 200;
+
+/// 带 MetadataPointer 扩展的 Mint 大小：
+/// 82（Mint）+ 83（填充）+ 1（账户类型）+ 4（TLV 头）+ 64（扩展数据）
+const MINT_WITH_POINTER_LEN: usize = 234;
+
+/// Token-2022 MetadataPointerExtension 指令
+const METADATA_POINTER_EXTENSION: u8 = 39;
+/// Token-2022 InitializeMint2 指令
+const INITIALIZE_MINT_2: u8 = 20;
+/// spl-token-metadata-interface Initialize 指令标识
+/// sha256("spl_token_metadata_interface:initialize_account")[..8]
+const METADATA_INITIALIZE: [u8; 8] = [210, 225, 30, 162, 88, 184, 77, 141];
+/// spl-token-metadata-interface UpdateField 指令标识
+/// sha256("spl_token_metadata_interface:updating_field")[..8]
+const METADATA_UPDATE_FIELD: [u8; 8] = [221, 233, 49, 45, 181, 202, 220, 200];
+
+/// 元数据字段
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataField {
+    Name = 0u8,
+    Symbol = 1u8,
+    Uri = 2u8,
+}
+
+impl MetadataField {
+    /// 从字节解析字段
+    pub fn from_u8(value: u8) -> Result<Self, ProgramError> {
+        match value {
+            0 => Ok(Self::Name),
+            1 => Ok(Self::Symbol),
+            2 => Ok(Self::Uri),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+
+    /// 字段值的最大长度
+    pub const fn max_len(self) -> usize {
+        match self {
+            Self::Name => MAX_NAME_LEN,
+            Self::Symbol => MAX_SYMBOL_LEN,
+            Self::Uri => MAX_URI_LEN,
+        }
+    }
+}
+
+/// LP 代币元数据
+pub struct LpMetadata<'a> {
+    pub name: &'a [u8],     // 例如 "X-Y LP"
+    pub symbol: &'a [u8],
+    pub uri: &'a [u8],
+}
+
+impl<'a> LpMetadata<'a> {
+    /// 从字节数组解析：name、symbol、uri 依次以 1 字节长度为前缀
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ProgramError> {
+        let (name, rest) = read_field(data, MetadataField::Name)?;
+        let (symbol, rest) = read_field(rest, MetadataField::Symbol)?;
+        let (uri, rest) = read_field(rest, MetadataField::Uri)?;
+        if !rest.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { name, symbol, uri })
+    }
+}
+
+/// 读取一个带 1 字节长度前缀的字段值，并验证长度和 UTF-8 编码
+pub fn read_field(data: &[u8], field: MetadataField) -> Result<(&[u8], &[u8]), ProgramError> {
+    let (&len, rest) = data.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+    let len = len as usize;
+    if len > field.max_len() || rest.len() < len {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (value, rest) = rest.split_at(len);
+    // Token 程序按 UTF-8 字符串反序列化元数据
+    if core::str::from_utf8(value).is_err() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    Ok((value, rest))
+}
+
+/// 以 borsh String 格式（u32 长度 + 内容）写入，返回写入后的位置
+fn write_string(buffer: &mut [u8], offset: usize, value: &[u8]) -> usize {
+    buffer[offset..offset + 4].copy_from_slice(&(value.len() as u32).to_le_bytes());
+    buffer[offset + 4..offset + 4 + value.len()].copy_from_slice(value);
+    offset + 4 + value.len()
+}
+
+/// 创建带元数据的 Token-2022 LP Mint
+///
+/// 依次执行：创建账户、初始化 MetadataPointer（指向 Mint 自身，权限为 config）、
+/// InitializeMint2（铸币权限为 config）、初始化 TokenMetadata（更新权限为 config），
+/// 最后补足元数据扩容后的租金。lp_signers 用于创建 LP Mint PDA，config_signers 用于以铸币权限签名
+#[allow(clippy::too_many_arguments)]
+pub fn create_lp_mint_with_metadata(
+    payer: &AccountView,
+    mint_lp: &AccountView,
+    config: &AccountView,
+    token_program: &AccountView,
+    decimals: u8,
+    metadata: &LpMetadata,
+    lp_signers: &[Signer],
+    config_signers: &[Signer],
+) -> ProgramResult {
+    if token_program.address() != &TOKEN_2022_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    CreateAccount::with_minimum_balance(
+        payer,
+        mint_lp,
+        MINT_WITH_POINTER_LEN as u64,
+        &TOKEN_2022_PROGRAM_ID,
+        None,
+    )?
+    .invoke_signed(lp_signers)?;
+
+    // 指令数据布局：
+    // - [0]: discriminator = 39 (MetadataPointerExtension)
+    // - [1]: 0 (Initialize)
+    // - [2..34]: authority
+    // - [34..66]: metadata_address
+    let mut pointer_data = [0u8; 66];
+    pointer_data[0] = METADATA_POINTER_EXTENSION;
+    pointer_data[2..34].copy_from_slice(config.address().as_ref());
+    pointer_data[34..66].copy_from_slice(mint_lp.address().as_ref());
+    invoke(
+        &InstructionView {
+            program_id: &TOKEN_2022_PROGRAM_ID,
+            accounts: &[InstructionAccount::writable(mint_lp.address())],
+            data: &pointer_data,
+        },
+        &[mint_lp],
+    )?;
+
+    // 指令数据布局：
+    // - [0]: discriminator = 20 (InitializeMint2)
+    // - [1]: decimals
+    // - [2..34]: mint_authority
+    // - [34]: 0（无冻结权限）
+    let mut mint_data = [0u8; 35];
+    mint_data[0] = INITIALIZE_MINT_2;
+    mint_data[1] = decimals;
+    mint_data[2..34].copy_from_slice(config.address().as_ref());
+    invoke(
+        &InstructionView {
+            program_id: &TOKEN_2022_PROGRAM_ID,
+            accounts: &[InstructionAccount::writable(mint_lp.address())],
+            data: &mint_data,
+        },
+        &[mint_lp],
+    )?;
+
+    // 指令数据布局：标识 (8) + name + symbol + uri（borsh String）
+    let mut metadata_data = [0u8; 8 + 12 + MAX_NAME_LEN + MAX_SYMBOL_LEN + MAX_URI_LEN];
+    metadata_data[..8].copy_from_slice(&METADATA_INITIALIZE);
+    let offset = write_string(&mut metadata_data, 8, metadata.name);
+    let offset = write_string(&mut metadata_data, offset, metadata.symbol);
+    let offset = write_string(&mut metadata_data, offset, metadata.uri);
+    invoke_signed(
+        &InstructionView {
+            program_id: &TOKEN_2022_PROGRAM_ID,
+            accounts: &[
+                InstructionAccount::writable(mint_lp.address()),
+                InstructionAccount::readonly(config.address()),
+                InstructionAccount::readonly(mint_lp.address()),
+                InstructionAccount::readonly_signer(config.address()),
+            ],
+            data: &metadata_data[..offset],
+        },
+        &[mint_lp, config, mint_lp, config],
+        config_signers,
+    )?;
+
+    top_up_rent(payer, mint_lp)
+}
+
+/// 更新 LP Mint 的一个元数据字段，更新权限为 config
+pub fn update_lp_metadata_field(
+    payer: &AccountView,
+    mint_lp: &AccountView,
+    config: &AccountView,
+    field: MetadataField,
+    value: &[u8],
+    config_signers: &[Signer],
+) -> ProgramResult {
+    // 指令数据布局：
+    // - [0..8]: 标识 (UpdateField)
+    // - [8]: 字段（borsh 枚举：0 = name，1 = symbol，2 = uri）
+    // - [9..]: 新值（borsh String）
+    let mut instruction_data = [0u8; 9 + 4 + MAX_URI_LEN];
+    instruction_data[..8].copy_from_slice(&METADATA_UPDATE_FIELD);
+    instruction_data[8] = field as u8;
+    let len = write_string(&mut instruction_data, 9, value);
+    invoke_signed(
+        &InstructionView {
+            program_id: &TOKEN_2022_PROGRAM_ID,
+            accounts: &[
+                InstructionAccount::writable(mint_lp.address()),
+                InstructionAccount::readonly_signer(config.address()),
+            ],
+            data: &instruction_data[..len],
+        },
+        &[mint_lp, config],
+        config_signers,
+    )?;
+
+    // 新值更长时 Token 程序会扩容账户
+    top_up_rent(payer, mint_lp)
+}
+
+/// 账户扩容后由 payer 补足免租金所需的 lamports
+fn top_up_rent(payer: &AccountView, account: &AccountView) -> ProgramResult {
+    let minimum_balance = Rent::get()?.try_minimum_balance(account.data_len())?;
+    if account.lamports() < minimum_balance {
+        Transfer {
+            from: payer,
+            to: account,
+            lamports: minimum_balance - account.lamports(),
+        }.invoke()?;
+    }
+
+    Ok(())
+}
+
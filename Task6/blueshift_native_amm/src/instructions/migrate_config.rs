@@ -0,0 +1,87 @@
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+use crate::state::{Config, ConfigLayout};
+
+/// MigrateConfig 指令 - 把旧布局的 Config 升级为当前版本
+///
+/// 账户顺序：
+/// 0. payer (signer, writable) - 支付扩容所需租金
+/// 1. config (writable) - Config 账户
+/// 2. system_program - 系统程序
+///
+/// 旧数据整体移动到账户头之后，新增字段补零，即新功能默认关闭；
+/// 旧布局未记录的 LP Mint 按规范 PDA 补齐。
+/// 升级不改变池子的任何参数，因此任何人都可以执行。已是当前版本时不做修改
+pub fn migrate_config(program_id: &Address, _data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [payer, config, _system_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 payer 是签名者
+    if !payer.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let old_len = config.data_len();
+    {
+        let config_data = config.try_borrow()?;
+        if Config::layout(&config_data)? == ConfigLayout::Versioned(Config::VERSION) {
+            return Ok(());
+        }
+
+        // 旧布局至少包含 PDA 种子，必须能派生出该账户地址，
+        // 防止把本程序拥有的其他账户改写成 Config
+        let body = match Config::layout(&config_data)? {
+            ConfigLayout::Versioned(_) => &config_data[Config::HEADER_LEN..],
+            ConfigLayout::Legacy => &config_data[..],
+        };
+        if body.len() < Config::LEGACY_MIN_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let seed = &body[1..9];
+        let mint_x = &body[41..73];
+        let mint_y = &body[73..105];
+        let config_bump = body[107];
+        let expected = Address::derive_address(
+            &[Config::SEED, seed, mint_x, mint_y],
+            Some(config_bump),
+            program_id,
+        );
+        if config.address() != &expected {
+            return Err(ProgramError::InvalidSeeds);
+        }
+    }
+
+    // 补足扩容后的租金
+    let new_len = old_len.max(Config::ACCOUNT_LEN);
+    let minimum_balance = Rent::get()?.try_minimum_balance(new_len)?;
+    if config.lamports() < minimum_balance {
+        Transfer {
+            from: payer,
+            to: config,
+            lamports: minimum_balance - config.lamports(),
+        }.invoke()?;
+    }
+
+    config.resize(new_len)?;
+
+    let mut config_data = config.try_borrow_mut()?;
+    Config::migrate(&mut config_data, old_len)?;
+
+    // 记录 LP Mint，之后的指令直接与该地址比较
+    let config_state = Config::load_mut(&mut config_data)?;
+    config_state.mint_lp = *config_state.mint_lp_address(config.address(), program_id).as_array();
+
+    Ok(())
+}
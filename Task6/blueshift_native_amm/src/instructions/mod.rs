@@ -2,8 +2,52 @@ pub mod initialize;
 pub mod deposit;
 pub mod withdraw;
 pub mod swap;
+pub mod initialize_settings;
+pub mod update_fee_tiers;
+pub mod update_config;
+pub mod add_allowed_wallet;
+pub mod revoke_allowed_wallet;
+pub mod create_farm;
+pub mod stake;
+pub mod unstake;
+pub mod claim_rewards;
+pub mod lock_lp;
+pub mod unlock_lp;
+pub mod place_virtual_order;
+pub mod execute_virtual_orders;
+pub mod claim_virtual_order;
+pub mod place_limit_order;
+pub mod fill_limit_orders;
+pub mod claim_limit_order;
+pub mod set_pool_state;
+pub mod migrate_config;
+pub mod update_lp_metadata;
+pub mod helpers;
+pub mod metadata;
+pub mod native;
+pub mod token;
 
 pub use initialize::initialize;
 pub use deposit::deposit;
 pub use withdraw::withdraw;
 pub use swap::swap;
+pub use initialize_settings::initialize_settings;
+pub use update_fee_tiers::update_fee_tiers;
+pub use update_config::update_config;
+pub use add_allowed_wallet::add_allowed_wallet;
+pub use revoke_allowed_wallet::revoke_allowed_wallet;
+pub use create_farm::create_farm;
+pub use stake::stake;
+pub use unstake::unstake;
+pub use claim_rewards::claim_rewards;
+pub use lock_lp::lock_lp;
+pub use unlock_lp::unlock_lp;
+pub use place_virtual_order::place_virtual_order;
+pub use execute_virtual_orders::execute_virtual_orders;
+pub use claim_virtual_order::{cancel_virtual_order, claim_virtual_order};
+pub use place_limit_order::place_limit_order;
+pub use fill_limit_orders::fill_limit_orders;
+pub use claim_limit_order::{cancel_limit_order, claim_limit_order};
+pub use set_pool_state::set_pool_state;
+pub use migrate_config::migrate_config;
+pub use update_lp_metadata::update_lp_metadata;
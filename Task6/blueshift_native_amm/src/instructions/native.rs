@@ -0,0 +1,169 @@
+use pinocchio::{
+    cpi::Signer,
+    error::ProgramError,
+    AccountView,
+    Address,
+    ProgramResult,
+};
+use pinocchio_system::instructions::{CreateAccount, Transfer as SystemTransfer};
+use pinocchio_token::instructions::{CloseAccount, InitializeAccount3, SyncNative, Transfer};
+use crate::error::AmmError;
+use super::token::TokenSide;
+
+/// 原生 SOL 的包装 Mint（wSOL）
+pub const NATIVE_MINT: Address = Address::from_str_const("So11111111111111111111111111111111111111112");
+
+/// 标准代币账户大小
+const TOKEN_ACCOUNT_LEN: u64 = 165;
+
+/// 原生 SOL 模式所需的附加账户
+///
+/// 当池子的某一侧是 wSOL，且用户在代币账户的位置直接传入钱包地址时，
+/// 指令改为使用原生 lamports。附加账户按以下顺序追加在指令账户末尾：
+/// 0. system_program - 系统程序（包装时转账 lamports）
+/// 1. native_mint - wSOL Mint（解包时初始化临时账户）
+/// 2. temp_wsol (signer, writable) - 新生成的临时 wSOL 账户，指令结束前关闭
+pub struct NativeAccounts<'a> {
+    pub system_program: &'a AccountView,
+    pub native_mint: Option<&'a AccountView>,
+    pub temp_wsol: Option<&'a AccountView>,
+}
+
+impl<'a> NativeAccounts<'a> {
+    /// 从指令末尾的附加账户解析
+    pub fn try_from_accounts(accounts: &'a [AccountView]) -> Result<Self, ProgramError> {
+        let (system_program, rest) = accounts.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if system_program.address() != &pinocchio_system::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let native_mint = rest.first();
+        if let Some(native_mint) = native_mint {
+            if native_mint.address() != &NATIVE_MINT {
+                return Err(AmmError::InvalidNativeMint.into());
+            }
+        }
+
+        Ok(Self {
+            system_program,
+            native_mint,
+            temp_wsol: rest.get(1),
+        })
+    }
+
+    /// 把用户的原生 lamports 包装进 wSOL 金库
+    pub fn wrap(&self, user: &AccountView, vault: &AccountView, amount: u64) -> ProgramResult {
+        SystemTransfer {
+            from: user,
+            to: vault,
+            lamports: amount,
+        }.invoke()?;
+
+        SyncNative { native_token: vault }.invoke()
+    }
+
+    /// 从 wSOL 金库解包 lamports 给用户
+    ///
+    /// 创建临时 wSOL 账户（owner 为 config），从金库转入后立即关闭，
+    /// 关闭时账户中的全部 lamports（含租金）都退回用户
+    pub fn unwrap(
+        &self,
+        user: &AccountView,
+        vault: &AccountView,
+        config: &AccountView,
+        signers: &[Signer],
+        amount: u64,
+    ) -> ProgramResult {
+        let native_mint = self.native_mint.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let temp_wsol = self.temp_wsol.ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        CreateAccount::with_minimum_balance(
+            user,
+            temp_wsol,
+            TOKEN_ACCOUNT_LEN,
+            &pinocchio_token::ID,
+            None,
+        )?
+        .invoke()?;
+
+        InitializeAccount3 {
+            account: temp_wsol,
+            mint: native_mint,
+            owner: config.address(),
+        }.invoke()?;
+
+        Transfer {
+            from: vault,
+            to: temp_wsol,
+            authority: config,
+            amount,
+        }.invoke_signed(signers)?;
+
+        CloseAccount {
+            account: temp_wsol,
+            destination: user,
+            authority: config,
+        }.invoke_signed(signers)
+    }
+}
+
+/// 用户是否以原生 SOL 参与该侧
+///
+/// 条件：该侧 mint 为 wSOL，且代币账户位置传入的就是用户钱包
+#[inline(always)]
+pub fn is_native(user: &AccountView, user_token_account: &AccountView, mint: &[u8; 32]) -> bool {
+    mint == NATIVE_MINT.as_array() && user_token_account.address() == user.address()
+}
+
+/// 用户代币转入金库，原生 SOL 模式下自动包装
+#[inline(always)]
+pub fn transfer_in(
+    user: &AccountView,
+    user_token_account: &AccountView,
+    vault: &AccountView,
+    side: &TokenSide,
+    native: Option<&NativeAccounts>,
+    amount: u64,
+) -> ProgramResult {
+    match native {
+        Some(native) if is_native(user, user_token_account, side.mint.address().as_array()) => {
+            native.wrap(user, vault, amount)
+        }
+        _ => side.transfer(user_token_account, vault, user, amount, &[]),
+    }
+}
+
+/// 金库代币转给用户（使用 config PDA 签名），原生 SOL 模式下自动解包
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_out(
+    user: &AccountView,
+    user_token_account: &AccountView,
+    vault: &AccountView,
+    side: &TokenSide,
+    config: &AccountView,
+    signers: &[Signer],
+    native: Option<&NativeAccounts>,
+    amount: u64,
+) -> ProgramResult {
+    match native {
+        Some(native) if is_native(user, user_token_account, side.mint.address().as_array()) => {
+            native.unwrap(user, vault, config, signers, amount)
+        }
+        _ => side.transfer(vault, user_token_account, config, amount, signers),
+    }
+}
+
+/// 任一侧使用原生 SOL 时解析附加账户
+#[inline(always)]
+pub fn native_accounts<'a>(
+    user: &AccountView,
+    sides: [(&AccountView, &[u8; 32]); 2],
+    remaining: &'a [AccountView],
+) -> Result<Option<NativeAccounts<'a>>, ProgramError> {
+    if sides.iter().any(|(account, mint)| is_native(user, account, mint)) {
+        NativeAccounts::try_from_accounts(remaining).map(Some)
+    } else {
+        Ok(None)
+    }
+}
@@ -0,0 +1,174 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use core::mem::size_of;
+use crate::error::AmmError;
+use crate::state::{min_order_amount, Config, LimitOrderBook};
+use super::helpers::{check_allowed, check_vault, token_amount};
+use super::token::TokenSide;
+
+/// PlaceLimitOrder 指令数据
+pub struct PlaceLimitOrderInstructionData {
+    pub is_x: bool,     // 是否卖出 X 换 Y
+    pub amount: u64,    // 卖出数量
+    pub price: u128,    // 限价：每单位 X 对应的 Y（乘以 PRICE_PRECISION）
+    pub book_bump: u8,  // 订单簿 PDA 的 bump（池子首个限价单时使用）
+}
+
+impl PlaceLimitOrderInstructionData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != size_of::<u8>() + size_of::<u64>() + size_of::<u128>() + size_of::<u8>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let is_x = data[0] != 0;
+        let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+        let price = u128::from_le_bytes(data[9..25].try_into().unwrap());
+
+        // 验证数据
+        if price == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        if amount == 0 {
+            return Err(AmmError::InvalidAmount.into());
+        }
+
+        Ok(Self {
+            is_x,
+            amount,
+            price,
+            book_bump: data[25],
+        })
+    }
+}
+
+/// PlaceLimitOrder 指令 - 挂限价单
+///
+/// 卖出的代币托管在池子金库中；池子价格越过限价后，订单在 Swap 或
+/// FillLimitOrders 中按曲线成交，边际价格到达限价时停止，剩余部分继续挂单。
+/// 订单簿槽位有限，金库实际收到的数量不得低于 min_order_amount(decimals)
+///
+/// 账户顺序：
+/// 0. user (signer, writable) - 用户
+/// 1. config (writable) - Config 账户
+/// 2. order_book (writable) - 限价单簿 PDA ["orders", config]（不存在时创建）
+/// 3. vault_in (writable) - 卖出代币的金库
+/// 4. user_ata_in (writable) - 用户卖出代币的账户
+/// 5. token_program_in - 卖出代币的 Token 程序
+/// 6. mint_in - 卖出代币的 Mint
+/// 7. system_program - 系统程序
+///
+/// 附加账户：
+/// - allow_entry - 用户的白名单条目 PDA ["allow", config, user]（仅许可池子）
+pub fn place_limit_order(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    // 验证账户数量
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    // 解析账户
+    let user = &accounts[0];
+    let config = &accounts[1];
+    let order_book = &accounts[2];
+    let vault_in = &accounts[3];
+    let user_ata_in = &accounts[4];
+    let token_program_in = &accounts[5];
+    let mint_in = &accounts[6];
+    let _system_program = &accounts[7];
+
+    // 验证 user 是签名者
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // 解析指令数据
+    let instruction_data = PlaceLimitOrderInstructionData::try_from_bytes(data)?;
+    let is_x = instruction_data.is_x;
+
+    // 读取 config 状态并验证卖出侧
+    let config_data = config.try_borrow()?;
+    let config_state = Config::load(&config_data)?;
+    if !config_state.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let expected_mint_in = if is_x { &config_state.mint_x } else { &config_state.mint_y };
+    let side_in = TokenSide::try_new(mint_in, token_program_in, expected_mint_in)?;
+    check_vault(vault_in, config.address(), &side_in)?;
+
+    let permissioned = config_state.is_permissioned();
+    let has_limit_orders = config_state.has_limit_orders();
+    drop(config_data);
+
+    check_allowed(program_id, config, permissioned, user, &accounts[8..])?;
+
+    // 池子的首个限价单：创建订单簿
+    if !has_limit_orders {
+        let bump_binding = [instruction_data.book_bump];
+        let book_seeds = [
+            Seed::from(LimitOrderBook::SEED),
+            Seed::from(config.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        let book_signers = [Signer::from(&book_seeds)];
+
+        CreateAccount::with_minimum_balance(
+            user,
+            order_book,
+            LimitOrderBook::LEN as u64,
+            program_id,
+            None,
+        )?
+        .invoke_signed(&book_signers)?;
+
+        let mut book_data = order_book.try_borrow_mut()?;
+        let book_state = LimitOrderBook::load_mut(&mut book_data)?;
+        book_state.config.copy_from_slice(config.address().as_ref());
+        book_state.bump = instruction_data.book_bump;
+        drop(book_data);
+
+        let mut config_data = config.try_borrow_mut()?;
+        Config::load_mut(&mut config_data)?.limit_orders = 1;
+    }
+
+    // 验证订单簿
+    if !order_book.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    {
+        let book_data = order_book.try_borrow()?;
+        let book_state = LimitOrderBook::load(&book_data)?;
+        if book_state.config != *config.address().as_array()
+            || order_book.address() != &book_state.derive_address(program_id)
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    // 转入卖出数量，按金库实际收到的数量挂单
+    let balance_before = token_amount(vault_in)?;
+    side_in.transfer(user_ata_in, vault_in, user, instruction_data.amount, &[])?;
+    let deposited = token_amount(vault_in)?
+        .checked_sub(balance_before)
+        .ok_or(AmmError::VaultBalanceDecreased)?;
+    if deposited < min_order_amount(side_in.decimals) {
+        return Err(AmmError::OrderTooSmall.into());
+    }
+
+    let mut book_data = order_book.try_borrow_mut()?;
+    LimitOrderBook::load_mut(&mut book_data)?.push(user.address(), is_x, deposited, instruction_data.price)?;
+
+    let mut config_data = config.try_borrow_mut()?;
+    Config::load_mut(&mut config_data)?.adjust_reserved(is_x, deposited, 0)
+}
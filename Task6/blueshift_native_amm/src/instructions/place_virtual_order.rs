@@ -0,0 +1,229 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use core::mem::size_of;
+use blueshift_amm_math::{mul_div_floor, MathError};
+use crate::error::{math_error, AmmError};
+use crate::state::{min_order_amount, Config, VirtualOrder, VirtualOrderPool, ORDER_INTERVAL, RATE_PRECISION};
+use super::helpers::{check_allowed, check_vault, settle_virtual_orders, token_amount};
+use super::token::TokenSide;
+
+/// PlaceVirtualOrder 指令数据
+pub struct PlaceVirtualOrderInstructionData {
+    pub is_x: bool,         // 是否卖出 X 换 Y
+    pub amount: u64,        // 卖出总量
+    pub duration: u64,      // 持续时间（秒），到期时间向上对齐到 ORDER_INTERVAL
+    pub order_id: u64,      // 订单编号，同一用户在同一池子内唯一
+    pub order_bump: u8,     // 订单 PDA 的 bump
+    pub pool_bump: u8,      // 长期订单池 PDA 的 bump（池子首个订单时使用）
+}
+
+impl PlaceVirtualOrderInstructionData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != size_of::<u8>() + size_of::<u64>() * 3 + size_of::<u8>() * 2 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let is_x = data[0] != 0;
+        let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+        let duration = u64::from_le_bytes(data[9..17].try_into().unwrap());
+        let order_id = u64::from_le_bytes(data[17..25].try_into().unwrap());
+
+        // 验证数据
+        if amount == 0 || duration == 0 || duration > i64::MAX as u64 / 2 {
+            return Err(AmmError::InvalidAmount.into());
+        }
+
+        Ok(Self {
+            is_x,
+            amount,
+            duration,
+            order_id,
+            order_bump: data[25],
+            pool_bump: data[26],
+        })
+    }
+}
+
+/// PlaceVirtualOrder 指令 - 创建长期订单
+///
+/// 用户存入卖出总量，订单在到期前以恒定速率卖入池子；
+/// 成交通过 ExecuteVirtualOrders 或下一笔 Swap 结算。
+/// 到期时间槽位有限，金库实际收到的数量不得低于 min_order_amount(decimals)
+///
+/// 账户顺序：
+/// 0. user (signer, writable) - 用户，支付订单租金
+/// 1. config (writable) - Config 账户
+/// 2. order_pool (writable) - 长期订单池 PDA ["twamm", config]（不存在时创建）
+/// 3. order (writable) - 订单 PDA ["twamm_order", config, user, order_id]
+/// 4. vault_x (writable) - X 代币金库（config 的关联代币账户）
+/// 5. vault_y (writable) - Y 代币金库（config 的关联代币账户）
+/// 6. user_ata_in (writable) - 用户卖出代币的账户
+/// 7. token_program_in - 卖出代币的 Token 程序
+/// 8. mint_in - 卖出代币的 Mint
+/// 9. system_program - 系统程序
+///
+/// 附加账户：
+/// - allow_entry - 用户的白名单条目 PDA ["allow", config, user]（仅许可池子）
+pub fn place_virtual_order(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    // 验证账户数量
+    if accounts.len() < 10 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    // 解析账户
+    let user = &accounts[0];
+    let config = &accounts[1];
+    let order_pool = &accounts[2];
+    let order = &accounts[3];
+    let vault_x = &accounts[4];
+    let vault_y = &accounts[5];
+    let user_ata_in = &accounts[6];
+    let token_program_in = &accounts[7];
+    let mint_in = &accounts[8];
+    let _system_program = &accounts[9];
+
+    // 验证 user 是签名者
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // 解析指令数据
+    let instruction_data = PlaceVirtualOrderInstructionData::try_from_bytes(data)?;
+    let is_x = instruction_data.is_x;
+
+    // 读取 config 状态并验证卖出侧金库，两个金库都由 settle_virtual_orders 验证
+    let config_data = config.try_borrow()?;
+    let config_state = Config::load(&config_data)?;
+    if !config_state.is_initialized() {
+        return Err(AmmError::PoolNotInitialized.into());
+    }
+
+    let (vault_in, expected_mint_in) = if is_x {
+        (vault_x, &config_state.mint_x)
+    } else {
+        (vault_y, &config_state.mint_y)
+    };
+    let side_in = TokenSide::try_new(mint_in, token_program_in, expected_mint_in)?;
+    check_vault(vault_in, config.address(), &side_in)?;
+
+    let permissioned = config_state.is_permissioned();
+    let has_virtual_orders = config_state.has_virtual_orders();
+    drop(config_data);
+
+    check_allowed(program_id, config, permissioned, user, &accounts[10..])?;
+
+    let now = Clock::get()?.unix_timestamp;
+
+    // 池子的首个长期订单：创建订单池
+    if !has_virtual_orders {
+        let bump_binding = [instruction_data.pool_bump];
+        let pool_seeds = [
+            Seed::from(VirtualOrderPool::SEED),
+            Seed::from(config.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        let pool_signers = [Signer::from(&pool_seeds)];
+
+        CreateAccount::with_minimum_balance(
+            user,
+            order_pool,
+            VirtualOrderPool::LEN as u64,
+            program_id,
+            None,
+        )?
+        .invoke_signed(&pool_signers)?;
+
+        let mut pool_data = order_pool.try_borrow_mut()?;
+        let pool_state = VirtualOrderPool::load_mut(&mut pool_data)?;
+        pool_state.config.copy_from_slice(config.address().as_ref());
+        pool_state.last_executed = now.to_le_bytes();
+        pool_state.bump = instruction_data.pool_bump;
+        drop(pool_data);
+
+        let mut config_data = config.try_borrow_mut()?;
+        Config::load_mut(&mut config_data)?.virtual_orders = 1;
+    }
+
+    // 1. 先把已有订单结算到当前时间
+    settle_virtual_orders(program_id, config, order_pool, vault_x, vault_y, now)?;
+
+    // 2. 转入卖出总量，按金库实际收到的数量计算速率
+    let balance_before = token_amount(vault_in)?;
+    side_in.transfer(user_ata_in, vault_in, user, instruction_data.amount, &[])?;
+    let deposited = token_amount(vault_in)?
+        .checked_sub(balance_before)
+        .ok_or(AmmError::VaultBalanceDecreased)?;
+    if deposited < min_order_amount(side_in.decimals) {
+        return Err(AmmError::OrderTooSmall.into());
+    }
+
+    // 到期时间向上对齐到 ORDER_INTERVAL，使同一时间到期的订单可以合并
+    let end_at = (now + instruction_data.duration as i64 + ORDER_INTERVAL - 1) / ORDER_INTERVAL * ORDER_INTERVAL;
+    let sell_rate = mul_div_floor(deposited as u128, RATE_PRECISION, (end_at - now) as u128)
+        .ok_or(math_error(MathError::OrderRateOverflow))?;
+    if sell_rate == 0 {
+        return Err(AmmError::OrderTooSmall.into());
+    }
+
+    // 3. 加入订单池，并把存入的数量记为属于订单
+    let (earnings_start, sold_start) = {
+        let mut config_data = config.try_borrow_mut()?;
+        let mut pool_data = order_pool.try_borrow_mut()?;
+        VirtualOrderPool::load_mut(&mut pool_data)?.add_order(
+            Config::load_mut(&mut config_data)?,
+            is_x,
+            deposited,
+            sell_rate,
+            end_at,
+        )?
+    };
+
+    // 4. 创建订单账户
+    let order_id_bytes = instruction_data.order_id.to_le_bytes();
+    let bump_binding = [instruction_data.order_bump];
+    let order_seeds = [
+        Seed::from(VirtualOrder::SEED),
+        Seed::from(config.address().as_ref()),
+        Seed::from(user.address().as_ref()),
+        Seed::from(&order_id_bytes),
+        Seed::from(&bump_binding),
+    ];
+    let order_signers = [Signer::from(&order_seeds)];
+
+    CreateAccount::with_minimum_balance(
+        user,
+        order,
+        VirtualOrder::LEN as u64,
+        program_id,
+        None,
+    )?
+    .invoke_signed(&order_signers)?;
+
+    let mut order_data = order.try_borrow_mut()?;
+    let order_state = VirtualOrder::load_mut(&mut order_data)?;
+    order_state.config.copy_from_slice(config.address().as_ref());
+    order_state.owner.copy_from_slice(user.address().as_ref());
+    order_state.order_id = order_id_bytes;
+    order_state.is_x = is_x as u8;
+    order_state.amount = deposited.to_le_bytes();
+    order_state.sell_rate = sell_rate.to_le_bytes();
+    order_state.earnings_start = earnings_start.to_le_bytes();
+    order_state.sold_start = sold_start.to_le_bytes();
+    order_state.end_at = end_at.to_le_bytes();
+    order_state.bump = instruction_data.order_bump;
+
+    Ok(())
+}
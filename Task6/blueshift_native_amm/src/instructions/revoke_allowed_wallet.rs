@@ -0,0 +1,62 @@
+use pinocchio::{
+    error::ProgramError,
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::state::{AllowListEntry, Config};
+
+/// RevokeAllowedWallet 指令 - 把钱包移出许可池子的白名单
+///
+/// 关闭白名单条目账户，租金退回管理权限
+///
+/// 账户顺序：
+/// 0. authority (signer, writable) - 池子管理权限
+/// 1. config - Config 账户
+/// 2. entry (writable) - 白名单条目 PDA ["allow", config, wallet]
+pub fn revoke_allowed_wallet(program_id: &Address, _data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [authority, config, entry, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 authority 是签名者
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有，且 authority 为池子管理权限
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    {
+        let config_data = config.try_borrow()?;
+        if !Config::load(&config_data)?.is_authority(authority.address()) {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+    }
+
+    // 验证条目属于该池子
+    if !entry.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    {
+        let entry_data = entry.try_borrow()?;
+        let entry_state = AllowListEntry::load(&entry_data)?;
+        if entry_state.config != *config.address().as_array()
+            || entry.address() != &entry_state.derive_address(program_id)
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    // 关闭条目，租金退回管理权限
+    let lamports = entry.lamports();
+    authority.set_lamports(
+        authority
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+    entry.set_lamports(0);
+    entry.close()
+}
@@ -0,0 +1,99 @@
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::error::AmmError;
+use crate::state::{AmmState, Config};
+use super::helpers::settle_virtual_orders;
+
+/// SetPoolState 指令数据
+pub struct SetPoolStateInstructionData {
+    pub state: u8,  // 目标状态：1 = 已初始化（恢复交易），2 = 已禁用，3 = 仅限提取
+}
+
+impl SetPoolStateInstructionData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        let [state] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+
+        if *state != AmmState::Initialized as u8
+            && *state != AmmState::Disabled as u8
+            && *state != AmmState::WithdrawOnly as u8
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { state: *state })
+    }
+}
+
+/// SetPoolState 指令 - 暂停或恢复池子
+///
+/// 账户顺序：
+/// 0. signer (signer) - 池子管理权限或暂停权限
+/// 1. config (writable) - Config 账户
+/// 2. order_pool (writable) - 长期订单池 PDA ["twamm", config]（仅已创建长期订单池的池子）
+/// 3. vault_x - X 代币金库（config 的关联代币账户，仅已创建长期订单池的池子）
+/// 4. vault_y - Y 代币金库（config 的关联代币账户，仅已创建长期订单池的池子）
+///
+/// 暂停权限（Config.guardian）只能把池子切换为已禁用或仅限提取，
+/// 用于在不持有管理密钥的情况下立即停止交易；恢复交易只能由池子管理权限执行。
+/// 切换前先按原状态把长期订单结算到当前时间：暂停前的时段正常卖出，
+/// 暂停期间的时段不会在恢复后被补卖
+pub fn set_pool_state(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [signer, config, remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 signer 是签名者
+    if !signer.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // 解析指令数据
+    let instruction_data = SetPoolStateInstructionData::try_from_bytes(data)?;
+
+    let has_virtual_orders = {
+        let config_data = config.try_borrow()?;
+        let config_state = Config::load(&config_data)?;
+
+        // 未初始化的池子不能切换状态
+        if config_state.state == AmmState::Uninitialized as u8 {
+            return Err(AmmError::PoolNotInitialized.into());
+        }
+
+        // 管理权限可以切换到任意状态，暂停权限只能停止交易
+        let pausing = instruction_data.state != AmmState::Initialized as u8;
+        let is_authority = config_state.is_authority(signer.address());
+        let allowed = if pausing { is_authority || config_state.is_guardian(signer.address()) } else { is_authority };
+        if !allowed {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+
+        config_state.has_virtual_orders()
+    };
+
+    // 按原状态把长期订单结算到当前时间
+    if has_virtual_orders {
+        let [order_pool, vault_x, vault_y, ..] = remaining else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let now = Clock::get()?.unix_timestamp;
+        settle_virtual_orders(program_id, config, order_pool, vault_x, vault_y, now)?;
+    }
+
+    let mut config_data = config.try_borrow_mut()?;
+    Config::load_mut(&mut config_data)?.state = instruction_data.state;
+
+    Ok(())
+}
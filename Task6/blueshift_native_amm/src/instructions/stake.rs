@@ -0,0 +1,131 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use crate::error::AmmError;
+use crate::state::{Farm, StakePosition};
+use super::helpers::{check_farm, check_position, check_vault, reward_balance};
+use super::token::TokenSide;
+
+/// Stake 指令数据
+pub struct StakeInstructionData {
+    pub amount: u64,        // 质押的 LP 数量
+    pub position_bump: u8,  // 仓位 PDA ["stake", farm, user] 的 bump（首次质押时使用）
+}
+
+impl StakeInstructionData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        // 8 + 1 = 9
+        if data.len() != 9 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        if amount == 0 {
+            return Err(AmmError::InvalidAmount.into());
+        }
+
+        Ok(Self {
+            amount,
+            position_bump: data[8],
+        })
+    }
+}
+
+/// Stake 指令 - 把 LP 代币质押进 farm
+///
+/// 首次质押时创建用户的仓位 PDA；每次质押前先结算已累积的奖励，
+/// 累积的奖励不超过奖励金库中尚未分配的余额
+///
+/// 账户顺序：
+/// 0. user (signer, writable) - 用户，首次质押时支付仓位租金
+/// 1. farm (writable) - Farm PDA ["farm", mint_lp, reward_mint]
+/// 2. position (writable) - 仓位 PDA ["stake", farm, user]
+/// 3. user_lp_ata (writable) - 用户的 LP 代币账户
+/// 4. stake_vault (writable) - 质押金库（farm 的 LP 关联代币账户）
+/// 5. mint_lp - LP Token Mint
+/// 6. token_program - LP Mint 的 Token 程序
+/// 7. system_program - 系统程序
+/// 8. reward_vault - 奖励金库（farm 的奖励代币关联账户）
+pub fn stake(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [user, farm, position, user_lp_ata, stake_vault, mint_lp, token_program, _system_program, reward_vault, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 user 是签名者
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 解析指令数据
+    let instruction_data = StakeInstructionData::try_from_bytes(data)?;
+
+    // 验证 farm、LP Mint、质押金库和奖励金库
+    check_farm(program_id, farm)?;
+    let reward_balance = reward_balance(farm, reward_vault)?;
+    let lp_side = {
+        let farm_data = farm.try_borrow()?;
+        TokenSide::try_new(mint_lp, token_program, &Farm::load(&farm_data)?.mint_lp)?
+    };
+    check_vault(stake_vault, farm.address(), &lp_side)?;
+
+    // 首次质押时创建仓位，否则验证仓位归属
+    if position.data_len() == 0 {
+        let bump_binding = [instruction_data.position_bump];
+        let position_seeds = [
+            Seed::from(StakePosition::SEED),
+            Seed::from(farm.address().as_ref()),
+            Seed::from(user.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        let position_signers = [Signer::from(&position_seeds)];
+
+        CreateAccount::with_minimum_balance(
+            user,
+            position,
+            StakePosition::LEN as u64,
+            program_id,
+            None,
+        )?
+        .invoke_signed(&position_signers)?;
+
+        let mut position_data = position.try_borrow_mut()?;
+        let position_state = StakePosition::load_mut(&mut position_data)?;
+        position_state.farm.copy_from_slice(farm.address().as_ref());
+        position_state.owner.copy_from_slice(user.address().as_ref());
+        position_state.bump = instruction_data.position_bump;
+    } else {
+        check_position(program_id, farm, position, user)?;
+    }
+
+    // 转入 LP 代币
+    lp_side.transfer(user_lp_ata, stake_vault, user, instruction_data.amount, &[])?;
+
+    // 推进累加器，结算奖励后增加质押数量
+    let now = Clock::get()?.unix_timestamp;
+    let mut farm_data = farm.try_borrow_mut()?;
+    let farm_state = Farm::load_mut(&mut farm_data)?;
+    farm_state.update(now, reward_balance)?;
+
+    let mut position_data = position.try_borrow_mut()?;
+    let position_state = StakePosition::load_mut(&mut position_data)?;
+    let amount = position_state
+        .amount()
+        .checked_add(instruction_data.amount)
+        .ok_or(AmmError::RewardOverflow)?;
+    position_state.settle(farm_state.acc_reward_per_share(), amount)?;
+
+    let total_staked = farm_state
+        .total_staked()
+        .checked_add(instruction_data.amount)
+        .ok_or(AmmError::RewardOverflow)?;
+    farm_state.total_staked = total_staked.to_le_bytes();
+
+    Ok(())
+}
@@ -5,9 +5,21 @@ use pinocchio::{
     AccountView,
     ProgramResult,
 };
-use pinocchio_token::instructions::Transfer;
 use core::mem::size_of;
-use crate::state::Config;
+use blueshift_amm_math::{price_change_bps, quote_swap, MathError};
+use crate::{
+    error::{math_error, AmmError},
+    event::{emit, SwapEvent},
+    state::{Config, PriceFeed},
+};
+use super::{
+    helpers::{
+        check_allowed, check_expiration, check_pool_state, check_vault, fill_crossed_orders,
+        settle_virtual_orders, split_optional, token_amount,
+    },
+    native::{is_native, native_accounts, transfer_in, transfer_out},
+    token::TokenSide,
+};
 
 /// Swap 指令数据
 pub struct SwapInstructionData {
@@ -31,7 +43,7 @@ impl SwapInstructionData {
 
         // 验证数据
         if amount == 0 || min == 0 {
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(AmmError::InvalidAmount.into());
         }
 
         Ok(Self {
@@ -46,16 +58,33 @@ impl SwapInstructionData {
 /// Swap 指令 - 代币交换
 /// 
 /// 账户顺序：
-/// 0. user (signer) - 用户
-/// 1. config - Config 账户
-/// 2. vault_x (writable) - X 代币金库
-/// 3. vault_y (writable) - Y 代币金库
-/// 4. user_x_ata (writable) - 用户的 X 代币账户
-/// 5. user_y_ata (writable) - 用户的 Y 代币账户
-/// 6. token_program - Token 程序
-pub fn swap(_program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+/// 0. user (signer, writable) - 用户
+/// 1. config (writable) - Config 账户（记录波动率和 slot 内累计价格变化）
+/// 2. vault_x (writable) - X 代币金库（config 的关联代币账户）
+/// 3. vault_y (writable) - Y 代币金库（config 的关联代币账户）
+/// 4. user_x_ata (writable) - 用户的 X 代币账户（原生 SOL 时传入用户钱包）
+/// 5. user_y_ata (writable) - 用户的 Y 代币账户（原生 SOL 时传入用户钱包）
+/// 6. token_program_x - X 代币的 Token 程序（Token 或 Token-2022）
+/// 7. token_program_y - Y 代币的 Token 程序（Token 或 Token-2022）
+/// 8. mint_x - 代币 X 的 Mint
+/// 9. mint_y - 代币 Y 的 Mint
+///
+/// 附加账户（按顺序追加）：
+/// - order_pool (writable) - 长期订单池 PDA ["twamm", config]（仅已创建长期订单池的池子）
+/// - order_book (writable) - 限价单簿 PDA ["orders", config]（仅已创建限价单簿的池子）
+/// - price_feed - 参考价格源（仅设置了参考价格保护的池子，地址见 Config.oracle）
+/// - allow_entry - 用户的白名单条目 PDA ["allow", config, user]（仅许可池子）
+/// - system_program - 系统程序（仅原生 SOL 模式）
+/// - native_mint - wSOL Mint（仅原生 SOL 输出时）
+/// - temp_wsol (signer, writable) - 临时 wSOL 账户（仅原生 SOL 输出时，指令内关闭）
+///
+/// 输入侧按金库实际收到的数量定价，因此带转账费扩展的 Mint 也能正确报价；
+/// `min` 约束的是用户实际收到的数量。定价前先结算长期订单，交换后撮合
+/// 价格已被越过的限价单，储备为金库余额扣除属于订单的部分。
+/// 设置了参考价格保护的池子在报价后检查价格源的有效期和成交价偏离
+pub fn swap(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
     // 验证账户数量
-    if accounts.len() < 7 {
+    if accounts.len() < 10 {
         return Err(ProgramError::NotEnoughAccountKeys);
     }
 
@@ -66,34 +95,55 @@ pub fn swap(_program_id: &Address, data: &[u8], accounts: &[AccountView]) -> Pro
     let vault_y = &accounts[3];
     let user_x_ata = &accounts[4];
     let user_y_ata = &accounts[5];
-    let _token_program = &accounts[6];
+    let token_program_x = &accounts[6];
+    let token_program_y = &accounts[7];
+    let mint_x = &accounts[8];
+    let mint_y = &accounts[9];
 
     // 验证 user 是签名者
     if !user.is_signer() {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
     // 解析指令数据
     let instruction_data = SwapInstructionData::try_from_bytes(data)?;
 
-    // 读取 config 状态
+    // 验证未过期
+    let clock = check_expiration(instruction_data.expiration)?;
+    let now = clock.unix_timestamp;
+
+    // 读取 config 状态（旧布局缺少的字段按关闭处理，无需先迁移）
     let config_data = config.try_borrow()?;
-    let config_state = Config::load(&config_data)?;
+    let config_state = &Config::read(&config_data)?;
 
     // 验证 AMM 状态
-    if !config_state.is_initialized() {
-        return Err(ProgramError::UninitializedAccount);
-    }
+    check_pool_state(config_state, false)?;
+
+    // 验证两侧 Mint、Token 程序和金库
+    let side_x = TokenSide::try_new(mint_x, token_program_x, &config_state.mint_x)?;
+    let side_y = TokenSide::try_new(mint_y, token_program_y, &config_state.mint_y)?;
+    check_vault(vault_x, config.address(), &side_x)?;
+    check_vault(vault_y, config.address(), &side_y)?;
+
+    let fee = config_state.effective_fee(now);
+    let (weight_x, weight_y) = config_state.weights(now);
+    let permissioned = config_state.is_permissioned();
+    let has_virtual_orders = config_state.has_virtual_orders();
+    let has_limit_orders = config_state.has_limit_orders();
+    let has_oracle = config_state.has_oracle();
 
-    // 简化版本：直接按固定比例交换
-    // 实际实现需要使用 constant-product-curve 计算精确金额和费用
-    
     // 创建 PDA 签名种子
-    let seed_bytes = config_state.seed.to_le_bytes();
+    let seed_bytes = config_state.seed;
     let config_bump_binding = [config_state.config_bump];
     let mint_x_address = config_state.mint_x_address();
     let mint_y_address = config_state.mint_y_address();
-    
+    drop(config_data);
+
     let config_seeds = [
         Seed::from(b"config"),
         Seed::from(&seed_bytes),
@@ -102,39 +152,120 @@ pub fn swap(_program_id: &Address, data: &[u8], accounts: &[AccountView]) -> Pro
         Seed::from(&config_bump_binding),
     ];
     let config_signers = [Signer::from(&config_seeds)];
-    
-    if instruction_data.is_x {
-        // X -> Y: 用户转入 X，接收 Y
-        Transfer {
-            from: user_x_ata,
-            to: vault_x,
-            authority: user,
-            amount: instruction_data.amount,
-        }.invoke()?;
-
-        // 从金库转出 Y（使用 PDA 签名）
-        Transfer {
-            from: vault_y,
-            to: user_y_ata,
-            authority: config,
-            amount: instruction_data.min,
-        }.invoke_signed(&config_signers)?;
+
+    // 已创建长期订单池的池子：先把长期订单结算到当前时间
+    let (order_pool, remaining) = split_optional(has_virtual_orders, &accounts[10..])?;
+    if let Some(order_pool) = order_pool {
+        settle_virtual_orders(program_id, config, order_pool, vault_x, vault_y, now)?;
+    }
+    let (order_book, remaining) = split_optional(has_limit_orders, remaining)?;
+    let (price_feed, remaining) = split_optional(has_oracle, remaining)?;
+
+    // 许可池子需要白名单条目，其后为原生 SOL 模式的附加账户
+    let native = native_accounts(
+        user,
+        [(user_x_ata, mint_x_address.as_array()), (user_y_ata, mint_y_address.as_array())],
+        check_allowed(program_id, config, permissioned, user, remaining)?,
+    )?;
+
+    // 按方向整理输入/输出两侧
+    let (side_in, vault_in, user_in, side_out, vault_out, user_out) = if instruction_data.is_x {
+        (&side_x, vault_x, user_x_ata, &side_y, vault_y, user_y_ata)
     } else {
-        // Y -> X: 用户转入 Y，接收 X
-        Transfer {
-            from: user_y_ata,
-            to: vault_y,
-            authority: user,
-            amount: instruction_data.amount,
-        }.invoke()?;
-
-        // 从金库转出 X（使用 PDA 签名）
-        Transfer {
-            from: vault_x,
-            to: user_x_ata,
-            authority: config,
-            amount: instruction_data.min,
-        }.invoke_signed(&config_signers)?;
+        (&side_y, vault_y, user_y_ata, &side_x, vault_x, user_x_ata)
+    };
+
+    // 储备不含属于订单的代币
+    let (reserve_in, reserve_out) = {
+        let config_data = config.try_borrow()?;
+        let (reserve_x, reserve_y) =
+            Config::read(&config_data)?.reserves(token_amount(vault_x)?, token_amount(vault_y)?);
+        if instruction_data.is_x { (reserve_x, reserve_y) } else { (reserve_y, reserve_x) }
+    };
+
+    // 1. 用户转入，按金库实际收到的数量定价（扣除 Token-2022 转账费）
+    let balance_in = token_amount(vault_in)?;
+    transfer_in(user, user_in, vault_in, side_in, native.as_ref(), instruction_data.amount)?;
+    let amount_in = token_amount(vault_in)?
+        .checked_sub(balance_in)
+        .ok_or(AmmError::VaultBalanceDecreased)?;
+    let new_reserve_in = reserve_in
+        .checked_add(amount_in)
+        .ok_or(math_error(MathError::ReserveOverflow))?;
+
+    // 2. 按（加权）恒定乘积曲线和当前生效费用计算输出
+    let (weight_in, weight_out) = if instruction_data.is_x { (weight_x, weight_y) } else { (weight_y, weight_x) };
+    let quote = quote_swap(reserve_in, reserve_out, weight_in, weight_out, amount_in, fee).map_err(math_error)?;
+    let amount_out = quote.amount_out;
+    let new_reserve_out = reserve_out - amount_out;
+
+    // 参考价格保护：价格源未过期且成交价偏离不超过上限
+    if let Some(price_feed) = price_feed {
+        let feed_data = price_feed.try_borrow()?;
+        let config_data = config.try_borrow()?;
+        Config::read(&config_data)?.check_oracle(
+            price_feed.address(),
+            PriceFeed::load(&feed_data)?,
+            now,
+            instruction_data.is_x,
+            amount_in,
+            amount_out,
+        )?;
+    }
+
+    // 3. 价格影响熔断，并记录本次交换造成的价格波动（仅动态费用模式）
+    let (reserve_x, reserve_y, new_x, new_y) = if instruction_data.is_x {
+        (reserve_in, reserve_out, new_reserve_in, new_reserve_out)
+    } else {
+        (reserve_out, reserve_in, new_reserve_out, new_reserve_in)
+    };
+    let price_change = price_change_bps(reserve_x, reserve_y, new_x, new_y);
+    {
+        let mut config_data = config.try_borrow_mut()?;
+        let mut config_state = Config::read(&config_data)?;
+        config_state.check_price_impact(clock.slot, price_change)?;
+        config_state.record_price_move(now, price_change);
+        config_state.write(&mut config_data)?;
+    }
+
+    // 4. 从金库转出（使用 PDA 签名），按用户实际收到的数量做滑点保护
+    let user_native = is_native(user, user_out, side_out.mint.address().as_array());
+    let balance_before = if user_native { 0 } else { token_amount(user_out)? };
+    transfer_out(
+        user,
+        user_out,
+        vault_out,
+        side_out,
+        config,
+        &config_signers,
+        native.as_ref(),
+        amount_out,
+    )?;
+    let received = if user_native {
+        amount_out
+    } else {
+        token_amount(user_out)?.saturating_sub(balance_before)
+    };
+
+    if received < instruction_data.min {
+        return Err(AmmError::SlippageExceeded.into());
+    }
+
+    // 5. 输出交换事件
+    emit(&SwapEvent {
+        pool: *config.address().as_array(),
+        user: *user.address().as_array(),
+        is_x: instruction_data.is_x,
+        amount_in,
+        amount_out,
+        fee: quote.fee,
+        reserve_x: new_x,
+        reserve_y: new_y,
+    }.to_bytes());
+
+    // 6. 撮合被本次交换越过限价的订单
+    if let Some(order_book) = order_book {
+        fill_crossed_orders(program_id, config, order_book, vault_x, vault_y, now)?;
     }
 
     Ok(())
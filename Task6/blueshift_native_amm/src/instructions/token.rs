@@ -0,0 +1,157 @@
+use pinocchio::{
+    cpi::{invoke_signed, Signer},
+    error::ProgramError,
+    instruction::{InstructionAccount, InstructionView},
+    AccountView,
+    Address,
+    ProgramResult,
+};
+use crate::error::AmmError;
+
+/// Token-2022 程序 ID
+pub const TOKEN_2022_PROGRAM_ID: Address =
+    Address::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Mint 账户中 decimals 字段的偏移（两个 Token 程序相同）
+const MINT_DECIMALS_OFFSET: usize = 44;
+/// 标准 Mint 账户大小
+const MINT_LEN: usize = 82;
+
+/// 是否为受支持的 Token 程序（旧版 Token 或 Token-2022）
+#[inline(always)]
+pub fn is_token_program(address: &Address) -> bool {
+    address == &pinocchio_token::ID || address == &TOKEN_2022_PROGRAM_ID
+}
+
+/// 池子一侧代币的 Mint 及其所属 Token 程序
+///
+/// 所有代币转账都通过传入的 Token 程序执行 TransferChecked，
+/// 因此同时支持旧版 Token 和 Token-2022（含扩展）的 Mint
+pub struct TokenSide<'a> {
+    pub mint: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub decimals: u8,
+}
+
+impl<'a> TokenSide<'a> {
+    /// 验证 Mint 与 Config 一致、由传入的 Token 程序拥有，并读取 decimals
+    pub fn try_new(
+        mint: &'a AccountView,
+        token_program: &'a AccountView,
+        expected_mint: &[u8; 32],
+    ) -> Result<Self, ProgramError> {
+        if mint.address().as_array() != expected_mint {
+            return Err(AmmError::InvalidMint.into());
+        }
+        if !is_token_program(token_program.address()) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if !mint.owned_by(token_program.address()) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let data = mint.try_borrow()?;
+        if data.len() < MINT_LEN {
+            return Err(AmmError::InvalidMint.into());
+        }
+        let decimals = data[MINT_DECIMALS_OFFSET];
+
+        Ok(Self {
+            mint,
+            token_program,
+            decimals,
+        })
+    }
+
+    /// 通过该侧的 Token 程序执行 TransferChecked
+    pub fn transfer(
+        &self,
+        from: &AccountView,
+        to: &AccountView,
+        authority: &AccountView,
+        amount: u64,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let instruction_accounts: [InstructionAccount; 4] = [
+            InstructionAccount::writable(from.address()),
+            InstructionAccount::readonly(self.mint.address()),
+            InstructionAccount::writable(to.address()),
+            InstructionAccount::readonly_signer(authority.address()),
+        ];
+
+        // 指令数据布局：
+        // - [0]: discriminator = 12 (TransferChecked)
+        // - [1..9]: amount (u64)
+        // - [9]: decimals (u8)
+        let mut instruction_data = [0u8; 10];
+        instruction_data[0] = 12;
+        instruction_data[1..9].copy_from_slice(&amount.to_le_bytes());
+        instruction_data[9] = self.decimals;
+
+        let instruction = InstructionView {
+            program_id: self.token_program.address(),
+            accounts: &instruction_accounts,
+            data: &instruction_data,
+        };
+
+        invoke_signed(&instruction, &[from, self.mint, to, authority], signers)
+    }
+
+    /// 通过该侧的 Token 程序执行 MintToChecked
+    pub fn mint_to(
+        &self,
+        to: &AccountView,
+        mint_authority: &AccountView,
+        amount: u64,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let instruction_accounts: [InstructionAccount; 3] = [
+            InstructionAccount::writable(self.mint.address()),
+            InstructionAccount::writable(to.address()),
+            InstructionAccount::readonly_signer(mint_authority.address()),
+        ];
+
+        // 指令数据布局：
+        // - [0]: discriminator = 14 (MintToChecked)
+        // - [1..9]: amount (u64)
+        // - [9]: decimals (u8)
+        let mut instruction_data = [0u8; 10];
+        instruction_data[0] = 14;
+        instruction_data[1..9].copy_from_slice(&amount.to_le_bytes());
+        instruction_data[9] = self.decimals;
+
+        let instruction = InstructionView {
+            program_id: self.token_program.address(),
+            accounts: &instruction_accounts,
+            data: &instruction_data,
+        };
+
+        invoke_signed(&instruction, &[self.mint, to, mint_authority], signers)
+    }
+
+    /// 通过该侧的 Token 程序执行 BurnChecked
+    pub fn burn(&self, from: &AccountView, authority: &AccountView, amount: u64) -> ProgramResult {
+        let instruction_accounts: [InstructionAccount; 3] = [
+            InstructionAccount::writable(from.address()),
+            InstructionAccount::writable(self.mint.address()),
+            InstructionAccount::readonly_signer(authority.address()),
+        ];
+
+        // 指令数据布局：
+        // - [0]: discriminator = 15 (BurnChecked)
+        // - [1..9]: amount (u64)
+        // - [9]: decimals (u8)
+        let mut instruction_data = [0u8; 10];
+        instruction_data[0] = 15;
+        instruction_data[1..9].copy_from_slice(&amount.to_le_bytes());
+        instruction_data[9] = self.decimals;
+
+        let instruction = InstructionView {
+            program_id: self.token_program.address(),
+            accounts: &instruction_accounts,
+            data: &instruction_data,
+        };
+
+        invoke_signed(&instruction, &[from, self.mint, authority], &[])
+    }
+}
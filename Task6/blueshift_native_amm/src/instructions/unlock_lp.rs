@@ -0,0 +1,70 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::state::LpLock;
+use super::helpers::{check_lock, check_vault};
+use super::token::TokenSide;
+
+/// UnlockLp 指令 - 解锁时间到达后取回全部锁定的 LP 代币
+///
+/// 锁仓账户保留，之后可以再次锁定
+///
+/// 账户顺序：
+/// 0. user (signer) - 锁仓所有者
+/// 1. lock (writable) - 锁仓 PDA ["lock", mint_lp, user]
+/// 2. user_lp_ata (writable) - 用户的 LP 代币账户
+/// 3. lock_vault (writable) - 锁仓金库（lock 的 LP 关联代币账户）
+/// 4. mint_lp - LP Token Mint
+/// 5. token_program - LP Mint 的 Token 程序
+pub fn unlock_lp(program_id: &Address, _data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [user, lock, user_lp_ata, lock_vault, mint_lp, token_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 user 是签名者
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证锁仓、LP Mint 和锁仓金库
+    check_lock(program_id, lock, user)?;
+
+    let mut lock_data = lock.try_borrow_mut()?;
+    let lock_state = LpLock::load_mut(&mut lock_data)?;
+    let lp_side = TokenSide::try_new(mint_lp, token_program, &lock_state.mint_lp)?;
+    check_vault(lock_vault, lock.address(), &lp_side)?;
+
+    // 解锁时间之前拒绝取回
+    let now = Clock::get()?.unix_timestamp;
+    if lock_state.is_locked(now) {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let amount = lock_state.amount();
+    if amount == 0 {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    lock_state.amount = [0u8; 8];
+
+    // 复制签名种子后释放借用，CPI 需要使用 lock 账户
+    let mint_lp_bytes = lock_state.mint_lp;
+    let owner_bytes = lock_state.owner;
+    let bump_binding = [lock_state.bump];
+    drop(lock_data);
+
+    let lock_seeds = [
+        Seed::from(LpLock::SEED),
+        Seed::from(&mint_lp_bytes),
+        Seed::from(&owner_bytes),
+        Seed::from(&bump_binding),
+    ];
+    let lock_signers = [Signer::from(&lock_seeds)];
+
+    // 转出 LP 代币（使用 lock PDA 签名）
+    lp_side.transfer(lock_vault, user_lp_ata, lock, amount, &lock_signers)
+}
@@ -0,0 +1,107 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::error::AmmError;
+use crate::state::{Farm, StakePosition};
+use super::helpers::{check_farm, check_position, check_vault, reward_balance};
+use super::token::TokenSide;
+
+/// Unstake 指令数据
+pub struct UnstakeInstructionData {
+    pub amount: u64,    // 取回的 LP 数量
+}
+
+impl UnstakeInstructionData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != 8 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        if amount == 0 {
+            return Err(AmmError::InvalidAmount.into());
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+/// Unstake 指令 - 从 farm 取回质押的 LP 代币
+///
+/// 随时可以取回；取回前先结算奖励，已结算的奖励保留在仓位中，
+/// 之后通过 ClaimRewards 领取
+///
+/// 账户顺序：
+/// 0. user (signer) - 用户
+/// 1. farm (writable) - Farm PDA ["farm", mint_lp, reward_mint]
+/// 2. position (writable) - 仓位 PDA ["stake", farm, user]
+/// 3. user_lp_ata (writable) - 用户的 LP 代币账户
+/// 4. stake_vault (writable) - 质押金库（farm 的 LP 关联代币账户）
+/// 5. mint_lp - LP Token Mint
+/// 6. token_program - LP Mint 的 Token 程序
+/// 7. reward_vault - 奖励金库（farm 的奖励代币关联账户）
+pub fn unstake(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [user, farm, position, user_lp_ata, stake_vault, mint_lp, token_program, reward_vault, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 user 是签名者
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 解析指令数据
+    let instruction_data = UnstakeInstructionData::try_from_bytes(data)?;
+
+    // 验证 farm、仓位、LP Mint、质押金库和奖励金库
+    check_farm(program_id, farm)?;
+    check_position(program_id, farm, position, user)?;
+    let reward_balance = reward_balance(farm, reward_vault)?;
+
+    let mut farm_data = farm.try_borrow_mut()?;
+    let farm_state = Farm::load_mut(&mut farm_data)?;
+    let lp_side = TokenSide::try_new(mint_lp, token_program, &farm_state.mint_lp)?;
+    check_vault(stake_vault, farm.address(), &lp_side)?;
+
+    // 推进累加器，结算奖励后减少质押数量
+    let now = Clock::get()?.unix_timestamp;
+    farm_state.update(now, reward_balance)?;
+
+    let mut position_data = position.try_borrow_mut()?;
+    let position_state = StakePosition::load_mut(&mut position_data)?;
+    let amount = position_state
+        .amount()
+        .checked_sub(instruction_data.amount)
+        .ok_or(AmmError::InsufficientStake)?;
+    position_state.settle(farm_state.acc_reward_per_share(), amount)?;
+    drop(position_data);
+
+    let total_staked = farm_state
+        .total_staked()
+        .checked_sub(instruction_data.amount)
+        .ok_or(AmmError::RewardOverflow)?;
+    farm_state.total_staked = total_staked.to_le_bytes();
+
+    // 复制签名种子后释放借用，CPI 需要使用 farm 账户
+    let mint_lp_bytes = farm_state.mint_lp;
+    let reward_mint_bytes = farm_state.reward_mint;
+    let bump_binding = [farm_state.bump];
+    drop(farm_data);
+
+    let farm_seeds = [
+        Seed::from(Farm::SEED),
+        Seed::from(&mint_lp_bytes),
+        Seed::from(&reward_mint_bytes),
+        Seed::from(&bump_binding),
+    ];
+    let farm_signers = [Signer::from(&farm_seeds)];
+
+    // 转出 LP 代币（使用 farm PDA 签名）
+    lp_side.transfer(stake_vault, user_lp_ata, farm, instruction_data.amount, &farm_signers)
+}
@@ -0,0 +1,162 @@
+use pinocchio::{
+    error::ProgramError,
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use core::mem::size_of;
+use crate::state::Config;
+
+/// UpdateConfig 指令数据
+///
+/// 第一个字节为更新类型，其余为对应参数
+pub enum UpdateConfigInstructionData {
+    /// 0: 设置动态费用
+    /// enabled (u8) + min_fee (u16) + max_fee (u16) + volatility_factor (u16) + decay_period (u64)
+    DynamicFee {
+        enabled: bool,
+        min_fee: u16,
+        max_fee: u16,
+        volatility_factor: u16,
+        decay_period: u64,
+    },
+    /// 1: 设置价格影响熔断
+    /// max_price_impact (u16) + max_slot_price_move (u16)，单位基点，0 表示不限制
+    PriceLimits {
+        max_price_impact: u16,
+        max_slot_price_move: u16,
+    },
+    /// 2: 开启或关闭许可模式
+    /// enabled (u8)
+    Permissioned { enabled: bool },
+    /// 3: 设置参考价格保护
+    /// oracle (32 字节，全零表示关闭) + max_deviation (u16，基点) + max_staleness (u64，秒，0 表示不检查)
+    Oracle {
+        oracle: Address,
+        max_deviation: u16,
+        max_staleness: u64,
+    },
+    /// 4: 设置暂停权限
+    /// guardian (32 字节，全零表示移除)
+    Guardian { guardian: Address },
+}
+
+impl UpdateConfigInstructionData {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        match data.split_first() {
+            Some((0, data)) => {
+                if data.len() != size_of::<u8>() + size_of::<u16>() * 3 + size_of::<u64>() {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+
+                Ok(Self::DynamicFee {
+                    enabled: data[0] != 0,
+                    min_fee: u16::from_le_bytes(data[1..3].try_into().unwrap()),
+                    max_fee: u16::from_le_bytes(data[3..5].try_into().unwrap()),
+                    volatility_factor: u16::from_le_bytes(data[5..7].try_into().unwrap()),
+                    decay_period: u64::from_le_bytes(data[7..15].try_into().unwrap()),
+                })
+            }
+            Some((1, data)) => {
+                if data.len() != size_of::<u16>() * 2 {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+
+                Ok(Self::PriceLimits {
+                    max_price_impact: u16::from_le_bytes(data[0..2].try_into().unwrap()),
+                    max_slot_price_move: u16::from_le_bytes(data[2..4].try_into().unwrap()),
+                })
+            }
+            Some((2, data)) => {
+                if data.len() != size_of::<u8>() {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+
+                Ok(Self::Permissioned { enabled: data[0] != 0 })
+            }
+            Some((3, data)) => {
+                if data.len() != 32 + size_of::<u16>() + size_of::<u64>() {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+
+                let mut oracle = [0u8; 32];
+                oracle.copy_from_slice(&data[0..32]);
+
+                Ok(Self::Oracle {
+                    oracle: Address::new_from_array(oracle),
+                    max_deviation: u16::from_le_bytes(data[32..34].try_into().unwrap()),
+                    max_staleness: u64::from_le_bytes(data[34..42].try_into().unwrap()),
+                })
+            }
+            Some((4, data)) => {
+                let guardian: [u8; 32] = data.try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                Ok(Self::Guardian { guardian: Address::new_from_array(guardian) })
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+/// UpdateConfig 指令 - 由池子管理权限修改 Config 参数
+///
+/// 账户顺序：
+/// 0. authority (signer) - Config 中记录的管理权限
+/// 1. config (writable) - Config 账户
+pub fn update_config(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [authority, config, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 authority 是签名者
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // 解析指令数据
+    let instruction_data = UpdateConfigInstructionData::try_from_bytes(data)?;
+
+    let mut config_data = config.try_borrow_mut()?;
+    let config_state = Config::load_mut(&mut config_data)?;
+
+    // 只有池子管理权限可以修改
+    if !config_state.is_authority(authority.address()) {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+
+    match instruction_data {
+        UpdateConfigInstructionData::DynamicFee {
+            enabled,
+            min_fee,
+            max_fee,
+            volatility_factor,
+            decay_period,
+        } => config_state.set_dynamic_fee(enabled, min_fee, max_fee, volatility_factor, decay_period),
+        UpdateConfigInstructionData::PriceLimits {
+            max_price_impact,
+            max_slot_price_move,
+        } => {
+            config_state.set_price_limits(max_price_impact, max_slot_price_move);
+            Ok(())
+        }
+        UpdateConfigInstructionData::Permissioned { enabled } => {
+            config_state.permissioned = enabled as u8;
+            Ok(())
+        }
+        UpdateConfigInstructionData::Oracle {
+            oracle,
+            max_deviation,
+            max_staleness,
+        } => config_state.set_oracle(&oracle, max_deviation, max_staleness),
+        UpdateConfigInstructionData::Guardian { guardian } => {
+            config_state.guardian.copy_from_slice(guardian.as_ref());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use pinocchio::{
+    error::ProgramError,
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::state::AmmSettings;
+use super::initialize_settings::FeeTiersData;
+
+/// UpdateFeeTiers 指令 - 替换允许的费率档位列表
+///
+/// 已创建的池子不受影响，新列表只约束之后的 `initialize`
+///
+/// 账户顺序：
+/// 0. authority (signer) - 设置管理权限
+/// 1. settings (writable) - 设置 PDA ["settings"]
+pub fn update_fee_tiers(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [authority, settings, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 authority 是签名者
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 settings 账户归本程序所有
+    if !settings.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // 解析指令数据
+    let fee_tiers = FeeTiersData::try_from_bytes(data)?;
+
+    let mut settings_data = settings.try_borrow_mut()?;
+    let settings_state = AmmSettings::load_mut(&mut settings_data)?;
+
+    // 验证 settings 地址
+    if settings.address() != &settings_state.derive_address(program_id) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // 只有设置管理权限可以修改
+    if settings_state.authority != *authority.address().as_array() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+
+    settings_state.set_fee_tiers(fee_tiers.as_slice())
+}
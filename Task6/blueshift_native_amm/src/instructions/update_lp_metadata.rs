@@ -0,0 +1,118 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    Address,
+    AccountView,
+    ProgramResult,
+};
+use crate::error::AmmError;
+use crate::state::Config;
+use super::metadata::{read_field, update_lp_metadata_field, MetadataField};
+use super::token::TOKEN_2022_PROGRAM_ID;
+
+/// UpdateLpMetadata 指令数据
+pub struct UpdateLpMetadataInstructionData<'a> {
+    pub lp_bump: u8,            // LP Mint PDA ["mint_lp", config] 的 bump
+    pub field: MetadataField,   // 要修改的字段
+    pub value: &'a [u8],        // 新值（UTF-8）
+}
+
+impl<'a> UpdateLpMetadataInstructionData<'a> {
+    /// 从字节数组解析指令数据
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ProgramError> {
+        // 1 (lp_bump) + 1 (field) + 1 (长度) + value
+        let [lp_bump, field, rest @ ..] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+
+        let field = MetadataField::from_u8(*field)?;
+        let (value, rest) = read_field(rest, field)?;
+        if !rest.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            lp_bump: *lp_bump,
+            field,
+            value,
+        })
+    }
+}
+
+/// UpdateLpMetadata 指令 - 修改 LP 代币的名称、符号或 URI
+///
+/// 账户顺序：
+/// 0. authority (signer, writable) - 池子管理权限，同时支付元数据扩容的租金
+/// 1. config - Config 账户
+/// 2. mint_lp (writable) - LP Token Mint PDA ["mint_lp", config]（带元数据的 Token-2022 Mint）
+/// 3. token_program - Token-2022 程序
+/// 4. system_program - 系统程序
+///
+/// 元数据更新权限为 config PDA，只有初始化时附带元数据的池子可以修改
+pub fn update_lp_metadata(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+    let [authority, config, mint_lp, token_program, _system_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // 验证 authority 是签名者
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // 解析指令数据
+    let instruction_data = UpdateLpMetadataInstructionData::try_from_bytes(data)?;
+
+    // 验证池子管理权限，并准备 config PDA 签名种子
+    let config_data = config.try_borrow()?;
+    let config_state = Config::load(&config_data)?;
+    if !config_state.is_initialized() {
+        return Err(AmmError::PoolNotInitialized.into());
+    }
+    if !config_state.is_authority(authority.address()) {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+    let seed_bytes = config_state.seed;
+    let config_bump_binding = [config_state.config_bump];
+    let mint_x_address = config_state.mint_x_address();
+    let mint_y_address = config_state.mint_y_address();
+    drop(config_data);
+
+    // 验证 LP Mint 属于该池子，且是带元数据的 Token-2022 Mint
+    let expected_mint_lp = Address::derive_address(
+        &[b"mint_lp", config.address().as_ref()],
+        Some(instruction_data.lp_bump),
+        program_id,
+    );
+    if mint_lp.address() != &expected_mint_lp {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if token_program.address() != &TOKEN_2022_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !mint_lp.owned_by(&TOKEN_2022_PROGRAM_ID) {
+        return Err(AmmError::InvalidLpMint.into());
+    }
+
+    let config_seeds = [
+        Seed::from(b"config"),
+        Seed::from(&seed_bytes),
+        Seed::from(mint_x_address.as_ref()),
+        Seed::from(mint_y_address.as_ref()),
+        Seed::from(&config_bump_binding),
+    ];
+    let config_signers = [Signer::from(&config_seeds)];
+
+    update_lp_metadata_field(
+        authority,
+        mint_lp,
+        config,
+        instruction_data.field,
+        instruction_data.value,
+        &config_signers,
+    )
+}
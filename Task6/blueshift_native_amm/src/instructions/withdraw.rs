@@ -5,9 +5,19 @@ use pinocchio::{
     AccountView,
     ProgramResult,
 };
-use pinocchio_token::instructions::{Transfer, Burn};
 use core::mem::size_of;
-use crate::state::Config;
+use blueshift_amm_math::quote_withdraw;
+use crate::{
+    error::{math_error, AmmError},
+    event::{emit, EventKind, LiquidityEvent},
+    state::Config,
+};
+use super::helpers::{
+    check_expiration, check_pool_state, check_vault, lp_supply, settle_virtual_orders,
+    pool_reserves, split_optional,
+};
+use super::token::TokenSide;
+use super::native::{native_accounts, transfer_out};
 
 /// Withdraw 指令数据
 pub struct WithdrawInstructionData {
@@ -31,7 +41,7 @@ impl WithdrawInstructionData {
 
         // 验证数据
         if amount == 0 {
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(AmmError::InvalidAmount.into());
         }
 
         Ok(Self {
@@ -44,20 +54,33 @@ impl WithdrawInstructionData {
 }
 
 /// Withdraw 指令 - 提取流动性
-/// 
+///
+/// 按 LP 占比取回两侧代币：取回数量为 floor(储备 * amount / LP 供应量)，
+/// 不得低于 min_x / min_y
+///
 /// 账户顺序：
 /// 0. user (signer, writable) - 用户
-/// 1. config - Config 账户
-/// 2. mint_lp (writable) - LP Token Mint
-/// 3. vault_x (writable) - X 代币金库
-/// 4. vault_y (writable) - Y 代币金库
-/// 5. user_x_ata (writable) - 用户的 X 代币账户
-/// 6. user_y_ata (writable) - 用户的 Y 代币账户
+/// 1. config (writable) - Config 账户（仅已创建长期订单池时需要可写）
+/// 2. mint_lp (writable) - LP Token Mint（必须是 Config 记录的 LP Mint）
+/// 3. vault_x (writable) - X 代币金库（config 的关联代币账户）
+/// 4. vault_y (writable) - Y 代币金库（config 的关联代币账户）
+/// 5. user_x_ata (writable) - 用户的 X 代币账户（原生 SOL 时传入用户钱包）
+/// 6. user_y_ata (writable) - 用户的 Y 代币账户（原生 SOL 时传入用户钱包）
 /// 7. user_lp_ata (writable) - 用户的 LP 代币账户
-/// 8. token_program - Token 程序
-pub fn withdraw(_program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
+/// 8. token_program - LP Mint 的 Token 程序
+/// 9. token_program_x - X 代币的 Token 程序（Token 或 Token-2022）
+/// 10. token_program_y - Y 代币的 Token 程序（Token 或 Token-2022）
+/// 11. mint_x - 代币 X 的 Mint
+/// 12. mint_y - 代币 Y 的 Mint
+///
+/// 附加账户（按顺序追加）：
+/// - order_pool (writable) - 长期订单池 PDA ["twamm", config]（仅已创建长期订单池的池子）
+/// - system_program - 系统程序（仅原生 SOL 模式）
+/// - native_mint - wSOL Mint（仅原生 SOL 模式）
+/// - temp_wsol (signer, writable) - 临时 wSOL 账户（仅原生 SOL 模式，指令内关闭）
+pub fn withdraw(program_id: &Address, data: &[u8], accounts: &[AccountView]) -> ProgramResult {
     // 验证账户数量
-    if accounts.len() < 9 {
+    if accounts.len() < 13 {
         return Err(ProgramError::NotEnoughAccountKeys);
     }
 
@@ -70,42 +93,78 @@ pub fn withdraw(_program_id: &Address, data: &[u8], accounts: &[AccountView]) ->
     let user_x_ata = &accounts[5];
     let user_y_ata = &accounts[6];
     let user_lp_ata = &accounts[7];
-    let _token_program = &accounts[8];
+    let token_program = &accounts[8];
+    let token_program_x = &accounts[9];
+    let token_program_y = &accounts[10];
+    let mint_x = &accounts[11];
+    let mint_y = &accounts[12];
 
     // 验证 user 是签名者
     if !user.is_signer() {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
+    // 验证 config 账户归本程序所有
+    if !config.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
     // 解析指令数据
     let instruction_data = WithdrawInstructionData::try_from_bytes(data)?;
 
-    // 读取 config 状态
+    // 验证未过期
+    let now = check_expiration(instruction_data.expiration)?.unix_timestamp;
+
+    // 读取 config 状态（旧布局缺少的字段按关闭处理，无需先迁移）
     let config_data = config.try_borrow()?;
-    let config_state = Config::load(&config_data)?;
+    let config_state = &Config::read(&config_data)?;
 
     // 验证 AMM 状态（可以提取）
-    if !config_state.can_withdraw() {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    check_pool_state(config_state, true)?;
 
-    // 简化版本：直接按比例提取
-    // 实际实现需要使用 constant-product-curve 计算精确金额
+    // 验证两侧 Mint、Token 程序和金库
+    let side_x = TokenSide::try_new(mint_x, token_program_x, &config_state.mint_x)?;
+    let side_y = TokenSide::try_new(mint_y, token_program_y, &config_state.mint_y)?;
+    check_vault(vault_x, config.address(), &side_x)?;
+    check_vault(vault_y, config.address(), &side_y)?;
+    // LP Mint 可以是旧版 Token 或带元数据的 Token-2022，其地址和铸币权限由 lp_supply 按 Config 校验
+    let side_lp = TokenSide::try_new(mint_lp, token_program, mint_lp.address().as_array())?;
 
-    // 销毁用户的 LP 代币
-    Burn {
-        mint: mint_lp,
-        account: user_lp_ata,
-        authority: user,
-        amount: instruction_data.amount,
-    }.invoke()?;
+    let has_virtual_orders = config_state.has_virtual_orders();
 
     // 创建 PDA 签名种子
-    let seed_bytes = config_state.seed.to_le_bytes();
+    let seed_bytes = config_state.seed;
     let config_bump_binding = [config_state.config_bump];
     let mint_x_address = config_state.mint_x_address();
     let mint_y_address = config_state.mint_y_address();
-    
+    drop(config_data);
+
+    // 已创建长期订单池的池子：先把长期订单结算到当前时间
+    let (order_pool, remaining) = split_optional(has_virtual_orders, &accounts[13..])?;
+    if let Some(order_pool) = order_pool {
+        settle_virtual_orders(program_id, config, order_pool, vault_x, vault_y, now)?;
+    }
+
+    // 提取不检查白名单：池子切换为许可模式或移除白名单后，已有 LP 仍可取回资金
+    let native = native_accounts(
+        user,
+        [(user_x_ata, mint_x_address.as_array()), (user_y_ata, mint_y_address.as_array())],
+        remaining,
+    )?;
+
+    // 按 LP 占比计算取回数量，储备不含属于订单的代币
+    let (reserve_x, reserve_y) = pool_reserves(config, vault_x, vault_y)?;
+    let quote = quote_withdraw(reserve_x, reserve_y, lp_supply(program_id, mint_lp, config.address(), config_state)?, instruction_data.amount)
+        .map_err(math_error)?;
+
+    // 滑点保护
+    if quote.amount_x < instruction_data.min_x || quote.amount_y < instruction_data.min_y {
+        return Err(AmmError::SlippageExceeded.into());
+    }
+
+    // 销毁用户的 LP 代币
+    side_lp.burn(user_lp_ata, user, instruction_data.amount)?;
+
     let config_seeds = [
         Seed::from(b"config"),
         Seed::from(&seed_bytes),
@@ -116,20 +175,40 @@ pub fn withdraw(_program_id: &Address, data: &[u8], accounts: &[AccountView]) ->
     let config_signers = [Signer::from(&config_seeds)];
 
     // 转移 X 代币到用户（使用 config PDA 签名）
-    Transfer {
-        from: vault_x,
-        to: user_x_ata,
-        authority: config,
-        amount: instruction_data.min_x,
-    }.invoke_signed(&config_signers)?;
+    transfer_out(
+        user,
+        user_x_ata,
+        vault_x,
+        &side_x,
+        config,
+        &config_signers,
+        native.as_ref(),
+        quote.amount_x,
+    )?;
 
     // 转移 Y 代币到用户（使用 config PDA 签名）
-    Transfer {
-        from: vault_y,
-        to: user_y_ata,
-        authority: config,
-        amount: instruction_data.min_y,
-    }.invoke_signed(&config_signers)?;
+    transfer_out(
+        user,
+        user_y_ata,
+        vault_y,
+        &side_y,
+        config,
+        &config_signers,
+        native.as_ref(),
+        quote.amount_y,
+    )?;
+
+    // 输出提取事件
+    let (reserve_x, reserve_y) = pool_reserves(config, vault_x, vault_y)?;
+    emit(&LiquidityEvent {
+        pool: *config.address().as_array(),
+        user: *user.address().as_array(),
+        lp_amount: instruction_data.amount,
+        amount_x: quote.amount_x,
+        amount_y: quote.amount_y,
+        reserve_x,
+        reserve_y,
+    }.to_bytes(EventKind::Withdraw));
 
     Ok(())
 }
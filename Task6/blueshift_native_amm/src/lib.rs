@@ -1,20 +1,27 @@
 #![no_std]
 
 use pinocchio::{
-    entrypoint,
-    nostd_panic_handler,
     error::ProgramError,
     Address,
     AccountView,
     ProgramResult,
 };
 
-entrypoint!(process_instruction);
-nostd_panic_handler!();
+// 作为库被链下客户端依赖时开启 no-entrypoint：不导出程序入口，由 std 提供 panic 处理和内存分配
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::nostd_panic_handler!();
+#[cfg(feature = "no-entrypoint")]
+extern crate std;
 
+pub mod error;
+pub mod event;
 pub mod instructions;
 pub mod state;
 
+pub use blueshift_amm_math as math;
+pub use error::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -27,7 +34,7 @@ pub const ID: Address = Address::new_from_array([
 ]);
 
 /// 程序入口点
-fn process_instruction(
+pub fn process_instruction(
     program_id: &Address,
     accounts: &[AccountView],
     instruction_data: &[u8],
@@ -37,6 +44,28 @@ fn process_instruction(
         Some((1, data)) => deposit(program_id, data, accounts),
         Some((2, data)) => withdraw(program_id, data, accounts),
         Some((3, data)) => swap(program_id, data, accounts),
+        Some((4, data)) => initialize_settings(program_id, data, accounts),
+        Some((5, data)) => update_fee_tiers(program_id, data, accounts),
+        Some((6, data)) => update_config(program_id, data, accounts),
+        Some((7, data)) => add_allowed_wallet(program_id, data, accounts),
+        Some((8, data)) => revoke_allowed_wallet(program_id, data, accounts),
+        Some((9, data)) => create_farm(program_id, data, accounts),
+        Some((10, data)) => stake(program_id, data, accounts),
+        Some((11, data)) => unstake(program_id, data, accounts),
+        Some((12, data)) => claim_rewards(program_id, data, accounts),
+        Some((13, data)) => lock_lp(program_id, data, accounts),
+        Some((14, data)) => unlock_lp(program_id, data, accounts),
+        Some((15, data)) => place_virtual_order(program_id, data, accounts),
+        Some((16, data)) => execute_virtual_orders(program_id, data, accounts),
+        Some((17, data)) => claim_virtual_order(program_id, data, accounts),
+        Some((18, data)) => cancel_virtual_order(program_id, data, accounts),
+        Some((19, data)) => place_limit_order(program_id, data, accounts),
+        Some((20, data)) => fill_limit_orders(program_id, data, accounts),
+        Some((21, data)) => claim_limit_order(program_id, data, accounts),
+        Some((22, data)) => cancel_limit_order(program_id, data, accounts),
+        Some((23, data)) => set_pool_state(program_id, data, accounts),
+        Some((24, data)) => migrate_config(program_id, data, accounts),
+        Some((25, data)) => update_lp_metadata(program_id, data, accounts),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
@@ -0,0 +1,51 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+
+/// 许可池子的白名单条目
+///
+/// PDA 种子：["allow", config, wallet]
+/// 账户存在即表示该钱包可以在此池子中交换和提供流动性
+#[repr(C)]
+pub struct AllowListEntry {
+    pub config: [u8; 32],   // 所属池子的 Config 地址
+    pub wallet: [u8; 32],   // 被允许的钱包
+    pub bump: u8,           // PDA bump seed
+}
+
+impl AllowListEntry {
+    /// AllowListEntry 结构的大小（字节）
+    pub const LEN: usize = 32                     // config
+        + 32                                      // wallet
+        + size_of::<u8>();                        // bump
+
+    /// PDA 种子前缀
+    pub const SEED: &'static [u8] = b"allow";
+
+    /// 从字节数组加载 AllowListEntry（不可变）
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// 从字节数组加载 AllowListEntry（可变）
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// 根据存储的 bump 派生条目 PDA 地址
+    #[inline(always)]
+    pub fn derive_address(&self, program_id: &Address) -> Address {
+        Address::derive_address(&[Self::SEED, &self.config, &self.wallet], Some(self.bump), program_id)
+    }
+}
+
+const _: () = assert!(size_of::<AllowListEntry>() == AllowListEntry::LEN);
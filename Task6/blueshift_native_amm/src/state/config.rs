@@ -0,0 +1,650 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+use blueshift_amm_math::{execution_deviation_bps, BPS_DENOMINATOR};
+use crate::error::AmmError;
+use super::PriceFeed;
+
+/// LBP 单侧权重下限（基点）
+pub const MIN_WEIGHT: u16 = 100;
+
+/// AMM 配置状态
+/// 所有多字节字段都使用字节数组存储，保证结构体按 1 字节对齐，
+/// 与账户中的紧凑布局完全一致
+#[repr(C)]
+pub struct Config {
+    pub state: u8,              // AMM 状态
+    pub seed: [u8; 8],          // PDA 派生种子（即费率档位）
+    pub authority: [u8; 32],    // 管理权限
+    pub mint_x: [u8; 32],       // 代币 X 的 Mint
+    pub mint_y: [u8; 32],       // 代币 Y 的 Mint
+    pub fee: [u8; 2],           // 交换费用（基点）
+    pub config_bump: u8,        // PDA bump seed
+    pub fee_mode: u8,           // 费用模式（FeeMode）
+    pub min_fee: [u8; 2],       // 动态费用下限（基点）
+    pub max_fee: [u8; 2],       // 动态费用上限（基点）
+    pub volatility_factor: [u8; 2], // 波动率计入费用的比例（基点）
+    pub decay_period: [u8; 8],  // 波动率半衰期（秒）
+    pub volatility: [u8; 8],    // 累积价格波动（基点）
+    pub volatility_updated_at: [u8; 8], // 波动率最后更新时间（Unix 时间戳）
+    pub max_price_impact: [u8; 2],      // 单笔交换最大价格影响（基点，0 表示不限制）
+    pub max_slot_price_move: [u8; 2],   // 单个 slot 内累计价格变化上限（基点，0 表示不限制）
+    pub price_move_slot: [u8; 8],       // slot_price_move 所属的 slot
+    pub slot_price_move: [u8; 8],       // 当前 slot 内累计价格变化（基点）
+    pub permissioned: u8,               // 是否为许可池子（1 = 仅白名单钱包可交易和提供流动性）
+    pub reserved_x: [u8; 8],            // 金库中属于订单（未成交或待领取）的 X，不计入储备
+    pub reserved_y: [u8; 8],            // 金库中属于订单（未成交或待领取）的 Y，不计入储备
+    pub virtual_orders: u8,             // 是否已创建长期订单池（1 = 交换前需要结算长期订单）
+    pub limit_orders: u8,               // 是否已创建限价单簿（1 = 交换后需要撮合限价单）
+    pub start_weight_x: [u8; 2],        // LBP 起始时 X 的权重（基点，0 表示不是 LBP 池子）
+    pub end_weight_x: [u8; 2],          // LBP 结束时 X 的权重（基点）
+    pub weight_start_at: [u8; 8],       // 权重开始变化的时间（Unix 时间戳）
+    pub weight_end_at: [u8; 8],         // 权重停止变化的时间（Unix 时间戳），即发售结束时间
+    pub oracle: [u8; 32],               // 参考价格源账户（全零表示不检查）
+    pub max_oracle_deviation: [u8; 2], // 成交价相对参考价格的最大偏离（基点）
+    pub max_oracle_staleness: [u8; 8], // 参考价格最长有效期（秒，0 表示不检查）
+    pub guardian: [u8; 32],             // 暂停权限：只能禁用池子或切换为仅限提取（全零表示未设置）
+    pub mint_lp: [u8; 32],              // LP Mint PDA ["mint_lp", config]（全零表示旧布局未记录）
+}
+
+/// 费用模式
+#[repr(u8)]
+pub enum FeeMode {
+    Static = 0u8,   // 固定费用，始终使用 fee
+    Dynamic = 1u8,  // fee 加上由近期波动率决定的浮动部分
+}
+
+/// Config 账户的布局
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigLayout {
+    Legacy,         // 无账户头的旧布局，长度决定包含哪些字段
+    Versioned(u8),  // 带账户头的布局及其版本
+}
+
+/// AMM 状态枚举
+#[repr(u8)]
+pub enum AmmState {
+    Uninitialized = 0u8,    // 未初始化
+    Initialized = 1u8,      // 已初始化
+    Disabled = 2u8,         // 已禁用
+    WithdrawOnly = 3u8,     // 仅限提取
+}
+
+impl Config {
+    /// Config 结构的大小（字节）
+    pub const LEN: usize = size_of::<u8>()       // state
+        + size_of::<u64>()                        // seed
+        + 32                                      // authority
+        + 32                                      // mint_x
+        + 32                                      // mint_y
+        + size_of::<u16>()                        // fee
+        + size_of::<u8>()                         // config_bump
+        + size_of::<u8>()                         // fee_mode
+        + size_of::<u16>()                        // min_fee
+        + size_of::<u16>()                        // max_fee
+        + size_of::<u16>()                        // volatility_factor
+        + size_of::<u64>()                        // decay_period
+        + size_of::<u64>()                        // volatility
+        + size_of::<i64>()                        // volatility_updated_at
+        + size_of::<u16>()                        // max_price_impact
+        + size_of::<u16>()                        // max_slot_price_move
+        + size_of::<u64>()                        // price_move_slot
+        + size_of::<u64>()                        // slot_price_move
+        + size_of::<u8>()                         // permissioned
+        + size_of::<u64>()                        // reserved_x
+        + size_of::<u64>()                        // reserved_y
+        + size_of::<u8>()                         // virtual_orders
+        + size_of::<u8>()                         // limit_orders
+        + size_of::<u16>()                        // start_weight_x
+        + size_of::<u16>()                        // end_weight_x
+        + size_of::<i64>()                        // weight_start_at
+        + size_of::<i64>()                        // weight_end_at
+        + 32                                      // oracle
+        + size_of::<u16>()                        // max_oracle_deviation
+        + size_of::<u64>()                        // max_oracle_staleness
+        + 32                                      // guardian
+        + 32;                                     // mint_lp
+
+    /// 账户头标识，写在账户数据第 0 字节
+    ///
+    /// 旧布局的第 0 字节是 state（0..=3），不会与之混淆
+    pub const DISCRIMINATOR: u8 = 0xCF;
+
+    /// 当前布局版本，写在账户数据第 1 字节
+    pub const VERSION: u8 = 2;
+
+    /// 账户头长度：discriminator + version
+    pub const HEADER_LEN: usize = 2;
+
+    /// 新建 Config 账户的大小：账户头 + Config
+    pub const ACCOUNT_LEN: usize = Self::HEADER_LEN + Self::LEN;
+
+    /// 最早的无版本布局大小（state 到 config_bump）
+    ///
+    /// 新字段总是追加在末尾，因此任意版本的 Config 都是下一版本的前缀
+    pub const LEGACY_MIN_LEN: usize = 108;
+
+    /// Config PDA 种子前缀
+    pub const SEED: &'static [u8] = b"config";
+
+    /// 识别账户数据的布局
+    pub fn layout(bytes: &[u8]) -> Result<ConfigLayout, ProgramError> {
+        match bytes {
+            [Self::DISCRIMINATOR, version, ..] if *version != 0 && *version <= Self::VERSION => {
+                Ok(ConfigLayout::Versioned(*version))
+            }
+            [state, ..] if *state <= AmmState::WithdrawOnly as u8 && bytes.len() >= Self::LEGACY_MIN_LEN => {
+                Ok(ConfigLayout::Legacy)
+            }
+            _ => Err(AmmError::InvalidConfig.into()),
+        }
+    }
+
+    /// Config 在账户数据中的起始位置
+    ///
+    /// 当前版本从账户头之后开始；无版本布局只有与 Config 等长时才能原地使用，
+    /// 更早的布局和旧版本缺少字段，需要先执行 MigrateConfig
+    fn offset(bytes: &[u8]) -> Result<usize, ProgramError> {
+        let (offset, version) = match Self::layout(bytes)? {
+            ConfigLayout::Versioned(version) => (Self::HEADER_LEN, version),
+            ConfigLayout::Legacy => (0, 0),
+        };
+
+        if version != Self::VERSION && !(version == 0 && bytes.len() == Self::LEN) {
+            return Err(AmmError::ConfigOutdated.into());
+        }
+        if bytes.len() < offset + Self::LEN {
+            return Err(AmmError::InvalidConfig.into());
+        }
+
+        Ok(offset)
+    }
+
+    /// Config 字段在账户数据中的范围（起始位置, 长度）
+    ///
+    /// 任意版本的布局都是当前 Config 的前缀，长度决定包含哪些字段
+    fn body(bytes: &[u8]) -> Result<(usize, usize), ProgramError> {
+        let offset = match Self::layout(bytes)? {
+            ConfigLayout::Versioned(_) => Self::HEADER_LEN,
+            ConfigLayout::Legacy => 0,
+        };
+        let len = (bytes.len() - offset).min(Self::LEN);
+        if len < Self::LEGACY_MIN_LEN {
+            return Err(AmmError::InvalidConfig.into());
+        }
+
+        Ok((offset, len))
+    }
+
+    /// 读取任意版本的 Config
+    ///
+    /// 旧布局缺少的字段按零补齐，即对应功能关闭。Swap、Deposit 和 Withdraw
+    /// 通过它读取，旧池子不需要先执行 MigrateConfig 也能交易和提取
+    pub fn read(bytes: &[u8]) -> Result<Self, ProgramError> {
+        let (offset, len) = Self::body(bytes)?;
+
+        // 所有字段都是字节数组，全零是合法值
+        let mut config: Self = unsafe { core::mem::zeroed() };
+        let config_bytes =
+            unsafe { core::slice::from_raw_parts_mut(&mut config as *mut Self as *mut u8, Self::LEN) };
+        config_bytes[..len].copy_from_slice(&bytes[offset..offset + len]);
+
+        Ok(config)
+    }
+
+    /// 把 `read` 得到的 Config 写回账户数据，只写入该布局中存在的字段
+    pub fn write(&self, bytes: &mut [u8]) -> Result<(), ProgramError> {
+        let (offset, len) = Self::body(bytes)?;
+        let config_bytes = unsafe { core::slice::from_raw_parts(self as *const Self as *const u8, Self::LEN) };
+        bytes[offset..offset + len].copy_from_slice(&config_bytes[..len]);
+
+        Ok(())
+    }
+
+    /// 从字节数组加载 Config（不可变），接受当前版本和最后一个无版本布局
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        let offset = Self::offset(bytes)?;
+
+        Ok(unsafe { &*(bytes[offset..].as_ptr() as *const Self) })
+    }
+
+    /// 从字节数组加载 Config（可变），接受当前版本和最后一个无版本布局
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        let offset = Self::offset(bytes)?;
+
+        Ok(unsafe { &mut *(bytes[offset..].as_mut_ptr() as *mut Self) })
+    }
+
+    /// 在新账户中写入账户头并加载 Config（可变）
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::ACCOUNT_LEN {
+            return Err(AmmError::InvalidConfig.into());
+        }
+
+        bytes[0] = Self::DISCRIMINATOR;
+        bytes[1] = Self::VERSION;
+        Self::load_mut(bytes)
+    }
+
+    /// 把任意旧布局升级为当前版本
+    ///
+    /// bytes 必须已扩展到至少 ACCOUNT_LEN；旧数据整体后移到账户头之后，
+    /// 新增字段补零（即关闭状态）。返回原布局，已是当前版本时不做修改
+    pub fn migrate(bytes: &mut [u8], old_len: usize) -> Result<ConfigLayout, ProgramError> {
+        let layout = Self::layout(&bytes[..old_len])?;
+        if bytes.len() < Self::ACCOUNT_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (start, end) = match layout {
+            ConfigLayout::Versioned(version) if version == Self::VERSION => return Ok(layout),
+            ConfigLayout::Versioned(_) => (Self::HEADER_LEN, old_len),
+            ConfigLayout::Legacy => (0, old_len),
+        };
+        let old_body_len = (end - start).min(Self::LEN);
+
+        bytes.copy_within(start..start + old_body_len, Self::HEADER_LEN);
+        bytes[Self::HEADER_LEN + old_body_len..].fill(0);
+        bytes[0] = Self::DISCRIMINATOR;
+        bytes[1] = Self::VERSION;
+
+        Ok(layout)
+    }
+
+    /// 设置所有字段
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        seed: u64,
+        authority: &Address,
+        mint_x: &Address,
+        mint_y: &Address,
+        fee: u16,
+        config_bump: u8,
+    ) {
+        self.state = AmmState::Initialized as u8;
+        self.seed = seed.to_le_bytes();
+        self.authority.copy_from_slice(authority.as_ref());
+        self.mint_x.copy_from_slice(mint_x.as_ref());
+        self.mint_y.copy_from_slice(mint_y.as_ref());
+        self.fee = fee.to_le_bytes();
+        self.config_bump = config_bump;
+        self.fee_mode = FeeMode::Static as u8;
+        self.min_fee = [0u8; 2];
+        self.max_fee = [0u8; 2];
+        self.volatility_factor = [0u8; 2];
+        self.decay_period = [0u8; 8];
+        self.volatility = [0u8; 8];
+        self.volatility_updated_at = [0u8; 8];
+        self.max_price_impact = [0u8; 2];
+        self.max_slot_price_move = [0u8; 2];
+        self.price_move_slot = [0u8; 8];
+        self.slot_price_move = [0u8; 8];
+        self.permissioned = 0;
+        self.reserved_x = [0u8; 8];
+        self.reserved_y = [0u8; 8];
+        self.virtual_orders = 0;
+        self.limit_orders = 0;
+        self.start_weight_x = [0u8; 2];
+        self.end_weight_x = [0u8; 2];
+        self.weight_start_at = [0u8; 8];
+        self.weight_end_at = [0u8; 8];
+        self.oracle = [0u8; 32];
+        self.max_oracle_deviation = [0u8; 2];
+        self.max_oracle_staleness = [0u8; 8];
+        self.guardian = [0u8; 32];
+        self.mint_lp = [0u8; 32];
+    }
+
+    /// 设置 LBP 权重计划
+    ///
+    /// X 的权重在 [start_at, end_at] 内从 start_weight_x 线性变化到 end_weight_x，
+    /// 权重必须在 [MIN_WEIGHT, 10000 - MIN_WEIGHT] 之间
+    pub fn set_weight_schedule(
+        &mut self,
+        start_weight_x: u16,
+        end_weight_x: u16,
+        start_at: i64,
+        end_at: i64,
+    ) -> Result<(), ProgramError> {
+        let valid = |weight: u16| (MIN_WEIGHT..=BPS_DENOMINATOR as u16 - MIN_WEIGHT).contains(&weight);
+        if !valid(start_weight_x) || !valid(end_weight_x) || start_at >= end_at {
+            return Err(AmmError::InvalidWeightSchedule.into());
+        }
+
+        self.start_weight_x = start_weight_x.to_le_bytes();
+        self.end_weight_x = end_weight_x.to_le_bytes();
+        self.weight_start_at = start_at.to_le_bytes();
+        self.weight_end_at = end_at.to_le_bytes();
+
+        Ok(())
+    }
+
+    /// 设置价格影响熔断参数（基点，0 表示不限制）
+    #[inline(always)]
+    pub fn set_price_limits(&mut self, max_price_impact: u16, max_slot_price_move: u16) {
+        self.max_price_impact = max_price_impact.to_le_bytes();
+        self.max_slot_price_move = max_slot_price_move.to_le_bytes();
+    }
+
+    /// 设置参考价格保护
+    ///
+    /// oracle 为全零地址时关闭；开启时最大偏离必须在 (0, 10000] 内
+    pub fn set_oracle(
+        &mut self,
+        oracle: &Address,
+        max_deviation: u16,
+        max_staleness: u64,
+    ) -> Result<(), ProgramError> {
+        let enabled = oracle.as_array() != &[0u8; 32];
+        if enabled && (max_deviation == 0 || max_deviation as u64 > BPS_DENOMINATOR) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        self.oracle.copy_from_slice(oracle.as_ref());
+        self.max_oracle_deviation = max_deviation.to_le_bytes();
+        self.max_oracle_staleness = max_staleness.to_le_bytes();
+
+        Ok(())
+    }
+
+    /// 设置动态费用参数
+    ///
+    /// 开启时要求 min_fee <= max_fee <= 10000 且半衰期大于 0；
+    /// 切换模式时清空累积的波动率
+    pub fn set_dynamic_fee(
+        &mut self,
+        enabled: bool,
+        min_fee: u16,
+        max_fee: u16,
+        volatility_factor: u16,
+        decay_period: u64,
+    ) -> Result<(), ProgramError> {
+        if enabled && (min_fee > max_fee || max_fee > 10_000 || decay_period == 0) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        self.fee_mode = if enabled { FeeMode::Dynamic as u8 } else { FeeMode::Static as u8 };
+        self.min_fee = min_fee.to_le_bytes();
+        self.max_fee = max_fee.to_le_bytes();
+        self.volatility_factor = volatility_factor.to_le_bytes();
+        self.decay_period = decay_period.to_le_bytes();
+        self.volatility = [0u8; 8];
+        self.volatility_updated_at = [0u8; 8];
+
+        Ok(())
+    }
+
+    /// 获取 PDA 派生种子
+    #[inline(always)]
+    pub fn seed(&self) -> u64 {
+        u64::from_le_bytes(self.seed)
+    }
+
+    /// 获取交换费用（基点）
+    #[inline(always)]
+    pub fn fee(&self) -> u16 {
+        u16::from_le_bytes(self.fee)
+    }
+
+    /// 是否启用动态费用
+    #[inline(always)]
+    pub fn is_dynamic_fee(&self) -> bool {
+        self.fee_mode == FeeMode::Dynamic as u8
+    }
+
+    /// 按经过的时间衰减后的波动率
+    ///
+    /// 每经过一个 decay_period 波动率减半，周期内部线性插值
+    pub fn decayed_volatility(&self, now: i64) -> u64 {
+        let volatility = u64::from_le_bytes(self.volatility);
+        let decay_period = u64::from_le_bytes(self.decay_period);
+        if volatility == 0 || decay_period == 0 {
+            return 0;
+        }
+
+        let elapsed = now.saturating_sub(i64::from_le_bytes(self.volatility_updated_at)).max(0) as u64;
+        let halvings = elapsed / decay_period;
+        if halvings >= u64::BITS as u64 {
+            return 0;
+        }
+
+        let halved = volatility >> halvings;
+        let remainder = elapsed % decay_period;
+        halved - (halved as u128 * remainder as u128 / (2 * decay_period as u128)) as u64
+    }
+
+    /// 当前生效的交换费用（基点）
+    ///
+    /// 固定模式直接返回 fee；动态模式返回
+    /// fee + 衰减后波动率 * volatility_factor / 10000，并限制在 [min_fee, max_fee] 内
+    pub fn effective_fee(&self, now: i64) -> u16 {
+        let fee = self.fee();
+        if !self.is_dynamic_fee() {
+            return fee;
+        }
+
+        let volatility_factor = u16::from_le_bytes(self.volatility_factor) as u128;
+        let variable = self.decayed_volatility(now) as u128 * volatility_factor / 10_000;
+        let total = (fee as u128).saturating_add(variable);
+
+        let min_fee = u16::from_le_bytes(self.min_fee) as u128;
+        let max_fee = u16::from_le_bytes(self.max_fee) as u128;
+        total.clamp(min_fee, max_fee) as u16
+    }
+
+    /// 记录一次交换造成的价格波动
+    #[inline(always)]
+    pub fn record_price_move(&mut self, now: i64, price_change_bps: u64) {
+        if !self.is_dynamic_fee() {
+            return;
+        }
+
+        let volatility = self.decayed_volatility(now).saturating_add(price_change_bps);
+        self.volatility = volatility.to_le_bytes();
+        self.volatility_updated_at = now.to_le_bytes();
+    }
+
+    /// 价格影响熔断检查
+    ///
+    /// 单笔交换的价格变化不得超过 max_price_impact；同一 slot 内的累计
+    /// 价格变化不得超过 max_slot_price_move，达到上限后池子拒绝继续移动
+    /// 价格的交换，直到下一个 slot。检查通过后记录本次价格变化
+    pub fn check_price_impact(&mut self, slot: u64, price_change_bps: u64) -> Result<(), ProgramError> {
+        let max_price_impact = u16::from_le_bytes(self.max_price_impact) as u64;
+        if max_price_impact != 0 && price_change_bps > max_price_impact {
+            return Err(AmmError::PriceImpactExceeded.into());
+        }
+
+        let slot_price_move = if u64::from_le_bytes(self.price_move_slot) == slot {
+            u64::from_le_bytes(self.slot_price_move)
+        } else {
+            0
+        }
+        .saturating_add(price_change_bps);
+
+        let max_slot_price_move = u16::from_le_bytes(self.max_slot_price_move) as u64;
+        if max_slot_price_move != 0 && slot_price_move > max_slot_price_move {
+            return Err(AmmError::PriceImpactExceeded.into());
+        }
+
+        self.price_move_slot = slot.to_le_bytes();
+        self.slot_price_move = slot_price_move.to_le_bytes();
+
+        Ok(())
+    }
+
+    /// 是否为池子管理权限（零地址权限表示池子参数不可变）
+    #[inline(always)]
+    pub fn is_authority(&self, address: &Address) -> bool {
+        self.authority != [0u8; 32] && self.authority == *address.as_array()
+    }
+
+    /// 检查地址是否为暂停权限
+    #[inline(always)]
+    pub fn is_guardian(&self, address: &Address) -> bool {
+        self.guardian != [0u8; 32] && self.guardian == *address.as_array()
+    }
+
+    /// 是否为许可池子
+    #[inline(always)]
+    pub fn is_permissioned(&self) -> bool {
+        self.permissioned != 0
+    }
+
+    /// 是否已创建长期订单池
+    #[inline(always)]
+    pub fn has_virtual_orders(&self) -> bool {
+        self.virtual_orders != 0
+    }
+
+    /// 是否为 LBP 池子
+    #[inline(always)]
+    pub fn is_lbp(&self) -> bool {
+        self.start_weight_x != [0u8; 2]
+    }
+
+    /// LBP 发售是否已经结束（非 LBP 池子始终视为已结束）
+    #[inline(always)]
+    pub fn is_sale_over(&self, now: i64) -> bool {
+        !self.is_lbp() || now >= i64::from_le_bytes(self.weight_end_at)
+    }
+
+    /// now 时刻 X、Y 的权重（基点，两者之和为 10000）
+    ///
+    /// 非 LBP 池子两侧权重相等，即普通恒定乘积池子
+    pub fn weights(&self, now: i64) -> (u64, u64) {
+        if !self.is_lbp() {
+            return (BPS_DENOMINATOR / 2, BPS_DENOMINATOR / 2);
+        }
+
+        let start_weight = u16::from_le_bytes(self.start_weight_x) as i128;
+        let end_weight = u16::from_le_bytes(self.end_weight_x) as i128;
+        let start_at = i64::from_le_bytes(self.weight_start_at);
+        let end_at = i64::from_le_bytes(self.weight_end_at);
+
+        let elapsed = (now.clamp(start_at, end_at) - start_at) as i128;
+        let duration = (end_at - start_at) as i128;
+        let weight_x = (start_weight + (end_weight - start_weight) * elapsed / duration) as u64;
+
+        (weight_x, BPS_DENOMINATOR - weight_x)
+    }
+
+    /// 是否已创建限价单簿
+    #[inline(always)]
+    pub fn has_limit_orders(&self) -> bool {
+        self.limit_orders != 0
+    }
+
+    /// 金库中属于订单的 X 数量
+    #[inline(always)]
+    pub fn reserved_x(&self) -> u64 {
+        u64::from_le_bytes(self.reserved_x)
+    }
+
+    /// 金库中属于订单的 Y 数量
+    #[inline(always)]
+    pub fn reserved_y(&self) -> u64 {
+        u64::from_le_bytes(self.reserved_y)
+    }
+
+    /// 由金库余额计算池子的有效储备（扣除属于订单的部分）
+    #[inline(always)]
+    pub fn reserves(&self, balance_x: u64, balance_y: u64) -> (u64, u64) {
+        (
+            balance_x.saturating_sub(self.reserved_x()),
+            balance_y.saturating_sub(self.reserved_y()),
+        )
+    }
+
+    /// 增减属于订单的代币数量
+    ///
+    /// 订单资金转入金库或订单成交得到的代币记入时增加，
+    /// 订单成交卖出或用户领取时减少
+    pub fn adjust_reserved(&mut self, is_x: bool, add: u64, sub: u64) -> Result<(), ProgramError> {
+        let reserved = if is_x { &mut self.reserved_x } else { &mut self.reserved_y };
+        let amount = u64::from_le_bytes(*reserved)
+            .checked_add(add)
+            .and_then(|amount| amount.checked_sub(sub))
+            .ok_or(AmmError::ReservedOverflow)?;
+        *reserved = amount.to_le_bytes();
+
+        Ok(())
+    }
+
+    /// 是否设置了参考价格源
+    #[inline(always)]
+    pub fn has_oracle(&self) -> bool {
+        self.oracle != [0u8; 32]
+    }
+
+    /// 参考价格检查
+    ///
+    /// 价格源地址必须与 Config 一致且价格非零；设置了有效期时价格不得过期；
+    /// 成交价（Y/X，含费用和价格影响）相对参考价格的偏离不得超过 max_oracle_deviation
+    pub fn check_oracle(
+        &self,
+        feed_address: &Address,
+        feed: &PriceFeed,
+        now: i64,
+        is_x: bool,
+        amount_in: u64,
+        amount_out: u64,
+    ) -> Result<(), ProgramError> {
+        if feed_address.as_array() != &self.oracle || feed.price() == 0 {
+            return Err(AmmError::InvalidOracle.into());
+        }
+
+        let max_staleness = u64::from_le_bytes(self.max_oracle_staleness);
+        if max_staleness != 0 && now.saturating_sub(feed.updated_at()) as i128 > max_staleness as i128 {
+            return Err(AmmError::OracleStale.into());
+        }
+
+        let max_deviation = u16::from_le_bytes(self.max_oracle_deviation) as u64;
+        if execution_deviation_bps(is_x, amount_in, amount_out, feed.price()) > max_deviation {
+            return Err(AmmError::OracleDeviationExceeded.into());
+        }
+
+        Ok(())
+    }
+
+    /// 检查 AMM 状态
+    #[inline(always)]
+    pub fn is_initialized(&self) -> bool {
+        self.state == AmmState::Initialized as u8
+    }
+
+    /// 检查是否可以提取
+    #[inline(always)]
+    pub fn can_withdraw(&self) -> bool {
+        self.state == AmmState::Initialized as u8 || self.state == AmmState::WithdrawOnly as u8
+    }
+
+    /// 池子的 LP Mint 地址
+    ///
+    /// 旧布局没有记录 LP Mint（全零），按规范 bump 派生 PDA ["mint_lp", config]
+    pub fn mint_lp_address(&self, config: &Address, program_id: &Address) -> Address {
+        if self.mint_lp != [0u8; 32] {
+            return Address::new_from_array(self.mint_lp);
+        }
+
+        Address::find_program_address(&[b"mint_lp", config.as_ref()], program_id).0
+    }
+
+    /// 获取 mint_x 作为 Address
+    #[inline(always)]
+    pub fn mint_x_address(&self) -> Address {
+        Address::new_from_array(self.mint_x)
+    }
+
+    /// 获取 mint_y 作为 Address
+    #[inline(always)]
+    pub fn mint_y_address(&self) -> Address {
+        Address::new_from_array(self.mint_y)
+    }
+}
+
+// 结构体布局必须与账户中的紧凑布局一致
+const _: () = assert!(size_of::<Config>() == Config::LEN);
@@ -0,0 +1,218 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+use crate::error::AmmError;
+
+/// 奖励累加器的精度（每份 LP 的累计奖励放大倍数）
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// LP 质押奖励池
+///
+/// PDA 种子：["farm", mint_lp, reward_mint]
+/// 质押金库和奖励金库都是该 PDA 的关联代币账户；
+/// 奖励按 reward_rate（每秒）线性释放，按质押份额分配。
+/// 释放量不超过奖励金库中尚未分配的余额，金库耗尽后停止释放，重新注资后继续
+#[repr(C)]
+pub struct Farm {
+    pub config: [u8; 32],                   // 所属池子的 Config 地址
+    pub mint_lp: [u8; 32],                  // 质押的 LP Mint
+    pub reward_mint: [u8; 32],              // 奖励代币 Mint
+    pub reward_rate: [u8; 8],               // 每秒释放的奖励数量
+    pub acc_reward_per_share: [u8; 16],     // 每份 LP 的累计奖励（乘以 REWARD_PRECISION）
+    pub last_update: [u8; 8],               // 累加器最后更新时间（Unix 时间戳）
+    pub total_staked: [u8; 8],              // 当前质押的 LP 总量
+    pub reward_owed: [u8; 8],               // 已释放给质押者但尚未领取的奖励
+    pub bump: u8,                           // PDA bump seed
+}
+
+impl Farm {
+    /// Farm 结构的大小（字节）
+    pub const LEN: usize = 32                     // config
+        + 32                                      // mint_lp
+        + 32                                      // reward_mint
+        + size_of::<u64>()                        // reward_rate
+        + size_of::<u128>()                       // acc_reward_per_share
+        + size_of::<i64>()                        // last_update
+        + size_of::<u64>()                        // total_staked
+        + size_of::<u64>()                        // reward_owed
+        + size_of::<u8>();                        // bump
+
+    /// PDA 种子前缀
+    pub const SEED: &'static [u8] = b"farm";
+
+    /// 从字节数组加载 Farm（不可变）
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// 从字节数组加载 Farm（可变）
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// 根据存储的 bump 派生 farm PDA 地址
+    #[inline(always)]
+    pub fn derive_address(&self, program_id: &Address) -> Address {
+        Address::derive_address(&[Self::SEED, &self.mint_lp, &self.reward_mint], Some(self.bump), program_id)
+    }
+
+    /// 获取每份 LP 的累计奖励
+    #[inline(always)]
+    pub fn acc_reward_per_share(&self) -> u128 {
+        u128::from_le_bytes(self.acc_reward_per_share)
+    }
+
+    /// 获取当前质押的 LP 总量
+    #[inline(always)]
+    pub fn total_staked(&self) -> u64 {
+        u64::from_le_bytes(self.total_staked)
+    }
+
+    /// 获取已释放但尚未领取的奖励
+    #[inline(always)]
+    pub fn reward_owed(&self) -> u64 {
+        u64::from_le_bytes(self.reward_owed)
+    }
+
+    /// 将累加器推进到 now
+    ///
+    /// 释放量为 elapsed * reward_rate，但不超过奖励金库余额 reward_balance 中
+    /// 尚未分配的部分，因此已释放的奖励始终有足额的代币；超出余额的部分和
+    /// 无人质押期间的奖励不计入累加器，之后也不补发
+    pub fn update(&mut self, now: i64, reward_balance: u64) -> Result<(), ProgramError> {
+        let last_update = i64::from_le_bytes(self.last_update);
+        if now <= last_update {
+            return Ok(());
+        }
+
+        let total_staked = self.total_staked();
+        if total_staked > 0 {
+            let elapsed = (now - last_update) as u128;
+            let available = reward_balance.saturating_sub(self.reward_owed()) as u128;
+            let released = elapsed
+                .checked_mul(u64::from_le_bytes(self.reward_rate) as u128)
+                .ok_or(AmmError::RewardOverflow)?
+                .min(available);
+            let acc_reward_per_share = self
+                .acc_reward_per_share()
+                .checked_add(released * REWARD_PRECISION / total_staked as u128)
+                .ok_or(AmmError::RewardOverflow)?;
+            self.acc_reward_per_share = acc_reward_per_share.to_le_bytes();
+            // released 不超过 available，加上 reward_owed 后不超过 reward_balance
+            self.reward_owed = (self.reward_owed() + released as u64).to_le_bytes();
+        }
+        self.last_update = now.to_le_bytes();
+
+        Ok(())
+    }
+
+    /// 记录已发放的奖励
+    ///
+    /// 仓位结算按各自的质押数量取整，发放总量可能比 reward_owed 多出最小单位的舍入误差
+    #[inline(always)]
+    pub fn pay(&mut self, amount: u64) {
+        self.reward_owed = self.reward_owed().saturating_sub(amount).to_le_bytes();
+    }
+}
+
+/// 用户在某个 farm 中的质押仓位
+///
+/// PDA 种子：["stake", farm, owner]
+#[repr(C)]
+pub struct StakePosition {
+    pub farm: [u8; 32],                     // 所属 farm 地址
+    pub owner: [u8; 32],                    // 仓位所有者
+    pub amount: [u8; 8],                    // 质押的 LP 数量
+    pub reward_debt: [u8; 16],              // amount * acc_reward_per_share（已结算部分）
+    pub pending_rewards: [u8; 8],           // 已结算但尚未领取的奖励
+    pub bump: u8,                           // PDA bump seed
+}
+
+impl StakePosition {
+    /// StakePosition 结构的大小（字节）
+    pub const LEN: usize = 32                     // farm
+        + 32                                      // owner
+        + size_of::<u64>()                        // amount
+        + size_of::<u128>()                       // reward_debt
+        + size_of::<u64>()                        // pending_rewards
+        + size_of::<u8>();                        // bump
+
+    /// PDA 种子前缀
+    pub const SEED: &'static [u8] = b"stake";
+
+    /// 从字节数组加载 StakePosition（不可变）
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// 从字节数组加载 StakePosition（可变）
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// 根据存储的 bump 派生仓位 PDA 地址
+    #[inline(always)]
+    pub fn derive_address(&self, program_id: &Address) -> Address {
+        Address::derive_address(&[Self::SEED, &self.farm, &self.owner], Some(self.bump), program_id)
+    }
+
+    /// 获取质押的 LP 数量
+    #[inline(always)]
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    /// 获取已结算但尚未领取的奖励
+    #[inline(always)]
+    pub fn pending_rewards(&self) -> u64 {
+        u64::from_le_bytes(self.pending_rewards)
+    }
+
+    /// 按 farm 当前的累加器结算奖励，并把质押数量改为 amount
+    ///
+    /// 调用前必须先执行 Farm::update
+    pub fn settle(&mut self, acc_reward_per_share: u128, amount: u64) -> Result<(), ProgramError> {
+        let accrued = (self.amount() as u128)
+            .checked_mul(acc_reward_per_share)
+            .ok_or(AmmError::RewardOverflow)?
+            / REWARD_PRECISION;
+        let earned = accrued.saturating_sub(u128::from_le_bytes(self.reward_debt));
+        let pending_rewards = (self.pending_rewards() as u128)
+            .checked_add(earned)
+            .and_then(|pending| u64::try_from(pending).ok())
+            .ok_or(AmmError::RewardOverflow)?;
+
+        let reward_debt = (amount as u128)
+            .checked_mul(acc_reward_per_share)
+            .ok_or(AmmError::RewardOverflow)?
+            / REWARD_PRECISION;
+
+        self.amount = amount.to_le_bytes();
+        self.reward_debt = reward_debt.to_le_bytes();
+        self.pending_rewards = pending_rewards.to_le_bytes();
+
+        Ok(())
+    }
+}
+
+const _: () = assert!(size_of::<Farm>() == Farm::LEN);
+const _: () = assert!(size_of::<StakePosition>() == StakePosition::LEN);
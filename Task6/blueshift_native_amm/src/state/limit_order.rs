@@ -0,0 +1,242 @@
+use core::{cmp::Ordering, mem::size_of};
+use pinocchio::{error::ProgramError, Address};
+use blueshift_amm_math::{
+    check_invariant, compare_spot_price, limit_fill_input, mul_div_ceil, mul_div_floor, quote_swap, MathError,
+    BPS_DENOMINATOR,
+};
+use crate::error::{math_error, AmmError};
+use super::Config;
+
+/// 每个池子同时挂单的数量上限
+pub const MAX_LIMIT_ORDERS: usize = 32;
+/// 限价的精度（每单位 X 对应的 Y 乘以该倍数）
+pub const PRICE_PRECISION: u128 = 1_000_000_000_000;
+
+/// 限价单状态
+#[repr(u8)]
+pub enum LimitOrderStatus {
+    Empty = 0u8,    // 空位
+    Open = 1u8,     // 等待成交（可能已部分成交）
+    Filled = 2u8,   // 已全部成交或已取消剩余部分，等待领取
+}
+
+/// 单个限价单
+#[repr(C)]
+pub struct LimitOrder {
+    pub owner: [u8; 32],        // 挂单者
+    pub status: u8,             // 状态（LimitOrderStatus）
+    pub is_x: u8,               // 1 = 卖出 X 换 Y，0 = 卖出 Y 换 X
+    pub amount: [u8; 8],        // 尚未卖出的托管数量
+    pub price: [u8; 16],        // 限价：每单位 X 对应的 Y（乘以 PRICE_PRECISION）
+    pub proceeds: [u8; 8],      // 累计成交所得，领取前留在金库中
+}
+
+impl LimitOrder {
+    /// 是否卖出 X
+    #[inline(always)]
+    pub fn is_x(&self) -> bool {
+        self.is_x != 0
+    }
+
+    /// 获取托管的卖出数量
+    #[inline(always)]
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    /// 获取限价
+    #[inline(always)]
+    pub fn price(&self) -> u128 {
+        u128::from_le_bytes(self.price)
+    }
+
+    /// 获取成交所得
+    #[inline(always)]
+    pub fn proceeds(&self) -> u64 {
+        u64::from_le_bytes(self.proceeds)
+    }
+
+    /// 输入侧的限价（每单位卖出代币至少换得的另一侧数量），返回 (分子, 分母)
+    #[inline(always)]
+    pub fn limit(&self) -> (u128, u128) {
+        if self.is_x() {
+            (self.price(), PRICE_PRECISION)
+        } else {
+            (PRICE_PRECISION, self.price())
+        }
+    }
+
+    /// 按限价计算卖出 amount 时至少应得的数量，向下取整
+    pub fn min_output(&self, amount: u64) -> Result<u64, MathError> {
+        let (limit_num, limit_den) = self.limit();
+        mul_div_floor(amount as u128, limit_num, limit_den)
+            .and_then(|output| u64::try_from(output).ok())
+            .ok_or(MathError::OrderProceedsOverflow)
+    }
+
+    /// 池子扣费后的边际价格是否已经越过限价
+    ///
+    /// 卖出 X 的订单在现货价格 * (1 - fee) > price 时成交，卖出 Y 的订单在
+    /// 现货价格 < price * (1 - fee) 时成交；现货价格按两侧权重计算，非 LBP 池子即 y / x。
+    /// 落在费用区间内的订单无法成交，在这里排除，不进入 limit_fill_input 的对数计算；
+    /// 阈值取整只会把边界上的订单判为越过，由 limit_fill_input 精确计算
+    pub fn is_crossed(&self, reserve_x: u64, reserve_y: u64, weight_x: u64, weight_y: u64, fee_bps: u16) -> bool {
+        if reserve_x == 0 || reserve_y == 0 || fee_bps as u64 >= BPS_DENOMINATOR {
+            return false;
+        }
+
+        let keep = (BPS_DENOMINATOR - fee_bps as u64) as u128;
+        if self.is_x() {
+            // 阈值 price / (1 - fee) 向下取整；溢出时限价远高于任何现货价格
+            let Some(threshold) = mul_div_floor(self.price(), BPS_DENOMINATOR as u128, keep) else {
+                return false;
+            };
+            compare_spot_price(reserve_x, reserve_y, weight_x, weight_y, threshold, PRICE_PRECISION) == Ordering::Greater
+        } else {
+            // 阈值 price * (1 - fee) 向上取整，不超过 price，不会溢出
+            let Some(threshold) = mul_div_ceil(self.price(), keep, BPS_DENOMINATOR as u128) else {
+                return false;
+            };
+            compare_spot_price(reserve_x, reserve_y, weight_x, weight_y, threshold, PRICE_PRECISION) == Ordering::Less
+        }
+    }
+}
+
+/// 池子的限价单簿
+///
+/// PDA 种子：["orders", config]
+/// 托管的代币和成交所得都留在池子金库中，记录在 Config 的 reserved_x/reserved_y
+#[repr(C)]
+pub struct LimitOrderBook {
+    pub config: [u8; 32],                       // 所属池子的 Config 地址
+    pub bump: u8,                               // PDA bump seed
+    pub orders: [LimitOrder; MAX_LIMIT_ORDERS], // 订单槽位
+}
+
+impl LimitOrderBook {
+    /// LimitOrderBook 结构的大小（字节）
+    pub const LEN: usize = 32                     // config
+        + size_of::<u8>()                         // bump
+        + (32 + size_of::<u8>() * 2 + size_of::<u64>() * 2 + size_of::<u128>()) * MAX_LIMIT_ORDERS; // orders
+
+    /// PDA 种子前缀
+    pub const SEED: &'static [u8] = b"orders";
+
+    /// 从字节数组加载 LimitOrderBook（不可变）
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// 从字节数组加载 LimitOrderBook（可变）
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// 根据存储的 bump 派生订单簿 PDA 地址
+    #[inline(always)]
+    pub fn derive_address(&self, program_id: &Address) -> Address {
+        Address::derive_address(&[Self::SEED, &self.config], Some(self.bump), program_id)
+    }
+
+    /// 写入新订单，返回槽位编号
+    pub fn push(&mut self, owner: &Address, is_x: bool, amount: u64, price: u128) -> Result<u8, ProgramError> {
+        let index = self
+            .orders
+            .iter()
+            .position(|order| order.status == LimitOrderStatus::Empty as u8)
+            .ok_or(AmmError::OrderSlotsFull)?;
+
+        let order = &mut self.orders[index];
+        order.owner.copy_from_slice(owner.as_ref());
+        order.status = LimitOrderStatus::Open as u8;
+        order.is_x = is_x as u8;
+        order.amount = amount.to_le_bytes();
+        order.price = price.to_le_bytes();
+        order.proceeds = [0u8; 8];
+
+        Ok(index as u8)
+    }
+
+    /// 成交所有价格已被越过的订单
+    ///
+    /// 订单与池子其他交换一样按（加权）恒定乘积曲线和当前生效费用成交，
+    /// 每次只卖出曲线能够承接的部分：成交后卖出侧扣费后的边际价格仍不低于限价，
+    /// 因此平均成交价不差于限价，剩余部分继续挂单。卖出的代币并入储备，
+    /// 换得的代币记为属于订单，等待挂单者领取。每次成交后检查恒定乘积没有变小。
+    /// 池子暂停或仅限提取期间不撮合
+    pub fn fill(&mut self, config: &mut Config, balance_x: u64, balance_y: u64, now: i64) -> Result<(), ProgramError> {
+        if !config.is_initialized() {
+            return Ok(());
+        }
+
+        let (mut reserve_x, mut reserve_y) = config.reserves(balance_x, balance_y);
+        let (weight_x, weight_y) = config.weights(now);
+        let fee = config.effective_fee(now);
+
+        for order in self.orders.iter_mut() {
+            if order.status != LimitOrderStatus::Open as u8 || !order.is_crossed(reserve_x, reserve_y, weight_x, weight_y, fee) {
+                continue;
+            }
+
+            let is_x = order.is_x();
+            let (reserve_in, reserve_out, weight_in, weight_out) = if is_x {
+                (reserve_x, reserve_y, weight_x, weight_y)
+            } else {
+                (reserve_y, reserve_x, weight_y, weight_x)
+            };
+
+            // 曲线在扣费后的边际价格到达限价之前能承接的数量
+            let (limit_num, limit_den) = order.limit();
+            let Ok(depth) = limit_fill_input(reserve_in, reserve_out, weight_in, weight_out, limit_num, limit_den, fee) else {
+                continue;
+            };
+            let amount_in = order.amount().min(depth);
+            if amount_in == 0 {
+                continue;
+            }
+
+            // 无法报价或成交价差于限价的订单保持挂单，不影响其他订单和交换
+            let (Ok(quote), Ok(min_output)) = (
+                quote_swap(reserve_in, reserve_out, weight_in, weight_out, amount_in, fee),
+                order.min_output(amount_in),
+            ) else {
+                continue;
+            };
+            let output = quote.amount_out;
+            if output == 0 || output < min_output {
+                continue;
+            }
+
+            let new_reserve_in = reserve_in.checked_add(amount_in).ok_or(math_error(MathError::SwapOverflow))?;
+            let new_reserve_out = reserve_out - output;
+            let (new_x, new_y) = if is_x { (new_reserve_in, new_reserve_out) } else { (new_reserve_out, new_reserve_in) };
+            check_invariant(reserve_x, reserve_y, new_x, new_y, weight_x, weight_y).map_err(math_error)?;
+            (reserve_x, reserve_y) = (new_x, new_y);
+
+            config.adjust_reserved(is_x, 0, amount_in)?;
+            config.adjust_reserved(!is_x, output, 0)?;
+
+            let remaining = order.amount() - amount_in;
+            let proceeds = order.proceeds().checked_add(output).ok_or(math_error(MathError::OrderProceedsOverflow))?;
+            if remaining == 0 {
+                order.status = LimitOrderStatus::Filled as u8;
+            }
+            order.amount = remaining.to_le_bytes();
+            order.proceeds = proceeds.to_le_bytes();
+        }
+
+        Ok(())
+    }
+}
+
+const _: () = assert!(size_of::<LimitOrderBook>() == LimitOrderBook::LEN);
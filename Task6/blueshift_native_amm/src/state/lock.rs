@@ -0,0 +1,76 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+
+/// 时间锁定的 LP 仓位
+///
+/// PDA 种子：["lock", mint_lp, owner]
+/// 锁定的 LP 存放在该 PDA 的关联代币账户中；账户公开可读，
+/// 任何人都可以在链上核验锁定的是哪个池子的 LP、锁定数量和解锁时间
+#[repr(C)]
+pub struct LpLock {
+    pub config: [u8; 32],       // 所属池子的 Config 地址
+    pub owner: [u8; 32],        // 锁仓所有者
+    pub mint_lp: [u8; 32],      // 锁定的 LP Mint（该池子的 LP Mint PDA）
+    pub amount: [u8; 8],        // 锁定的 LP 数量
+    pub unlock_at: [u8; 8],     // 解锁时间（Unix 时间戳）
+    pub bump: u8,               // PDA bump seed
+}
+
+impl LpLock {
+    /// LpLock 结构的大小（字节）
+    pub const LEN: usize = 32                     // config
+        + 32                                      // owner
+        + 32                                      // mint_lp
+        + size_of::<u64>()                        // amount
+        + size_of::<i64>()                        // unlock_at
+        + size_of::<u8>();                        // bump
+
+    /// PDA 种子前缀
+    pub const SEED: &'static [u8] = b"lock";
+
+    /// 从字节数组加载 LpLock（不可变）
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// 从字节数组加载 LpLock（可变）
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// 根据存储的 bump 派生锁仓 PDA 地址
+    #[inline(always)]
+    pub fn derive_address(&self, program_id: &Address) -> Address {
+        Address::derive_address(&[Self::SEED, &self.mint_lp, &self.owner], Some(self.bump), program_id)
+    }
+
+    /// 获取锁定的 LP 数量
+    #[inline(always)]
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    /// 获取解锁时间
+    #[inline(always)]
+    pub fn unlock_at(&self) -> i64 {
+        i64::from_le_bytes(self.unlock_at)
+    }
+
+    /// 在 now 时刻是否仍处于锁定状态
+    #[inline(always)]
+    pub fn is_locked(&self, now: i64) -> bool {
+        self.amount() > 0 && now < self.unlock_at()
+    }
+}
+
+const _: () = assert!(size_of::<LpLock>() == LpLock::LEN);
@@ -0,0 +1,19 @@
+pub mod allow_list;
+pub mod config;
+pub mod farm;
+pub mod limit_order;
+pub mod lock;
+pub mod price_feed;
+pub mod registry;
+pub mod settings;
+pub mod twamm;
+
+pub use allow_list::*;
+pub use config::*;
+pub use farm::*;
+pub use limit_order::*;
+pub use lock::*;
+pub use price_feed::*;
+pub use registry::*;
+pub use settings::*;
+pub use twamm::*;
@@ -0,0 +1,45 @@
+use core::mem::size_of;
+use pinocchio::error::ProgramError;
+
+/// 参考价格源
+///
+/// 程序只读取、从不写入的简单布局，可以由外部预言机的适配程序或本地替身产生：
+/// - [0..8]: price (u64 LE) - 每个 X 最小单位对应的 Y 最小单位数量 * `PRICE_SCALE`（10^9）
+/// - [8..16]: updated_at (i64 LE) - 价格更新时间（unix 时间戳）
+///
+/// 账户数据可以更长，只读取前 16 字节；账户地址由池子管理权限写入 Config
+#[repr(C)]
+pub struct PriceFeed {
+    pub price: [u8; 8],         // 参考价格（Y/X，精度 10^9）
+    pub updated_at: [u8; 8],    // 价格更新时间
+}
+
+impl PriceFeed {
+    /// PriceFeed 结构的大小（字节）
+    pub const LEN: usize = size_of::<u64>()      // price
+        + size_of::<i64>();                       // updated_at
+
+    /// 从字节数组加载 PriceFeed（不可变）
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// 参考价格
+    #[inline(always)]
+    pub fn price(&self) -> u64 {
+        u64::from_le_bytes(self.price)
+    }
+
+    /// 价格更新时间
+    #[inline(always)]
+    pub fn updated_at(&self) -> i64 {
+        i64::from_le_bytes(self.updated_at)
+    }
+}
+
+const _: () = assert!(size_of::<PriceFeed>() == PriceFeed::LEN);
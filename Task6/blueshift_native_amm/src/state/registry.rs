@@ -0,0 +1,131 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+use crate::error::AmmError;
+
+/// 每个注册表页容纳的池子数量
+pub const POOLS_PER_PAGE: usize = 32;
+
+/// 注册表条目：一个池子的 Config 地址及其交易对和费率
+#[repr(C)]
+pub struct RegistryEntry {
+    pub config: [u8; 32],   // 池子 Config 地址
+    pub mint_x: [u8; 32],   // 代币 X 的 Mint
+    pub mint_y: [u8; 32],   // 代币 Y 的 Mint
+    pub fee: [u8; 2],       // 交换费用（基点）
+}
+
+impl RegistryEntry {
+    /// RegistryEntry 结构的大小（字节）
+    pub const LEN: usize = 32 + 32 + 32 + size_of::<u16>();
+
+    /// 获取 Config 地址
+    #[inline(always)]
+    pub fn config_address(&self) -> Address {
+        Address::new_from_array(self.config)
+    }
+
+    /// 获取交换费用（基点）
+    #[inline(always)]
+    pub fn fee(&self) -> u16 {
+        u16::from_le_bytes(self.fee)
+    }
+
+    /// 检查池子是否包含指定的 mint
+    #[inline(always)]
+    pub fn has_mint(&self, mint: &Address) -> bool {
+        self.mint_x == *mint.as_array() || self.mint_y == *mint.as_array()
+    }
+}
+
+/// 池子注册表页
+///
+/// PDA 种子：["registry", page (u32 LE)]
+/// 第 n 个池子写入第 n / POOLS_PER_PAGE 页。客户端先读取 settings 中的
+/// `pool_count` 得到页数，再逐页 getAccountInfo 即可枚举全部池子
+#[repr(C)]
+pub struct RegistryPage {
+    pub page: [u8; 4],                              // 页码
+    pub count: u8,                                  // 本页已使用的条目数
+    pub bump: u8,                                   // PDA bump seed
+    pub entries: [RegistryEntry; POOLS_PER_PAGE],   // 池子条目
+}
+
+impl RegistryPage {
+    /// RegistryPage 结构的大小（字节）
+    pub const LEN: usize = size_of::<u32>()       // page
+        + size_of::<u8>()                         // count
+        + size_of::<u8>()                         // bump
+        + RegistryEntry::LEN * POOLS_PER_PAGE;    // entries
+
+    /// PDA 种子前缀
+    pub const SEED: &'static [u8] = b"registry";
+
+    /// 从字节数组加载 RegistryPage（不可变）
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// 从字节数组加载 RegistryPage（可变）
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// 获取页码
+    #[inline(always)]
+    pub fn page(&self) -> u32 {
+        u32::from_le_bytes(self.page)
+    }
+
+    /// 根据页码和存储的 bump 派生注册表页 PDA 地址
+    #[inline(always)]
+    pub fn derive_address(&self, program_id: &Address) -> Address {
+        Address::derive_address(&[Self::SEED, &self.page], Some(self.bump), program_id)
+    }
+
+    /// 本页已写入的条目
+    #[inline(always)]
+    pub fn entries(&self) -> &[RegistryEntry] {
+        &self.entries[..(self.count as usize).min(POOLS_PER_PAGE)]
+    }
+
+    /// 本页中包含指定 mint 的池子
+    #[inline(always)]
+    pub fn pools_for_mint<'a>(&'a self, mint: &'a Address) -> impl Iterator<Item = &'a RegistryEntry> {
+        self.entries().iter().filter(move |entry| entry.has_mint(mint))
+    }
+
+    /// 追加一个池子条目
+    pub fn push(
+        &mut self,
+        config: &Address,
+        mint_x: &Address,
+        mint_y: &Address,
+        fee: u16,
+    ) -> Result<(), ProgramError> {
+        let index = self.count as usize;
+        if index >= POOLS_PER_PAGE {
+            return Err(AmmError::RegistryFull.into());
+        }
+
+        let entry = &mut self.entries[index];
+        entry.config.copy_from_slice(config.as_ref());
+        entry.mint_x.copy_from_slice(mint_x.as_ref());
+        entry.mint_y.copy_from_slice(mint_y.as_ref());
+        entry.fee = fee.to_le_bytes();
+        self.count += 1;
+
+        Ok(())
+    }
+}
+
+const _: () = assert!(size_of::<RegistryPage>() == RegistryPage::LEN);
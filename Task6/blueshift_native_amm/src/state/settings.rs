@@ -0,0 +1,105 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+
+/// 最多允许配置的费率档位数量
+pub const MAX_FEE_TIERS: usize = 8;
+
+/// 全局 AMM 设置
+///
+/// PDA 种子：["settings"]，整个程序只有一个
+/// 记录允许使用的费率档位，`initialize` 只接受列表中的费率；
+/// 同时记录已创建的池子总数，用于定位注册表的当前页
+#[repr(C)]
+pub struct AmmSettings {
+    pub authority: [u8; 32],                    // 设置管理权限
+    pub fee_tier_count: u8,                     // 已启用的费率档位数量
+    pub fee_tiers: [[u8; 2]; MAX_FEE_TIERS],    // 费率档位（基点）
+    pub bump: u8,                               // PDA bump seed
+    pub pool_count: [u8; 8],                    // 已注册的池子总数
+}
+
+impl AmmSettings {
+    /// AmmSettings 结构的大小（字节）
+    pub const LEN: usize = 32                     // authority
+        + size_of::<u8>()                         // fee_tier_count
+        + size_of::<u16>() * MAX_FEE_TIERS        // fee_tiers
+        + size_of::<u8>()                         // bump
+        + size_of::<u64>();                       // pool_count
+
+    /// PDA 种子前缀
+    pub const SEED: &'static [u8] = b"settings";
+
+    /// 从字节数组加载 AmmSettings（不可变）
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// 从字节数组加载 AmmSettings（可变）
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// 根据存储的 bump 派生 settings PDA 地址
+    #[inline(always)]
+    pub fn derive_address(&self, program_id: &Address) -> Address {
+        Address::derive_address(&[Self::SEED], Some(self.bump), program_id)
+    }
+
+    /// 获取管理权限
+    #[inline(always)]
+    pub fn authority_address(&self) -> Address {
+        Address::new_from_array(self.authority)
+    }
+
+    /// 获取已注册的池子总数
+    #[inline(always)]
+    pub fn pool_count(&self) -> u64 {
+        u64::from_le_bytes(self.pool_count)
+    }
+
+    /// 写入费率档位列表
+    ///
+    /// 档位必须严格递增、不为 0 且不超过 10000 基点
+    pub fn set_fee_tiers(&mut self, fee_tiers: &[u16]) -> Result<(), ProgramError> {
+        if fee_tiers.is_empty() || fee_tiers.len() > MAX_FEE_TIERS {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut previous = 0u16;
+        for &tier in fee_tiers {
+            if tier <= previous || tier > 10_000 {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            previous = tier;
+        }
+
+        self.fee_tiers = [[0u8; 2]; MAX_FEE_TIERS];
+        for (slot, tier) in self.fee_tiers.iter_mut().zip(fee_tiers) {
+            *slot = tier.to_le_bytes();
+        }
+        self.fee_tier_count = fee_tiers.len() as u8;
+
+        Ok(())
+    }
+
+    /// 检查费率是否在允许的档位列表中
+    #[inline(always)]
+    pub fn is_fee_allowed(&self, fee: u16) -> bool {
+        let count = (self.fee_tier_count as usize).min(MAX_FEE_TIERS);
+        self.fee_tiers[..count]
+            .iter()
+            .any(|tier| u16::from_le_bytes(*tier) == fee)
+    }
+}
+
+const _: () = assert!(size_of::<AmmSettings>() == AmmSettings::LEN);
@@ -0,0 +1,572 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+use blueshift_amm_math::{mul_div_ceil, mul_div_floor, quote_swap, MathError};
+use crate::error::{math_error, AmmError};
+use super::Config;
+
+/// 长期订单的到期时间必须对齐到该间隔（秒）
+pub const ORDER_INTERVAL: i64 = 3600;
+/// 同时存在的到期时间数量上限
+pub const MAX_ORDER_EXPIRIES: usize = 32;
+/// 卖出速率的精度（每秒卖出数量乘以该倍数）
+pub const RATE_PRECISION: u128 = 1_000_000_000;
+/// 累计收益和累计卖出量的精度
+pub const EARNINGS_PRECISION: u128 = 1_000_000_000_000;
+
+/// 单个长期订单或限价单的最小卖出数量：千分之一个完整代币，至少 1 个最小单位
+///
+/// 到期时间和订单簿槽位有限，按 decimals 缩放使粉尘订单无法低成本占满槽位
+pub fn min_order_amount(decimals: u8) -> u64 {
+    10u64.checked_pow(decimals.saturating_sub(3) as u32).unwrap_or(u64::MAX)
+}
+
+/// 某个到期时间上的订单汇总
+///
+/// 到期时从池子的卖出速率中扣除 sell_rate_x/sell_rate_y，
+/// 并记录当时的累计收益和累计卖出量，供到期后的订单结算
+#[repr(C)]
+pub struct OrderExpiry {
+    pub end_at: [u8; 8],            // 到期时间（Unix 时间戳，ORDER_INTERVAL 的整数倍）
+    pub sell_rate_x: [u8; 16],      // 该时间到期的 X 卖出速率之和
+    pub sell_rate_y: [u8; 16],      // 该时间到期的 Y 卖出速率之和
+    pub earnings_x: [u8; 16],       // 到期时刻 earnings_x 的快照
+    pub earnings_y: [u8; 16],       // 到期时刻 earnings_y 的快照
+    pub sold_x: [u8; 16],           // 到期时刻 sold_x 的快照
+    pub sold_y: [u8; 16],           // 到期时刻 sold_y 的快照
+    pub orders: [u8; 4],            // 引用该到期时间且尚未关闭的订单数量（0 表示空位）
+}
+
+impl OrderExpiry {
+    /// 获取到期时间
+    #[inline(always)]
+    pub fn end_at(&self) -> i64 {
+        i64::from_le_bytes(self.end_at)
+    }
+
+    /// 获取引用该到期时间的订单数量
+    #[inline(always)]
+    pub fn orders(&self) -> u32 {
+        u32::from_le_bytes(self.orders)
+    }
+}
+
+/// 池子的长期订单（TWAMM）汇总状态
+///
+/// PDA 种子：["twamm", config]
+/// 所有未到期订单按各自的速率持续卖入池子，这里只记录两个方向的速率之和、
+/// 每单位速率的累计收益和实际卖出量；单个订单的成交结果由速率和累计值的差值计算。
+/// unsold_x/unsold_y 和 owed_x/owed_y 是长期订单在 Config reserved_x/reserved_y 中的份额，
+/// 最后一个订单关闭时把取整留下的余数归还池子储备
+#[repr(C)]
+pub struct VirtualOrderPool {
+    pub config: [u8; 32],           // 所属池子的 Config 地址
+    pub last_executed: [u8; 8],     // 订单最后结算时间（Unix 时间戳）
+    pub sell_rate_x: [u8; 16],      // 当前 X 卖出速率之和（乘以 RATE_PRECISION）
+    pub sell_rate_y: [u8; 16],      // 当前 Y 卖出速率之和（乘以 RATE_PRECISION）
+    pub earnings_x: [u8; 16],       // 每单位 X 卖出速率累计获得的 Y（乘以 EARNINGS_PRECISION）
+    pub earnings_y: [u8; 16],       // 每单位 Y 卖出速率累计获得的 X（乘以 EARNINGS_PRECISION）
+    pub sold_x: [u8; 16],           // 每单位 X 卖出速率实际卖出的 X（乘以 EARNINGS_PRECISION）
+    pub sold_y: [u8; 16],           // 每单位 Y 卖出速率实际卖出的 Y（乘以 EARNINGS_PRECISION）
+    pub unsold_x: [u8; 8],          // 订单存入且尚未卖出、尚未退回的 X
+    pub unsold_y: [u8; 8],          // 订单存入且尚未卖出、尚未退回的 Y
+    pub owed_x: [u8; 8],            // 订单换得且尚未领取的 X
+    pub owed_y: [u8; 8],            // 订单换得且尚未领取的 Y
+    pub bump: u8,                   // PDA bump seed
+    pub expiries: [OrderExpiry; MAX_ORDER_EXPIRIES], // 按到期时间汇总的订单
+}
+
+impl VirtualOrderPool {
+    /// VirtualOrderPool 结构的大小（字节）
+    pub const LEN: usize = 32                     // config
+        + size_of::<i64>()                        // last_executed
+        + size_of::<u128>()                       // sell_rate_x
+        + size_of::<u128>()                       // sell_rate_y
+        + size_of::<u128>()                       // earnings_x
+        + size_of::<u128>()                       // earnings_y
+        + size_of::<u128>()                       // sold_x
+        + size_of::<u128>()                       // sold_y
+        + size_of::<u64>() * 4                    // unsold_x, unsold_y, owed_x, owed_y
+        + size_of::<u8>()                         // bump
+        + (size_of::<i64>() + size_of::<u128>() * 6 + size_of::<u32>()) * MAX_ORDER_EXPIRIES; // expiries
+
+    /// PDA 种子前缀
+    pub const SEED: &'static [u8] = b"twamm";
+
+    /// 从字节数组加载 VirtualOrderPool（不可变）
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// 从字节数组加载 VirtualOrderPool（可变）
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// 根据存储的 bump 派生长期订单池 PDA 地址
+    #[inline(always)]
+    pub fn derive_address(&self, program_id: &Address) -> Address {
+        Address::derive_address(&[Self::SEED, &self.config], Some(self.bump), program_id)
+    }
+
+    /// 获取订单最后结算时间
+    #[inline(always)]
+    pub fn last_executed(&self) -> i64 {
+        i64::from_le_bytes(self.last_executed)
+    }
+
+    /// 获取某个方向的当前卖出速率之和
+    #[inline(always)]
+    pub fn sell_rate(&self, is_x: bool) -> u128 {
+        u128::from_le_bytes(if is_x { self.sell_rate_x } else { self.sell_rate_y })
+    }
+
+    /// 获取某个方向每单位速率的累计收益
+    #[inline(always)]
+    pub fn earnings(&self, is_x: bool) -> u128 {
+        u128::from_le_bytes(if is_x { self.earnings_x } else { self.earnings_y })
+    }
+
+    /// 获取某个方向每单位速率的累计卖出量
+    #[inline(always)]
+    pub fn sold(&self, is_x: bool) -> u128 {
+        u128::from_le_bytes(if is_x { self.sold_x } else { self.sold_y })
+    }
+
+    /// 获取某个方向尚未卖出的订单资金
+    #[inline(always)]
+    pub fn unsold(&self, is_x: bool) -> u64 {
+        u64::from_le_bytes(if is_x { self.unsold_x } else { self.unsold_y })
+    }
+
+    /// 获取订单换得且尚未领取的某一侧代币
+    #[inline(always)]
+    pub fn owed(&self, is_x: bool) -> u64 {
+        u64::from_le_bytes(if is_x { self.owed_x } else { self.owed_y })
+    }
+
+    /// 是否还有未关闭的订单
+    #[inline(always)]
+    pub fn has_orders(&self) -> bool {
+        self.expiries.iter().any(|expiry| expiry.orders() > 0)
+    }
+
+    /// 到期时间为 end_at 的订单当前可结算到的 (累计收益, 累计卖出量)
+    ///
+    /// 已到期的订单使用到期时刻的快照，未到期的使用当前值
+    pub fn accumulators_at(&self, is_x: bool, end_at: i64) -> Result<(u128, u128), ProgramError> {
+        if end_at > self.last_executed() {
+            return Ok((self.earnings(is_x), self.sold(is_x)));
+        }
+
+        let expiry = &self.expiries[self.expiry_index(end_at).ok_or(ProgramError::InvalidAccountData)?];
+        Ok(if is_x {
+            (u128::from_le_bytes(expiry.earnings_x), u128::from_le_bytes(expiry.sold_x))
+        } else {
+            (u128::from_le_bytes(expiry.earnings_y), u128::from_le_bytes(expiry.sold_y))
+        })
+    }
+
+    /// 查找到期时间为 end_at 且仍被订单引用的汇总项
+    fn expiry_index(&self, end_at: i64) -> Option<usize> {
+        self.expiries
+            .iter()
+            .position(|expiry| expiry.orders() > 0 && expiry.end_at() == end_at)
+    }
+
+    /// 查找 (after, until] 区间内最早到期的汇总项
+    fn next_expiry(&self, after: i64, until: i64) -> Option<usize> {
+        self.expiries
+            .iter()
+            .enumerate()
+            .filter(|(_, expiry)| {
+                expiry.orders() > 0 && expiry.end_at() > after && expiry.end_at() <= until
+            })
+            .min_by_key(|(_, expiry)| expiry.end_at())
+            .map(|(index, _)| index)
+    }
+
+    /// 把长期订单结算到 now
+    ///
+    /// 按到期时间把区间切分成若干段，每段内两个方向的卖出速率不变，
+    /// 依次按（加权）恒定乘积曲线卖入池子；成交结果只调整 Config 中属于订单的
+    /// 数量，不发生实际转账。只记录实际卖出的数量，池子没有流动性的时段不成交；
+    /// 池子暂停或仅限提取期间只推进时间和处理到期，订单在这段时间不卖出
+    pub fn execute(
+        &mut self,
+        config: &mut Config,
+        balance_x: u64,
+        balance_y: u64,
+        now: i64,
+    ) -> Result<(), ProgramError> {
+        let (mut reserve_x, mut reserve_y) = config.reserves(balance_x, balance_y);
+        let fee = config.fee();
+        let trading = config.is_initialized();
+
+        let mut last_executed = self.last_executed();
+        while last_executed < now {
+            let next_expiry = self.next_expiry(last_executed, now);
+            let until = next_expiry.map_or(now, |index| self.expiries[index].end_at());
+            let elapsed = (until - last_executed) as u128;
+
+            // LBP 池子按本段结束时的权重定价
+            let (weight_x, weight_y) = config.weights(until);
+            if trading {
+                self.sell(config, true, elapsed, (&mut reserve_x, weight_x), (&mut reserve_y, weight_y), fee)?;
+                self.sell(config, false, elapsed, (&mut reserve_y, weight_y), (&mut reserve_x, weight_x), fee)?;
+            }
+
+            // 到期：扣除该批订单的速率并记录收益快照
+            if let Some(index) = next_expiry {
+                let sell_rate_x = self.sell_rate(true).saturating_sub(u128::from_le_bytes(self.expiries[index].sell_rate_x));
+                let sell_rate_y = self.sell_rate(false).saturating_sub(u128::from_le_bytes(self.expiries[index].sell_rate_y));
+                self.sell_rate_x = sell_rate_x.to_le_bytes();
+                self.sell_rate_y = sell_rate_y.to_le_bytes();
+
+                let expiry = &mut self.expiries[index];
+                expiry.earnings_x = self.earnings_x;
+                expiry.earnings_y = self.earnings_y;
+                expiry.sold_x = self.sold_x;
+                expiry.sold_y = self.sold_y;
+            }
+
+            last_executed = until;
+        }
+        self.last_executed = now.to_le_bytes();
+
+        Ok(())
+    }
+
+    /// 把一个方向在 elapsed 秒内应卖出的数量卖入池子
+    fn sell(
+        &mut self,
+        config: &mut Config,
+        is_x: bool,
+        elapsed: u128,
+        (reserve_in, weight_in): (&mut u64, u64),
+        (reserve_out, weight_out): (&mut u64, u64),
+        fee: u16,
+    ) -> Result<(), ProgramError> {
+        let sell_rate = self.sell_rate(is_x);
+        if sell_rate == 0 {
+            return Ok(());
+        }
+
+        let amount_in = u64::try_from(
+            mul_div_floor(sell_rate, elapsed, RATE_PRECISION).ok_or(math_error(MathError::OrderRateOverflow))?,
+        )
+        .unwrap_or(u64::MAX)
+        .min(self.unsold(is_x));
+
+        if amount_in == 0 {
+            return Ok(());
+        }
+
+        // 池子没有流动性时本段不成交
+        let quote = match quote_swap(*reserve_in, *reserve_out, weight_in, weight_out, amount_in, fee) {
+            Ok(quote) => quote,
+            Err(MathError::EmptyReserve) => return Ok(()),
+            Err(error) => return Err(math_error(error)),
+        };
+
+        *reserve_in += amount_in;
+        *reserve_out -= quote.amount_out;
+        config.adjust_reserved(is_x, 0, amount_in)?;
+        config.adjust_reserved(!is_x, quote.amount_out, 0)?;
+        self.adjust_unsold(is_x, 0, amount_in)?;
+        self.adjust_owed(!is_x, quote.amount_out, 0)?;
+
+        // 每单位速率的累计值向下取整，订单按速率分摊时合计不超过实际数量
+        let per_rate = |amount: u64| {
+            mul_div_floor(amount as u128, EARNINGS_PRECISION, sell_rate)
+                .ok_or(math_error(MathError::OrderProceedsOverflow))
+        };
+        let earnings = self
+            .earnings(is_x)
+            .checked_add(per_rate(quote.amount_out)?)
+            .ok_or(math_error(MathError::OrderProceedsOverflow))?;
+        let sold = self
+            .sold(is_x)
+            .checked_add(per_rate(amount_in)?)
+            .ok_or(math_error(MathError::OrderRateOverflow))?;
+        if is_x {
+            self.earnings_x = earnings.to_le_bytes();
+            self.sold_x = sold.to_le_bytes();
+        } else {
+            self.earnings_y = earnings.to_le_bytes();
+            self.sold_y = sold.to_le_bytes();
+        }
+
+        Ok(())
+    }
+
+    /// 增减尚未卖出的订单资金
+    fn adjust_unsold(&mut self, is_x: bool, add: u64, sub: u64) -> Result<(), ProgramError> {
+        let unsold = if is_x { &mut self.unsold_x } else { &mut self.unsold_y };
+        *unsold = u64::from_le_bytes(*unsold)
+            .checked_add(add)
+            .and_then(|amount| amount.checked_sub(sub))
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .to_le_bytes();
+
+        Ok(())
+    }
+
+    /// 增减订单换得且尚未领取的数量
+    fn adjust_owed(&mut self, is_x: bool, add: u64, sub: u64) -> Result<(), ProgramError> {
+        let owed = if is_x { &mut self.owed_x } else { &mut self.owed_y };
+        *owed = u64::from_le_bytes(*owed)
+            .checked_add(add)
+            .and_then(|amount| amount.checked_sub(sub))
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .to_le_bytes();
+
+        Ok(())
+    }
+
+    /// 加入一个新订单，返回订单起始的 (累计收益, 累计卖出量)
+    ///
+    /// 调用前必须先结算到当前时间，end_at 必须晚于 last_executed；
+    /// amount 为订单存入的全部数量，同时计入 Config 中属于订单的部分
+    pub fn add_order(
+        &mut self,
+        config: &mut Config,
+        is_x: bool,
+        amount: u64,
+        sell_rate: u128,
+        end_at: i64,
+    ) -> Result<(u128, u128), ProgramError> {
+        let index = match self.expiry_index(end_at) {
+            Some(index) => index,
+            None => {
+                let index = self
+                    .expiries
+                    .iter()
+                    .position(|expiry| expiry.orders() == 0)
+                    .ok_or(AmmError::OrderSlotsFull)?;
+                let expiry = &mut self.expiries[index];
+                expiry.end_at = end_at.to_le_bytes();
+                expiry.sell_rate_x = [0u8; 16];
+                expiry.sell_rate_y = [0u8; 16];
+                expiry.earnings_x = [0u8; 16];
+                expiry.earnings_y = [0u8; 16];
+                expiry.sold_x = [0u8; 16];
+                expiry.sold_y = [0u8; 16];
+                index
+            }
+        };
+
+        let pool_rate = self
+            .sell_rate(is_x)
+            .checked_add(sell_rate)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let expiry = &mut self.expiries[index];
+        let expiry_rate = if is_x { &mut expiry.sell_rate_x } else { &mut expiry.sell_rate_y };
+        *expiry_rate = u128::from_le_bytes(*expiry_rate)
+            .checked_add(sell_rate)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .to_le_bytes();
+        expiry.orders = (expiry.orders() + 1).to_le_bytes();
+
+        if is_x {
+            self.sell_rate_x = pool_rate.to_le_bytes();
+        } else {
+            self.sell_rate_y = pool_rate.to_le_bytes();
+        }
+
+        self.adjust_unsold(is_x, amount, 0)?;
+        config.adjust_reserved(is_x, amount, 0)?;
+
+        Ok((self.earnings(is_x), self.sold(is_x)))
+    }
+
+    /// 结算一个订单，返回 (成交所得, 退回数量)
+    ///
+    /// 成交所得按累计收益的差值计算并向下取整；关闭订单时退回存入数量中
+    /// 实际没有卖出的部分，卖出量向上取整，使退回数量向下取整。
+    /// 最后一个订单关闭时，取整留下的余数从 Config 中属于订单的部分移回池子储备
+    pub fn settle_order(
+        &mut self,
+        config: &mut Config,
+        order: &mut VirtualOrder,
+        close: bool,
+    ) -> Result<(u64, u64), ProgramError> {
+        let is_x = order.is_x();
+        let end_at = order.end_at();
+        let (earnings, sold) = self.accumulators_at(is_x, end_at)?;
+
+        let proceeds = order.proceeds(earnings)?.min(self.owed(!is_x));
+        order.earnings_start = earnings.to_le_bytes();
+        self.adjust_owed(!is_x, 0, proceeds)?;
+        config.adjust_reserved(!is_x, 0, proceeds)?;
+
+        let mut refund = 0;
+        if close {
+            refund = order.amount().saturating_sub(order.sold(sold)?).min(self.unsold(is_x));
+            self.adjust_unsold(is_x, 0, refund)?;
+            config.adjust_reserved(is_x, 0, refund)?;
+            self.remove_order(is_x, order.sell_rate(), end_at)?;
+
+            if !self.has_orders() {
+                self.sweep(config)?;
+            }
+        }
+
+        Ok((proceeds, refund))
+    }
+
+    /// 没有订单时把剩余的取整余数归还池子储备
+    fn sweep(&mut self, config: &mut Config) -> Result<(), ProgramError> {
+        for is_x in [true, false] {
+            let remainder = self.unsold(is_x) + self.owed(is_x);
+            config.adjust_reserved(is_x, 0, remainder)?;
+        }
+        self.unsold_x = [0u8; 8];
+        self.unsold_y = [0u8; 8];
+        self.owed_x = [0u8; 8];
+        self.owed_y = [0u8; 8];
+
+        Ok(())
+    }
+
+    /// 移除一个订单
+    ///
+    /// 未到期的订单同时扣除其速率；已到期的速率在结算时已经扣除
+    fn remove_order(&mut self, is_x: bool, sell_rate: u128, end_at: i64) -> Result<(), ProgramError> {
+        let index = self.expiry_index(end_at).ok_or(ProgramError::InvalidAccountData)?;
+
+        if end_at > self.last_executed() {
+            let pool_rate = self.sell_rate(is_x).saturating_sub(sell_rate);
+            if is_x {
+                self.sell_rate_x = pool_rate.to_le_bytes();
+            } else {
+                self.sell_rate_y = pool_rate.to_le_bytes();
+            }
+
+            let expiry = &mut self.expiries[index];
+            let expiry_rate = if is_x { &mut expiry.sell_rate_x } else { &mut expiry.sell_rate_y };
+            *expiry_rate = u128::from_le_bytes(*expiry_rate).saturating_sub(sell_rate).to_le_bytes();
+        }
+
+        let expiry = &mut self.expiries[index];
+        expiry.orders = (expiry.orders() - 1).to_le_bytes();
+
+        Ok(())
+    }
+}
+
+/// 单个长期订单
+///
+/// PDA 种子：["twamm_order", config, owner, order_id (u64 LE)]
+#[repr(C)]
+pub struct VirtualOrder {
+    pub config: [u8; 32],           // 所属池子的 Config 地址
+    pub owner: [u8; 32],            // 订单所有者
+    pub order_id: [u8; 8],          // 所有者自选的订单编号
+    pub is_x: u8,                   // 1 = 卖出 X 换 Y，0 = 卖出 Y 换 X
+    pub amount: [u8; 8],            // 存入的卖出总量（金库实际收到的数量）
+    pub sell_rate: [u8; 16],        // 卖出速率（乘以 RATE_PRECISION）
+    pub earnings_start: [u8; 16],   // 上次领取时的累计收益
+    pub sold_start: [u8; 16],       // 下单时的累计卖出量
+    pub end_at: [u8; 8],            // 到期时间（Unix 时间戳）
+    pub bump: u8,                   // PDA bump seed
+}
+
+impl VirtualOrder {
+    /// VirtualOrder 结构的大小（字节）
+    pub const LEN: usize = 32                     // config
+        + 32                                      // owner
+        + size_of::<u64>()                        // order_id
+        + size_of::<u8>()                         // is_x
+        + size_of::<u64>()                        // amount
+        + size_of::<u128>()                       // sell_rate
+        + size_of::<u128>()                       // earnings_start
+        + size_of::<u128>()                       // sold_start
+        + size_of::<i64>()                        // end_at
+        + size_of::<u8>();                        // bump
+
+    /// PDA 种子前缀
+    pub const SEED: &'static [u8] = b"twamm_order";
+
+    /// 从字节数组加载 VirtualOrder（不可变）
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// 从字节数组加载 VirtualOrder（可变）
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// 根据存储的 bump 派生订单 PDA 地址
+    #[inline(always)]
+    pub fn derive_address(&self, program_id: &Address) -> Address {
+        Address::derive_address(
+            &[Self::SEED, &self.config, &self.owner, &self.order_id],
+            Some(self.bump),
+            program_id,
+        )
+    }
+
+    /// 是否卖出 X
+    #[inline(always)]
+    pub fn is_x(&self) -> bool {
+        self.is_x != 0
+    }
+
+    /// 获取存入的卖出总量
+    #[inline(always)]
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    /// 获取卖出速率
+    #[inline(always)]
+    pub fn sell_rate(&self) -> u128 {
+        u128::from_le_bytes(self.sell_rate)
+    }
+
+    /// 获取到期时间
+    #[inline(always)]
+    pub fn end_at(&self) -> i64 {
+        i64::from_le_bytes(self.end_at)
+    }
+
+    /// 累计收益从 earnings_start 增长到 earnings 期间获得的数量
+    pub fn proceeds(&self, earnings: u128) -> Result<u64, ProgramError> {
+        let earned = earnings.saturating_sub(u128::from_le_bytes(self.earnings_start));
+        mul_div_floor(self.sell_rate(), earned, EARNINGS_PRECISION)
+            .and_then(|proceeds| u64::try_from(proceeds).ok())
+            .ok_or(math_error(MathError::OrderProceedsOverflow))
+    }
+
+    /// 累计卖出量从 sold_start 增长到 sold 期间实际卖出的数量，向上取整
+    pub fn sold(&self, sold: u128) -> Result<u64, ProgramError> {
+        let sold = sold.saturating_sub(u128::from_le_bytes(self.sold_start));
+        mul_div_ceil(self.sell_rate(), sold, EARNINGS_PRECISION)
+            .and_then(|sold| u64::try_from(sold).ok())
+            .ok_or(math_error(MathError::OrderRateOverflow))
+    }
+}
+
+const _: () = assert!(size_of::<VirtualOrderPool>() == VirtualOrderPool::LEN);
+const _: () = assert!(size_of::<VirtualOrder>() == VirtualOrder::LEN);
@@ -0,0 +1,96 @@
+//! Config 版本测试
+//!
+//! 检查 `Config::read` 能读取任意版本的布局并把缺少的字段按零补齐，
+//! `Config::write` 只写回该布局中存在的字段，以及 `load` 对旧布局的处理
+
+use blueshift_native_amm::{AmmError, AmmState, Config, ID};
+use pinocchio::error::ProgramError;
+use solana_address::Address;
+
+/// 池子费率（基点）
+const FEE: u16 = 30;
+
+/// 当前版本的 Config 账户数据，开启动态费用和许可模式
+fn current() -> Vec<u8> {
+    let mut bytes = vec![0u8; Config::ACCOUNT_LEN];
+    let state = Config::init(&mut bytes).unwrap();
+    let authority = Address::new_from_array([7; 32]);
+    let mint_x = Address::new_from_array([1; 32]);
+    let mint_y = Address::new_from_array([2; 32]);
+    state.set_inner(FEE as u64, &authority, &mint_x, &mint_y, FEE, 254);
+    state.set_dynamic_fee(true, 10, 100, 5_000, 600).unwrap();
+    state.permissioned = 1;
+    bytes
+}
+
+/// 最早的 108 字节无账户头布局
+fn baseline() -> Vec<u8> {
+    current()[Config::HEADER_LEN..Config::HEADER_LEN + Config::LEGACY_MIN_LEN].to_vec()
+}
+
+#[test]
+fn reads_current_layout() {
+    let bytes = current();
+    let config = Config::read(&bytes).unwrap();
+    let loaded = Config::load(&bytes).unwrap();
+
+    assert_eq!((config.seed(), config.fee()), (loaded.seed(), loaded.fee()));
+    assert!(config.is_dynamic_fee() && config.is_permissioned());
+    assert_eq!(config.effective_fee(0), loaded.effective_fee(0));
+}
+
+#[test]
+fn baseline_layout_reads_with_features_off() {
+    let bytes = baseline();
+    assert_eq!(Config::load(&bytes).err(), Some(AmmError::ConfigOutdated.into()));
+
+    // 最早布局中的字段原样读取，之后追加的字段全部按关闭处理
+    let config = Config::read(&bytes).unwrap();
+    assert!(config.is_initialized());
+    assert_eq!((config.seed(), config.fee(), config.config_bump), (FEE as u64, FEE, 254));
+    assert!(config.is_authority(&Address::new_from_array([7; 32])));
+    assert_eq!(config.mint_y_address(), Address::new_from_array([2; 32]));
+    assert!(!config.is_dynamic_fee() && !config.is_permissioned() && !config.is_lbp());
+    assert!(!config.has_virtual_orders() && !config.has_limit_orders() && !config.has_oracle());
+    assert_eq!(config.effective_fee(0), FEE);
+    assert_eq!(config.reserves(1_000, 2_000), (1_000, 2_000));
+
+    // 旧布局没有记录 LP Mint，按规范 PDA 派生
+    let pool = Address::new_from_array([3; 32]);
+    let (expected, _) = Address::find_program_address(&[b"mint_lp", pool.as_ref()], &ID);
+    assert_eq!(config.mint_lp, [0u8; 32]);
+    assert_eq!(config.mint_lp_address(&pool, &ID), expected);
+}
+
+#[test]
+fn write_keeps_the_account_layout() {
+    // 写回旧布局时只覆盖其中存在的字段，账户长度不变
+    let mut bytes = baseline();
+    let mut config = Config::read(&bytes).unwrap();
+    config.state = AmmState::WithdrawOnly as u8;
+    config.check_price_impact(7, 25).unwrap();
+    config.write(&mut bytes).unwrap();
+
+    assert_eq!(bytes.len(), Config::LEGACY_MIN_LEN);
+    assert_eq!(bytes[0], AmmState::WithdrawOnly as u8);
+    assert_eq!(bytes[1..], baseline()[1..]);
+
+    // 当前布局完整写回
+    let mut bytes = current();
+    let mut config = Config::read(&bytes).unwrap();
+    config.record_price_move(100, 40);
+    config.write(&mut bytes).unwrap();
+    assert_eq!(Config::load(&bytes).unwrap().decayed_volatility(100), 40);
+}
+
+#[test]
+fn rejects_unrecognized_data() {
+    let invalid = Err::<(), ProgramError>(AmmError::InvalidConfig.into());
+    assert_eq!(Config::read(&[0xFF; 200]).map(|_| ()), invalid);
+    assert_eq!(Config::read(&baseline()[..100]).map(|_| ()), invalid);
+
+    // 带账户头但字段不足最早布局
+    let mut bytes = current();
+    bytes.truncate(Config::HEADER_LEN + 50);
+    assert_eq!(Config::read(&bytes).map(|_| ()), invalid);
+}
@@ -0,0 +1,41 @@
+//! 错误码测试
+//!
+//! 错误码发布后不得修改：检查每个 AmmError / MathError 都能通过 `error_name`
+//! 和 `from_code` 从 `ProgramError::Custom` 的错误码还原，且两类错误码连续、互不重叠
+
+use blueshift_native_amm::{error_name, math::MathError, math_error, AmmError};
+use pinocchio::error::ProgramError;
+
+#[test]
+fn amm_errors_round_trip() {
+    for (index, error) in AmmError::ALL.into_iter().enumerate() {
+        // 按顺序从 0 开始编号，新增错误只追加在末尾
+        assert_eq!(error.code(), index as u32, "{error:?}");
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(error.code()));
+        assert_eq!(AmmError::from_code(error.code()), Some(error));
+        assert_eq!(error_name(error.code()), Some(error.name()));
+        assert_eq!(error.name(), format!("{error:?}"));
+    }
+}
+
+#[test]
+fn math_errors_round_trip() {
+    for (index, error) in MathError::ALL.into_iter().enumerate() {
+        assert_eq!(error.code(), 0x100 + index as u32, "{error:?}");
+        assert_eq!(math_error(error), ProgramError::Custom(error.code()));
+        assert_eq!(MathError::from_code(error.code()), Some(error));
+        assert_eq!(error_name(error.code()), Some(error.name()));
+        assert_eq!(error.name(), format!("{error:?}"));
+    }
+}
+
+#[test]
+fn unknown_codes_have_no_name() {
+    let amm_end = AmmError::ALL.len() as u32;
+    let math_end = 0x100 + MathError::ALL.len() as u32;
+    assert!(amm_end < 0x100);
+
+    for code in [amm_end, 0xff, math_end, u32::MAX] {
+        assert_eq!(error_name(code), None, "{code:#x}");
+    }
+}
@@ -0,0 +1,109 @@
+//! LP 质押奖励测试
+//!
+//! 直接在内存中的 Farm 和 StakePosition 上按指令的顺序推进累加器、结算和领取，
+//! 检查释放的奖励不超过奖励金库中尚未分配的余额，金库重新注资后继续释放
+
+use blueshift_native_amm::{AmmError, Farm, StakePosition};
+
+/// farm 创建时的 unix 时间戳
+const NOW: i64 = 1_700_000_000;
+
+/// 创建每秒释放 rate 的 farm
+fn farm(rate: u64) -> Vec<u8> {
+    let mut bytes = vec![0u8; Farm::LEN];
+    let state = Farm::load_mut(&mut bytes).unwrap();
+    state.reward_rate = rate.to_le_bytes();
+    state.last_update = NOW.to_le_bytes();
+    bytes
+}
+
+/// 按 Stake 指令的顺序质押 amount
+fn stake(farm: &mut Farm, position: &mut StakePosition, amount: u64, now: i64, reward_balance: u64) {
+    farm.update(now, reward_balance).unwrap();
+    position.settle(farm.acc_reward_per_share(), position.amount() + amount).unwrap();
+    farm.total_staked = (farm.total_staked() + amount).to_le_bytes();
+}
+
+/// 按 ClaimRewards 指令的顺序领取奖励，返回领取数量
+fn claim(farm: &mut Farm, position: &mut StakePosition, now: i64, reward_balance: u64) -> u64 {
+    farm.update(now, reward_balance).unwrap();
+    position.settle(farm.acc_reward_per_share(), position.amount()).unwrap();
+    let claimed = position.pending_rewards().min(reward_balance);
+    position.pending_rewards = (position.pending_rewards() - claimed).to_le_bytes();
+    farm.pay(claimed);
+    claimed
+}
+
+#[test]
+fn accrual_capped_at_reward_vault() {
+    // 每秒 100，金库只注入 5000：50 秒后停止释放
+    let mut farm_bytes = farm(100);
+    let farm = Farm::load_mut(&mut farm_bytes).unwrap();
+    let mut position_bytes = vec![0u8; StakePosition::LEN];
+    let position = StakePosition::load_mut(&mut position_bytes).unwrap();
+
+    stake(farm, position, 1_000, NOW, 5_000);
+    farm.update(NOW + 1_000, 5_000).unwrap();
+    assert_eq!(farm.reward_owed(), 5_000);
+
+    // 已释放的奖励足额领取，金库清空后不再累积
+    let mut vault = 5_000;
+    vault -= claim(farm, position, NOW + 1_000, vault);
+    assert_eq!(vault, 0);
+    assert_eq!(farm.reward_owed(), 0);
+    assert_eq!(claim(farm, position, NOW + 2_000, vault), 0);
+    assert_eq!(position.pending_rewards(), 0);
+
+    // 重新注资后继续释放，金库耗尽期间已跳过的奖励不补发
+    vault += 10_000;
+    assert_eq!(claim(farm, position, NOW + 2_010, vault), 1_000);
+    assert_eq!(farm.reward_owed(), 0);
+}
+
+#[test]
+fn accrual_shares_unallocated_balance() {
+    // 两个仓位按质押份额分配，释放总量不超过金库余额
+    let mut farm_bytes = farm(1_000);
+    let farm = Farm::load_mut(&mut farm_bytes).unwrap();
+    let mut first_bytes = vec![0u8; StakePosition::LEN];
+    let first = StakePosition::load_mut(&mut first_bytes).unwrap();
+    let mut second_bytes = vec![0u8; StakePosition::LEN];
+    let second = StakePosition::load_mut(&mut second_bytes).unwrap();
+
+    let mut vault = 30_000;
+    stake(farm, first, 400, NOW, vault);
+    stake(farm, second, 100, NOW + 10, vault);
+
+    // 前 10 秒全部归 first，之后按 4:1 分配剩余的 20000
+    let first_claimed = claim(farm, first, NOW + 100, vault);
+    vault -= first_claimed;
+    let second_claimed = claim(farm, second, NOW + 100, vault);
+    vault -= second_claimed;
+
+    assert_eq!(first_claimed, 10_000 + 16_000);
+    assert_eq!(second_claimed, 4_000);
+    assert_eq!(vault, 0);
+    assert_eq!(farm.reward_owed(), 0);
+}
+
+#[test]
+fn update_without_stakers_releases_nothing() {
+    let mut farm_bytes = farm(100);
+    let farm = Farm::load_mut(&mut farm_bytes).unwrap();
+
+    farm.update(NOW + 100, 1_000).unwrap();
+    assert_eq!(farm.reward_owed(), 0);
+    assert_eq!(farm.acc_reward_per_share(), 0);
+}
+
+#[test]
+fn settle_overflow_is_reward_error() {
+    let mut position_bytes = vec![0u8; StakePosition::LEN];
+    let position = StakePosition::load_mut(&mut position_bytes).unwrap();
+    position.amount = u64::MAX.to_le_bytes();
+
+    assert_eq!(
+        position.settle(u128::MAX, u64::MAX).err(),
+        Some(AmmError::RewardOverflow.into())
+    );
+}
@@ -0,0 +1,220 @@
+//! 限价单撮合测试
+//!
+//! 直接在内存中的 Config 和 LimitOrderBook 上执行撮合，检查订单只成交曲线
+//! 在限价之前能承接的部分、恒定乘积不变小，以及订单资金的记账；池子暂停期间不撮合
+
+use blueshift_native_amm::{
+    math::compare_spot_price, AmmError, AmmState, Config, LimitOrderBook, LimitOrderStatus, MAX_LIMIT_ORDERS,
+    PRICE_PRECISION,
+};
+use core::cmp::Ordering;
+use solana_address::Address;
+
+/// 撮合时的 unix 时间戳
+const NOW: i64 = 1_700_000_000;
+/// 池子费率（基点）
+const FEE: u16 = 30;
+
+/// 创建费率为 FEE 的池子，weights 为固定的 X 权重（None 表示普通池子）
+fn config(weight_x: Option<u16>) -> Vec<u8> {
+    let mut bytes = vec![0u8; Config::ACCOUNT_LEN];
+    let state = Config::init(&mut bytes).unwrap();
+    state.set_inner(0, &Address::default(), &Address::default(), &Address::default(), FEE, 0);
+    if let Some(weight_x) = weight_x {
+        state.set_weight_schedule(weight_x, weight_x, NOW - 100, NOW + 100).unwrap();
+    }
+    bytes
+}
+
+/// 挂一个限价单并把托管数量记入 Config，返回订单簿
+fn book(config: &mut Config, is_x: bool, amount: u64, price: u128) -> Vec<u8> {
+    let mut bytes = vec![0u8; LimitOrderBook::LEN];
+    LimitOrderBook::load_mut(&mut bytes)
+        .unwrap()
+        .push(&Address::new_from_array([7u8; 32]), is_x, amount, price)
+        .unwrap();
+    config.adjust_reserved(is_x, amount, 0).unwrap();
+    bytes
+}
+
+/// 加权恒定乘积的对数 wx * ln(x) + wy * ln(y)
+fn weighted_log(x: u64, y: u64, weight_x: u64, weight_y: u64) -> f64 {
+    weight_x as f64 * (x as f64).ln() + weight_y as f64 * (y as f64).ln()
+}
+
+#[test]
+fn fill_stops_at_limit_and_preserves_k() {
+    // 1000000 / 1000000 的池子以 0.99 卖出 1000000 X：全部按限价成交会把池子掏空
+    let (reserve_x, reserve_y) = (1_000_000u64, 1_000_000u64);
+    let amount = 1_000_000u64;
+    let price = 99 * PRICE_PRECISION / 100;
+
+    let mut config_bytes = config(None);
+    let config = Config::load_mut(&mut config_bytes).unwrap();
+    let mut book_bytes = book(config, true, amount, price);
+    let book = LimitOrderBook::load_mut(&mut book_bytes).unwrap();
+
+    let (balance_x, balance_y) = (reserve_x + amount, reserve_y);
+    book.fill(config, balance_x, balance_y, NOW).unwrap();
+
+    // 扣除 0.3% 费用后，只成交边际价格到达 0.99 之前曲线能承接的约 3500 X，剩余部分继续挂单
+    let order = &book.orders[0];
+    let sold = amount - order.amount();
+    assert_eq!(order.status, LimitOrderStatus::Open as u8);
+    assert!((3000..=4000).contains(&sold), "sold {sold}");
+    assert!(order.proceeds() >= order.min_output(sold).unwrap());
+
+    // 成交后 k 不变小，价格仍不低于限价
+    let (new_x, new_y) = config.reserves(balance_x, balance_y);
+    assert_eq!((new_x, new_y), (reserve_x + sold, reserve_y - order.proceeds()));
+    assert!(new_x as u128 * new_y as u128 >= reserve_x as u128 * reserve_y as u128);
+    assert_ne!(compare_spot_price(new_x, new_y, 5000, 5000, price, PRICE_PRECISION), Ordering::Less);
+
+    // 再次撮合不会继续卖出
+    book.fill(config, balance_x, balance_y, NOW).unwrap();
+    assert_eq!(book.orders[0].amount(), amount - sold);
+}
+
+#[test]
+fn fill_skips_limit_within_fee() {
+    // 限价 0.999 在 0.3% 费用范围内，曲线无法以不差于限价的价格成交
+    let mut config_bytes = config(None);
+    let config = Config::load_mut(&mut config_bytes).unwrap();
+    let mut book_bytes = book(config, true, 1_000_000, 999 * PRICE_PRECISION / 1000);
+    let book = LimitOrderBook::load_mut(&mut book_bytes).unwrap();
+
+    book.fill(config, 2_000_000, 1_000_000, NOW).unwrap();
+    assert_eq!(book.orders[0].amount(), 1_000_000);
+    assert_eq!(book.orders[0].proceeds(), 0);
+    assert_eq!(config.reserves(2_000_000, 1_000_000), (1_000_000, 1_000_000));
+}
+
+#[test]
+fn crossed_excludes_fee_band() {
+    // 现货价格 4、费率 0.3%：扣费后的边际价格约为 3.988（卖出 X）和 4.012（卖出 Y）
+    let mut bytes = vec![0u8; LimitOrderBook::LEN];
+    let book = LimitOrderBook::load_mut(&mut bytes).unwrap();
+    let owner = Address::new_from_array([7u8; 32]);
+    for (is_x, price) in [(true, 3_990), (true, 3_980), (false, 4_010), (false, 4_020)] {
+        book.push(&owner, is_x, 1_000, price * PRICE_PRECISION / 1_000).unwrap();
+    }
+
+    let crossed: Vec<bool> = book
+        .orders
+        .iter()
+        .take(4)
+        .map(|order| order.is_crossed(1_000_000, 4_000_000, 5000, 5000, FEE))
+        .collect();
+    assert_eq!(crossed, [false, true, false, true]);
+
+    // 不收费时现货价格越过限价即可成交
+    assert!(book.orders[0].is_crossed(1_000_000, 4_000_000, 5000, 5000, 0));
+    assert!(book.orders[2].is_crossed(1_000_000, 4_000_000, 5000, 5000, 0));
+}
+
+#[test]
+fn push_rejects_full_book() {
+    let mut bytes = vec![0u8; LimitOrderBook::LEN];
+    let book = LimitOrderBook::load_mut(&mut bytes).unwrap();
+    let owner = Address::new_from_array([7u8; 32]);
+    for _ in 0..MAX_LIMIT_ORDERS {
+        book.push(&owner, true, 1_000, PRICE_PRECISION).unwrap();
+    }
+
+    assert_eq!(
+        book.push(&owner, true, 1_000, PRICE_PRECISION).err(),
+        Some(AmmError::OrderSlotsFull.into())
+    );
+}
+
+#[test]
+fn fill_sell_y_stops_at_limit() {
+    // 现货价格 4，挂单以 4.2 买入 X：价格上升到 4.2 之前成交
+    let (reserve_x, reserve_y) = (1_000_000u64, 4_000_000u64);
+    let amount = 10_000_000u64;
+    let price = 42 * PRICE_PRECISION / 10;
+
+    let mut config_bytes = config(None);
+    let config = Config::load_mut(&mut config_bytes).unwrap();
+    let mut book_bytes = book(config, false, amount, price);
+    let book = LimitOrderBook::load_mut(&mut book_bytes).unwrap();
+
+    let (balance_x, balance_y) = (reserve_x, reserve_y + amount);
+    book.fill(config, balance_x, balance_y, NOW).unwrap();
+
+    let order = &book.orders[0];
+    let sold = amount - order.amount();
+    assert!(sold > 0 && sold < amount);
+    assert!(order.proceeds() >= order.min_output(sold).unwrap());
+
+    let (new_x, new_y) = config.reserves(balance_x, balance_y);
+    assert!(new_x as u128 * new_y as u128 >= reserve_x as u128 * reserve_y as u128);
+    assert_ne!(compare_spot_price(new_x, new_y, 5000, 5000, price, PRICE_PRECISION), Ordering::Greater);
+}
+
+#[test]
+fn fill_small_order_completes() {
+    // 曲线足够深时订单全部成交，平均成交价不差于限价
+    let (reserve_x, reserve_y) = (1_000_000_000u64, 1_000_000_000u64);
+    let amount = 10_000u64;
+    let price = 9 * PRICE_PRECISION / 10;
+
+    let mut config_bytes = config(None);
+    let config = Config::load_mut(&mut config_bytes).unwrap();
+    let mut book_bytes = book(config, true, amount, price);
+    let book = LimitOrderBook::load_mut(&mut book_bytes).unwrap();
+
+    book.fill(config, reserve_x + amount, reserve_y, NOW).unwrap();
+
+    let order = &book.orders[0];
+    assert_eq!(order.status, LimitOrderStatus::Filled as u8);
+    assert_eq!(order.amount(), 0);
+    assert!(order.proceeds() >= order.min_output(amount).unwrap());
+    assert_eq!(config.reserved_x(), 0);
+    assert_eq!(config.reserved_y(), order.proceeds());
+}
+
+#[test]
+fn fill_weighted_pool_preserves_invariant() {
+    // X 权重 80% 的池子：现货价格为 (y / 0.2) / (x / 0.8) = 4
+    let (reserve_x, reserve_y) = (1_000_000u64, 1_000_000u64);
+    let amount = 1_000_000u64;
+    let price = 39 * PRICE_PRECISION / 10;
+
+    let mut config_bytes = config(Some(8000));
+    let config = Config::load_mut(&mut config_bytes).unwrap();
+    let mut book_bytes = book(config, true, amount, price);
+    let book = LimitOrderBook::load_mut(&mut book_bytes).unwrap();
+
+    let (balance_x, balance_y) = (reserve_x + amount, reserve_y);
+    book.fill(config, balance_x, balance_y, NOW).unwrap();
+
+    let order = &book.orders[0];
+    let sold = amount - order.amount();
+    assert!(sold > 0 && sold < amount);
+    assert!(order.proceeds() >= order.min_output(sold).unwrap());
+
+    let (new_x, new_y) = config.reserves(balance_x, balance_y);
+    assert!(weighted_log(new_x, new_y, 8000, 2000) >= weighted_log(reserve_x, reserve_y, 8000, 2000));
+    assert_ne!(compare_spot_price(new_x, new_y, 8000, 2000, price, PRICE_PRECISION), Ordering::Less);
+}
+
+#[test]
+fn fill_skips_paused_pool() {
+    // 暂停或仅限提取期间价格越过限价也不撮合，恢复交易后正常成交
+    let mut config_bytes = config(None);
+    let config = Config::load_mut(&mut config_bytes).unwrap();
+    let mut book_bytes = book(config, true, 10_000, 9 * PRICE_PRECISION / 10);
+    let book = LimitOrderBook::load_mut(&mut book_bytes).unwrap();
+
+    for state in [AmmState::Disabled, AmmState::WithdrawOnly] {
+        config.state = state as u8;
+        book.fill(config, 1_000_010_000, 1_000_000_000, NOW).unwrap();
+        assert_eq!(book.orders[0].amount(), 10_000);
+        assert_eq!(book.orders[0].proceeds(), 0);
+    }
+
+    config.state = AmmState::Initialized as u8;
+    book.fill(config, 1_000_010_000, 1_000_000_000, NOW).unwrap();
+    assert_eq!(book.orders[0].status, LimitOrderStatus::Filled as u8);
+}
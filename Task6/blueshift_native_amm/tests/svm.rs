@@ -0,0 +1,1158 @@
+//! 进程内 SVM 集成测试
+//!
+//! 用 mollusk 加载编译后的程序和真实的 SPL Token 程序，端到端执行
+//! Initialize / Deposit / Swap / Withdraw 并检查失败路径和恒定乘积不变量；
+//! 随机操作序列测试检查 k、LP 份额价值和代币守恒。
+//!
+//! 需要先运行 `cargo build-sbf` 生成 `target/deploy/blueshift_native_amm.so`
+//! （或通过 `SBF_OUT_DIR` 指定目录），因此这些测试标记为 `#[ignore]`，
+//! 用 `cargo test --test svm -- --ignored` 显式运行；找不到程序文件时测试失败
+
+use std::{collections::HashMap, path::PathBuf};
+
+use blueshift_amm_client::{
+    instructions::{self, DepositArgs, InitializeArgs, LpMetadata, MetadataField, Pool, SwapArgs, WithdrawArgs},
+    pda::{
+        associated_token_address, order_book_address, order_pool_address, settings_address, SYSTEM_PROGRAM_ID,
+        TOKEN_2022_ID, TOKEN_PROGRAM_ID,
+    },
+    AccountMeta, Address, Instruction,
+};
+use blueshift_native_amm::{
+    math::PRICE_SCALE, AmmError, AmmState, Config, LimitOrderBook, LpLock, VirtualOrder, ID, MAX_LIMIT_ORDERS, MAX_ORDER_EXPIRIES,
+    ORDER_INTERVAL, PRICE_PRECISION,
+};
+use mollusk_svm::{
+    program::{create_program_data_account_loader_v3, loader_keys::LOADER_V3},
+    result::ProgramResult,
+    Mollusk, MolluskContext,
+};
+use proptest::{
+    collection::vec,
+    prelude::*,
+    test_runner::{Config as ProptestConfig, TestRunner},
+};
+use solana_account::Account;
+use solana_rent::Rent;
+use solana_program_error::ProgramError;
+
+/// 测试开始时的 unix 时间戳
+const NOW: i64 = 1_700_000_000;
+/// 池子费率档位（基点）
+const FEE: u16 = 30;
+/// 用户初始持有的 X、Y 数量
+const USER_BALANCE: u64 = 1_000_000_000_000;
+/// 首次存入的 LP、X、Y 数量
+const INITIAL_LP: u64 = 1_000_000;
+const INITIAL_X: u64 = 1_000_000;
+const INITIAL_Y: u64 = 4_000_000;
+
+/// 代币账户中 amount 字段的偏移
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+/// Mint 账户中 supply 字段的偏移
+const MINT_SUPPLY_OFFSET: usize = 36;
+
+/// 查找编译后的程序，优先使用 SBF_OUT_DIR；找不到时 panic 并提示构建命令
+fn load_program() -> Vec<u8> {
+    let mut dirs = Vec::new();
+    if let Ok(dir) = std::env::var("SBF_OUT_DIR") {
+        dirs.push(PathBuf::from(dir));
+    }
+    dirs.push(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/deploy"));
+
+    let elf = dirs
+        .iter()
+        .map(|dir| dir.join("blueshift_native_amm.so"))
+        .find_map(|path| std::fs::read(path).ok());
+    elf.unwrap_or_else(|| panic!("未找到 blueshift_native_amm.so（查找目录：{dirs:?}），请先运行 cargo build-sbf"))
+}
+
+/// 已创建池子并完成首次存入的测试环境
+struct Harness {
+    ctx: MolluskContext<HashMap<Address, Account>>,
+    pool: Pool,
+    user: Address,
+}
+
+impl Harness {
+    /// 创建设置账户和池子，并由用户完成首次存入
+    fn new() -> Self {
+        Self::with_lp_metadata(None)
+    }
+
+    /// 同 `new`，附带 LP 元数据时 LP Mint 由 Token-2022 创建
+    fn with_lp_metadata(lp_metadata: Option<LpMetadata>) -> Self {
+        let elf = load_program();
+
+        let mut mollusk = Mollusk::default();
+        mollusk.add_program_with_loader_and_elf(&ID, &LOADER_V3, &elf);
+        mollusk_svm_programs_token::token::add_program(&mut mollusk);
+        mollusk_svm_programs_token::token2022::add_program(&mut mollusk);
+        mollusk.sysvars.clock.unix_timestamp = NOW;
+
+        // mint_x 必须小于 mint_y
+        let mint_x = Address::new_from_array([1; 32]);
+        let mint_y = Address::new_from_array([2; 32]);
+        let user = Address::new_from_array([9; 32]);
+        let mut pool = Pool::new(&ID, FEE as u64, &mint_x, &mint_y, &TOKEN_PROGRAM_ID, &TOKEN_PROGRAM_ID);
+        if lp_metadata.is_some() {
+            pool = pool.with_token_program_lp(&TOKEN_2022_ID);
+        }
+        let (user_x, user_y, user_lp) = pool.user_token_accounts(&user);
+
+        let rent = &mollusk.sysvars.rent;
+        let mut store = HashMap::new();
+        store.insert(user, Account::new(100_000_000_000, 0, &SYSTEM_PROGRAM_ID));
+        store.insert(program_data_address(), program_data_account(&elf, &user));
+        store.insert(mint_x, mint_account(rent, USER_BALANCE));
+        store.insert(mint_y, mint_account(rent, USER_BALANCE));
+        store.insert(pool.vault_x, token_account(rent, &mint_x, &pool.config, 0));
+        store.insert(pool.vault_y, token_account(rent, &mint_y, &pool.config, 0));
+        store.insert(user_x, token_account(rent, &mint_x, &user, USER_BALANCE));
+        store.insert(user_y, token_account(rent, &mint_y, &user, USER_BALANCE));
+        let mut user_lp_account = token_account(rent, &pool.mint_lp, &user, 0);
+        user_lp_account.owner = pool.token_program_lp;
+        store.insert(user_lp, user_lp_account);
+
+        let mut harness = Self {
+            ctx: mollusk.with_context(store),
+            pool,
+            user,
+        };
+
+        harness.process(&initialize_settings(&user, &[FEE])).expect_ok();
+        harness
+            .process(&instructions::initialize(
+                &harness.pool,
+                &user,
+                &InitializeArgs {
+                    fee: FEE,
+                    authority: Some(user),
+                    registry_page: 0,
+                    weight_schedule: None,
+                    lp_metadata,
+                },
+            ))
+            .expect_ok();
+        harness
+            .deposit(INITIAL_LP, INITIAL_X, INITIAL_Y, NOW)
+            .expect_ok();
+
+        harness
+    }
+
+    fn process(&mut self, instruction: &Instruction) -> ProgramResult {
+        self.ctx.process_instruction(instruction).program_result
+    }
+
+    fn deposit(&mut self, amount: u64, max_x: u64, max_y: u64, expiration: i64) -> ProgramResult {
+        let instruction = instructions::deposit(
+            &self.pool,
+            &self.user,
+            &DepositArgs { amount, max_x, max_y, expiration },
+        );
+        self.process(&instruction)
+    }
+
+    fn withdraw(&mut self, amount: u64, min_x: u64, min_y: u64, expiration: i64) -> ProgramResult {
+        let instruction = instructions::withdraw(
+            &self.pool,
+            &self.user,
+            &WithdrawArgs { amount, min_x, min_y, expiration },
+        );
+        self.process(&instruction)
+    }
+
+    fn swap(&mut self, is_x: bool, amount: u64, min: u64, expiration: i64) -> ProgramResult {
+        let instruction = instructions::swap(
+            &self.pool,
+            &self.user,
+            &SwapArgs { is_x, amount, min, expiration },
+        );
+        self.process(&instruction)
+    }
+
+    /// 读取代币账户余额
+    fn balance(&self, address: &Address) -> u64 {
+        let store = self.ctx.account_store.borrow();
+        let data = &store[address].data;
+        u64::from_le_bytes(
+            data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+                .try_into()
+                .unwrap(),
+        )
+    }
+
+    /// 金库余额（池子没有订单时即为储备）
+    fn reserves(&self) -> (u64, u64) {
+        (self.balance(&self.pool.vault_x), self.balance(&self.pool.vault_y))
+    }
+
+    fn k(&self) -> u128 {
+        let (x, y) = self.reserves();
+        x as u128 * y as u128
+    }
+
+    /// LP Mint 的供应量
+    fn lp_supply(&self) -> u64 {
+        let store = self.ctx.account_store.borrow();
+        let data = &store[&self.pool.mint_lp].data;
+        u64::from_le_bytes(data[MINT_SUPPLY_OFFSET..MINT_SUPPLY_OFFSET + 8].try_into().unwrap())
+    }
+}
+
+trait ExpectResult {
+    fn expect_ok(self);
+    fn expect_err(self, error: ProgramError);
+}
+
+impl ExpectResult for ProgramResult {
+    fn expect_ok(self) {
+        assert_eq!(self, ProgramResult::Success);
+    }
+
+    fn expect_err(self, error: ProgramError) {
+        assert_eq!(self, ProgramResult::Failure(error));
+    }
+}
+
+fn amm_error(error: AmmError) -> ProgramError {
+    ProgramError::Custom(error.code())
+}
+
+/// 按 Token 程序布局打包的 Mint 账户（无铸币权限，decimals 为 6）
+fn mint_account(rent: &Rent, supply: u64) -> Account {
+    let mut data = vec![0u8; 82];
+    data[MINT_SUPPLY_OFFSET..MINT_SUPPLY_OFFSET + 8].copy_from_slice(&supply.to_le_bytes());
+    data[44] = 6; // decimals
+    data[45] = 1; // is_initialized
+    Account {
+        lamports: rent.minimum_balance(data.len()),
+        data,
+        owner: TOKEN_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// 按 Token 程序布局打包的已初始化代币账户
+fn token_account(rent: &Rent, mint: &Address, owner: &Address, amount: u64) -> Account {
+    let mut data = vec![0u8; 165];
+    data[0..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data[108] = 1; // AccountState::Initialized
+    Account {
+        lamports: rent.minimum_balance(data.len()),
+        data,
+        owner: TOKEN_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// 本程序的 ProgramData 地址
+fn program_data_address() -> Address {
+    Address::find_program_address(&[ID.as_ref()], &LOADER_V3).0
+}
+
+/// 升级权限为 authority 的 ProgramData 账户
+fn program_data_account(elf: &[u8], authority: &Address) -> Account {
+    let mut account = create_program_data_account_loader_v3(elf);
+    account.data[12] = 1;
+    account.data[13..45].copy_from_slice(authority.as_ref());
+    account
+}
+
+/// 构建 InitializeSettings 指令（客户端只提供池子指令的构建）
+fn initialize_settings(authority: &Address, fee_tiers: &[u16]) -> Instruction {
+    let (settings, bump) = settings_address(&ID);
+
+    let mut data = vec![4, bump, fee_tiers.len() as u8];
+    for tier in fee_tiers {
+        data.extend_from_slice(&tier.to_le_bytes());
+    }
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(settings, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(program_data_address(), false),
+        ],
+        data,
+    }
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn initialize_and_first_deposit() {
+    let harness = Harness::new();
+    let (_, _, user_lp) = harness.pool.user_token_accounts(&harness.user);
+
+    assert_eq!(harness.reserves(), (INITIAL_X, INITIAL_Y));
+    assert_eq!(harness.balance(&user_lp), INITIAL_LP);
+    assert_eq!(
+        harness.ctx.account_store.borrow()[&harness.pool.config].owner,
+        ID
+    );
+    let store = harness.ctx.account_store.borrow();
+    assert_eq!(
+        Config::load(&store[&harness.pool.config].data).unwrap().mint_lp,
+        *harness.pool.mint_lp.as_array()
+    );
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn only_settings_authority_assigns_pool_authority() {
+    let mut harness = Harness::new();
+    let stranger = Address::new_from_array([4; 32]);
+    harness
+        .ctx
+        .account_store
+        .borrow_mut()
+        .insert(stranger, Account::new(100_000_000_000, 0, &SYSTEM_PROGRAM_ID));
+
+    // 升级权限以外的钱包不能创建设置账户
+    harness
+        .process(&initialize_settings(&stranger, &[FEE]))
+        .expect_err(ProgramError::IncorrectAuthority);
+
+    // 其他人创建的规范池子不能指定管理权限
+    let mint_z = Address::new_from_array([3; 32]);
+    let pool = Pool::new(&ID, FEE as u64, &harness.pool.mint_x, &mint_z, &TOKEN_PROGRAM_ID, &TOKEN_PROGRAM_ID);
+    let args = |authority| InitializeArgs {
+        fee: FEE,
+        authority,
+        registry_page: 0,
+        weight_schedule: None,
+        lp_metadata: None,
+    };
+    harness
+        .process(&instructions::initialize(&pool, &stranger, &args(Some(stranger))))
+        .expect_err(ProgramError::IncorrectAuthority);
+    harness.process(&instructions::initialize(&pool, &stranger, &args(None))).expect_ok();
+}
+
+/// 同一组种子下比规范 bump 小的另一个有效 PDA
+fn non_canonical_address(seeds: &[&[u8]]) -> (Address, u8) {
+    let (_, canonical) = Address::find_program_address(seeds, &ID);
+    (0..canonical)
+        .rev()
+        .find_map(|bump| {
+            let mut seeds = seeds.to_vec();
+            let bump_binding = [bump];
+            seeds.push(&bump_binding);
+            Address::create_program_address(&seeds, &ID).ok().map(|address| (address, bump))
+        })
+        .unwrap()
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn rejects_non_canonical_bumps() {
+    let mut harness = Harness::new();
+    let user = harness.user;
+
+    // 同一交易对和档位不能用其他 bump 再创建一个池子
+    let mut pool = harness.pool.clone();
+    let seed_bytes = pool.seed.to_le_bytes();
+    (pool.config, pool.config_bump) =
+        non_canonical_address(&[b"config", &seed_bytes, pool.mint_x.as_ref(), pool.mint_y.as_ref()]);
+    let args = InitializeArgs {
+        fee: FEE,
+        authority: None,
+        registry_page: 0,
+        weight_schedule: None,
+        lp_metadata: None,
+    };
+    harness
+        .process(&instructions::initialize(&pool, &user, &args))
+        .expect_err(amm_error(AmmError::NonCanonicalBump));
+
+    // 设置账户不能用其他 bump 创建
+    let (settings, bump) = non_canonical_address(&[b"settings"]);
+    let mut instruction = initialize_settings(&user, &[FEE]);
+    instruction.accounts[1].pubkey = settings;
+    instruction.data[1] = bump;
+    harness.process(&instruction).expect_err(amm_error(AmmError::NonCanonicalBump));
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn deposit_swap_withdraw_round_trip() {
+    let mut harness = Harness::new();
+    let (user_x, user_y, user_lp) = harness.pool.user_token_accounts(&harness.user);
+
+    // 按比例再存入一半
+    harness.deposit(INITIAL_LP / 2, INITIAL_X, INITIAL_Y, NOW).expect_ok();
+    assert_eq!(harness.reserves(), (INITIAL_X * 3 / 2, INITIAL_Y * 3 / 2));
+
+    // X 换 Y：用户收到的 Y 与金库减少的 Y 一致
+    let (_, reserve_y) = harness.reserves();
+    let before_y = harness.balance(&user_y);
+    harness.swap(true, 10_000, 1, NOW).expect_ok();
+    let received = harness.balance(&user_y) - before_y;
+    assert!(received > 0);
+    assert_eq!(harness.reserves().1, reserve_y - received);
+
+    // 取出全部 LP 的一半
+    let lp = harness.balance(&user_lp);
+    let (before_x, before_y) = (harness.balance(&user_x), harness.balance(&user_y));
+    harness.withdraw(lp / 2, 1, 1, NOW).expect_ok();
+    assert_eq!(harness.balance(&user_lp), lp - lp / 2);
+    assert!(harness.balance(&user_x) > before_x);
+    assert!(harness.balance(&user_y) > before_y);
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn rejects_missing_signer() {
+    let mut harness = Harness::new();
+
+    let mut instruction = instructions::swap(
+        &harness.pool,
+        &harness.user,
+        &SwapArgs { is_x: true, amount: 1_000, min: 1, expiration: NOW },
+    );
+    instruction.accounts[0].is_signer = false;
+
+    harness.process(&instruction).expect_err(ProgramError::MissingRequiredSignature);
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn rejects_expired_instructions() {
+    let mut harness = Harness::new();
+
+    harness.swap(true, 1_000, 1, NOW - 1).expect_err(amm_error(AmmError::Expired));
+    harness.deposit(1_000, u64::MAX, u64::MAX, NOW - 1).expect_err(amm_error(AmmError::Expired));
+    harness.withdraw(1_000, 1, 1, NOW - 1).expect_err(amm_error(AmmError::Expired));
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn enforces_slippage_limits() {
+    let mut harness = Harness::new();
+
+    harness.swap(true, 10_000, 10_000_000, NOW).expect_err(amm_error(AmmError::SlippageExceeded));
+    harness.deposit(INITIAL_LP, 1, 1, NOW).expect_err(amm_error(AmmError::SlippageExceeded));
+    harness
+        .withdraw(INITIAL_LP / 2, INITIAL_X, INITIAL_Y, NOW)
+        .expect_err(amm_error(AmmError::SlippageExceeded));
+
+    // 失败的指令不改变储备
+    assert_eq!(harness.reserves(), (INITIAL_X, INITIAL_Y));
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn disabled_pool_rejects_trading() {
+    let mut harness = Harness::new();
+
+    let instruction = instructions::set_pool_state(&harness.pool, &harness.user, AmmState::Disabled);
+    harness.process(&instruction).expect_ok();
+
+    harness.swap(true, 1_000, 1, NOW).expect_err(amm_error(AmmError::PoolDisabled));
+    harness.deposit(1_000, u64::MAX, u64::MAX, NOW).expect_err(amm_error(AmmError::PoolDisabled));
+    harness.withdraw(1_000, 1, 1, NOW).expect_err(amm_error(AmmError::PoolDisabled));
+}
+
+/// 铸币权限为 owner 的 Mint 账户
+fn mint_account_with_authority(rent: &Rent, supply: u64, owner: &Address) -> Account {
+    let mut account = mint_account(rent, supply);
+    account.data[0..4].copy_from_slice(&[1, 0, 0, 0]);
+    account.data[4..36].copy_from_slice(owner.as_ref());
+    account
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn rejects_spoofed_lp_mint() {
+    let mut harness = Harness::new();
+
+    // 伪造的 LP Mint：铸币权限同样是 config，但只有 1 个 LP，且全部在攻击者手中
+    let fake_mint = Address::new_from_array([5; 32]);
+    let fake_lp = Address::new_from_array([6; 32]);
+    {
+        let rent = harness.ctx.mollusk.sysvars.rent.clone();
+        let mut store = harness.ctx.account_store.borrow_mut();
+        store.insert(fake_mint, mint_account_with_authority(&rent, 1, &harness.pool.config));
+        store.insert(fake_lp, token_account(&rent, &fake_mint, &harness.user, 1));
+    }
+    let spoof = |mut instruction: Instruction| {
+        instruction.accounts[2].pubkey = fake_mint;
+        instruction.accounts[7].pubkey = fake_lp;
+        instruction
+    };
+
+    let withdraw = instructions::withdraw(
+        &harness.pool,
+        &harness.user,
+        &WithdrawArgs { amount: 1, min_x: 1, min_y: 1, expiration: NOW },
+    );
+    harness.process(&spoof(withdraw)).expect_err(amm_error(AmmError::InvalidLpMint));
+
+    let deposit = instructions::deposit(
+        &harness.pool,
+        &harness.user,
+        &DepositArgs { amount: 1_000, max_x: u64::MAX, max_y: u64::MAX, expiration: NOW },
+    );
+    harness.process(&spoof(deposit)).expect_err(amm_error(AmmError::InvalidLpMint));
+
+    assert_eq!(harness.reserves(), (INITIAL_X, INITIAL_Y));
+}
+
+/// 构建 LockLp 指令，锁仓金库为 lock 的 LP 关联代币账户
+fn lock_lp(pool: &Pool, user: &Address, mint_lp: &Address, user_lp: &Address, amount: u64, unlock_at: i64) -> Instruction {
+    let (lock, lock_bump) =
+        Address::find_program_address(&[LpLock::SEED, mint_lp.as_ref(), user.as_ref()], &ID);
+
+    let mut data = vec![13];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&unlock_at.to_le_bytes());
+    data.push(lock_bump);
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(lock, false),
+            AccountMeta::new(*user_lp, false),
+            AccountMeta::new(associated_token_address(&lock, mint_lp, &TOKEN_PROGRAM_ID), false),
+            AccountMeta::new_readonly(*mint_lp, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn lock_lp_only_accepts_pool_lp() {
+    let mut harness = Harness::new();
+    let pool = harness.pool.clone();
+    let user = harness.user;
+    let (_, _, user_lp) = pool.user_token_accounts(&user);
+
+    // 伪造的 LP Mint 和锁仓金库都齐全，仍然不能冒充该池子的 LP 锁仓
+    let fake_mint = Address::new_from_array([5; 32]);
+    let fake_lp = Address::new_from_array([6; 32]);
+    {
+        let rent = harness.ctx.mollusk.sysvars.rent.clone();
+        let mut store = harness.ctx.account_store.borrow_mut();
+        store.insert(fake_mint, mint_account_with_authority(&rent, 1_000, &pool.config));
+        store.insert(fake_lp, token_account(&rent, &fake_mint, &user, 1_000));
+        for mint in [fake_mint, pool.mint_lp] {
+            let (lock, _) = Address::find_program_address(&[LpLock::SEED, mint.as_ref(), user.as_ref()], &ID);
+            let vault = associated_token_address(&lock, &mint, &TOKEN_PROGRAM_ID);
+            store.insert(vault, token_account(&rent, &mint, &lock, 0));
+        }
+    }
+    harness
+        .process(&lock_lp(&pool, &user, &fake_mint, &fake_lp, 1_000, NOW + 100))
+        .expect_err(amm_error(AmmError::InvalidLpMint));
+
+    // 池子的 LP：锁仓账户记录所属池子
+    harness.process(&lock_lp(&pool, &user, &pool.mint_lp, &user_lp, 1_000, NOW + 100)).expect_ok();
+    let (lock, _) = Address::find_program_address(&[LpLock::SEED, pool.mint_lp.as_ref(), user.as_ref()], &ID);
+    let store = harness.ctx.account_store.borrow();
+    let lock_state = LpLock::load(&store[&lock].data).unwrap();
+    assert_eq!(lock_state.config, *pool.config.as_array());
+    assert_eq!(lock_state.amount(), 1_000);
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn rejects_non_canonical_vaults() {
+    let mut harness = Harness::new();
+
+    // owner 为 config、mint 正确但不是关联代币账户的金库：余额只有 1，会让交换按错误价格成交
+    let fake_vault_x = Address::new_from_array([5; 32]);
+    let fake_vault = Address::new_from_array([6; 32]);
+    {
+        let rent = harness.ctx.mollusk.sysvars.rent.clone();
+        let mut store = harness.ctx.account_store.borrow_mut();
+        store.insert(fake_vault_x, token_account(&rent, &harness.pool.mint_x, &harness.pool.config, 1));
+        store.insert(fake_vault, token_account(&rent, &harness.pool.mint_y, &harness.pool.config, 1));
+    }
+
+    let mut swap = instructions::swap(
+        &harness.pool,
+        &harness.user,
+        &SwapArgs { is_x: false, amount: 1_000, min: 1, expiration: NOW },
+    );
+    swap.accounts[3].pubkey = fake_vault;
+    harness.process(&swap).expect_err(amm_error(AmmError::InvalidVault));
+
+    let mut deposit = instructions::deposit(
+        &harness.pool,
+        &harness.user,
+        &DepositArgs { amount: 1_000, max_x: u64::MAX, max_y: u64::MAX, expiration: NOW },
+    );
+    deposit.accounts[4].pubkey = fake_vault;
+    harness.process(&deposit).expect_err(amm_error(AmmError::InvalidVault));
+
+    let mut withdraw = instructions::withdraw(
+        &harness.pool,
+        &harness.user,
+        &WithdrawArgs { amount: 1_000, min_x: 1, min_y: 1, expiration: NOW },
+    );
+    withdraw.accounts[4].pubkey = fake_vault;
+    harness.process(&withdraw).expect_err(amm_error(AmmError::InvalidVault));
+
+    let mut order = place_limit_order(&harness.pool, &harness.user, 10_000, PRICE_PRECISION);
+    order.accounts[3].pubkey = fake_vault_x;
+    harness.process(&order).expect_err(amm_error(AmmError::InvalidVault));
+
+    assert_eq!(harness.reserves(), (INITIAL_X, INITIAL_Y));
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn k_never_decreases() {
+    let mut harness = Harness::new();
+
+    let mut k = harness.k();
+    let trades = [
+        (true, 1u64),
+        (false, 3),
+        (true, 10_000),
+        (false, 250_000),
+        (true, 999_999),
+        (false, 7),
+        (true, 50_000),
+        (false, 4_000_000),
+    ];
+    for (i, (is_x, amount)) in trades.into_iter().enumerate() {
+        // 每笔交易推进一个 slot，避免触发同一 slot 内的价格变化限制
+        harness.ctx.mollusk.warp_to_slot(i as u64 + 1);
+        harness.ctx.mollusk.sysvars.clock.unix_timestamp = NOW;
+
+        // 输出为零的极小交易会被拒绝，其余必须成功
+        if harness.swap(is_x, amount, 1, NOW) != ProgramResult::Success {
+            assert!(amount < 10, "swap of {amount} failed");
+            continue;
+        }
+        let next = harness.k();
+        assert!(next >= k, "k decreased: {k} -> {next}");
+        k = next;
+    }
+}
+
+/// 随机序列中的一个操作
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Deposit { amount: u64 },
+    Withdraw { lp_bps: u64 },
+    Swap { is_x: bool, amount: u64 },
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn random_sequences_keep_invariants() {
+    let op = prop_oneof![
+        (1u64..=1_000_000).prop_map(|amount| Op::Deposit { amount }),
+        (1u64..=10_000).prop_map(|lp_bps| Op::Withdraw { lp_bps }),
+        (any::<bool>(), 1u64..=10_000_000).prop_map(|(is_x, amount)| Op::Swap { is_x, amount }),
+    ];
+
+    // 默认 32 组，CI 通过 PROPTEST_CASES 运行更多组
+    let cases = std::env::var("PROPTEST_CASES").ok().and_then(|cases| cases.parse().ok()).unwrap_or(32);
+    let mut runner = TestRunner::new(ProptestConfig::with_cases(cases));
+    runner
+        .run(&vec(op, 1..24), |ops| {
+            let mut harness = Harness::new();
+            let (user_x, user_y, user_lp) = harness.pool.user_token_accounts(&harness.user);
+            let total_x = harness.balance(&user_x) + harness.reserves().0;
+            let total_y = harness.balance(&user_y) + harness.reserves().1;
+
+            for op in ops {
+                let (k, supply) = (harness.k(), harness.lp_supply() as u128);
+                // 被拒绝的操作不改变任何账户，只检查成功的操作
+                let result = match op {
+                    Op::Deposit { amount } => harness.deposit(amount, u64::MAX, u64::MAX, NOW),
+                    Op::Withdraw { lp_bps } => {
+                        let amount = harness.balance(&user_lp) * lp_bps / 10_000;
+                        harness.withdraw(amount, 1, 1, NOW)
+                    }
+                    Op::Swap { is_x, amount } => harness.swap(is_x, amount, 1, NOW),
+                };
+                if result != ProgramResult::Success {
+                    continue;
+                }
+
+                let (next_k, next_supply) = (harness.k(), harness.lp_supply() as u128);
+                if let Op::Swap { .. } = op {
+                    prop_assert!(next_k >= k, "k decreased: {} -> {}", k, next_k);
+                }
+                // 每份 LP 对应的 sqrt(k) 不减少
+                prop_assert!(
+                    k * next_supply * next_supply <= next_k * supply * supply,
+                    "LP share value decreased after {:?}",
+                    op
+                );
+                prop_assert_eq!(harness.balance(&user_x) + harness.reserves().0, total_x);
+                prop_assert_eq!(harness.balance(&user_y) + harness.reserves().1, total_y);
+            }
+            Ok(())
+        })
+        .unwrap();
+}
+
+/// 构建 UpdateConfig 指令：设置参考价格保护
+fn set_oracle(pool: &Pool, authority: &Address, oracle: &Address, max_deviation: u16, max_staleness: u64) -> Instruction {
+    let mut data = vec![6, 3];
+    data.extend_from_slice(oracle.as_ref());
+    data.extend_from_slice(&max_deviation.to_le_bytes());
+    data.extend_from_slice(&max_staleness.to_le_bytes());
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pool.config, false),
+        ],
+        data,
+    }
+}
+
+/// 按 PriceFeed 布局打包的参考价格账户
+fn price_feed_account(price: u64, updated_at: i64) -> Account {
+    let mut data = price.to_le_bytes().to_vec();
+    data.extend_from_slice(&updated_at.to_le_bytes());
+    Account {
+        lamports: 1_000_000,
+        data,
+        owner: Address::new_from_array([8; 32]),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn oracle_guard_rejects_stale_and_deviating_prices() {
+    let mut harness = Harness::new();
+
+    // 参考价格 4 Y/X（与池子初始价格一致），最大偏离 5%，有效期 60 秒
+    let feed = Address::new_from_array([7; 32]);
+    let price = 4 * PRICE_SCALE as u64;
+    harness.ctx.account_store.borrow_mut().insert(feed, price_feed_account(price, NOW));
+    let instruction = set_oracle(&harness.pool, &harness.user, &feed, 500, 60);
+    harness.process(&instruction).expect_ok();
+
+    // 未传入价格源的交换缺少账户
+    harness.swap(true, 10_000, 1, NOW).expect_err(ProgramError::NotEnoughAccountKeys);
+
+    // 小额交换的成交价接近参考价格
+    harness.pool = harness.pool.clone().with_oracle(Some(feed));
+    harness.swap(true, 10_000, 1, NOW).expect_ok();
+
+    // 大额交换的价格影响使成交价偏离超过 5%
+    harness.swap(true, 200_000, 1, NOW).expect_err(amm_error(AmmError::OracleDeviationExceeded));
+
+    // 价格源超过有效期
+    harness.ctx.account_store.borrow_mut().insert(feed, price_feed_account(price, NOW - 61));
+    harness.swap(true, 10_000, 1, NOW).expect_err(amm_error(AmmError::OracleStale));
+
+    // 传入的账户不是 Config 中记录的价格源
+    let other = Address::new_from_array([6; 32]);
+    harness.ctx.account_store.borrow_mut().insert(other, price_feed_account(price, NOW));
+    harness.pool = harness.pool.clone().with_oracle(Some(other));
+    harness.swap(true, 10_000, 1, NOW).expect_err(amm_error(AmmError::InvalidOracle));
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn permissioned_pool_still_allows_withdraw() {
+    let mut harness = Harness::new();
+
+    // 已有 LP 的池子切换为许可模式，用户不在白名单
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new_readonly(harness.user, true),
+            AccountMeta::new(harness.pool.config, false),
+        ],
+        data: vec![6, 2, 1],
+    };
+    harness.process(&instruction).expect_ok();
+    harness.pool = harness.pool.clone().with_permissioned(true);
+
+    harness.swap(true, 1_000, 1, NOW).expect_err(amm_error(AmmError::WalletNotAllowed));
+    harness.deposit(1_000, u64::MAX, u64::MAX, NOW).expect_err(amm_error(AmmError::WalletNotAllowed));
+    harness.withdraw(INITIAL_LP, 1, 1, NOW).expect_ok();
+    assert_eq!(harness.lp_supply(), 0);
+}
+
+/// 构建 UpdateConfig 指令：设置暂停权限
+fn set_guardian(pool: &Pool, authority: &Address, guardian: &Address) -> Instruction {
+    let mut data = vec![6, 4];
+    data.extend_from_slice(guardian.as_ref());
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pool.config, false),
+        ],
+        data,
+    }
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn guardian_can_pause_but_not_resume() {
+    let mut harness = Harness::new();
+
+    let guardian = Address::new_from_array([5; 32]);
+    let stranger = Address::new_from_array([4; 32]);
+    let pool = harness.pool.clone();
+
+    // 暂停权限只能由管理权限设置
+    harness
+        .process(&set_guardian(&pool, &guardian, &guardian))
+        .expect_err(ProgramError::IncorrectAuthority);
+    harness.process(&set_guardian(&pool, &harness.user, &guardian)).expect_ok();
+
+    // 其他钱包不能暂停
+    harness
+        .process(&instructions::set_pool_state(&pool, &stranger, AmmState::Disabled))
+        .expect_err(ProgramError::IncorrectAuthority);
+
+    // 暂停权限切换为仅限提取：交换被拒绝，提取仍然可以
+    harness
+        .process(&instructions::set_pool_state(&pool, &guardian, AmmState::WithdrawOnly))
+        .expect_ok();
+    harness.swap(true, 1_000, 1, NOW).expect_err(amm_error(AmmError::PoolDisabled));
+    harness.withdraw(1_000, 1, 1, NOW).expect_ok();
+
+    // 暂停权限不能恢复交易
+    harness
+        .process(&instructions::set_pool_state(&pool, &guardian, AmmState::Initialized))
+        .expect_err(ProgramError::IncorrectAuthority);
+    harness
+        .process(&instructions::set_pool_state(&pool, &guardian, AmmState::Disabled))
+        .expect_ok();
+    harness.withdraw(1_000, 1, 1, NOW).expect_err(amm_error(AmmError::PoolDisabled));
+
+    // 管理权限恢复交易
+    harness
+        .process(&instructions::set_pool_state(&pool, &harness.user, AmmState::Initialized))
+        .expect_ok();
+    harness.swap(true, 1_000, 1, NOW).expect_ok();
+}
+
+/// 构建 PlaceVirtualOrder 指令：用户卖出 X
+fn place_virtual_order(pool: &Pool, user: &Address, order_id: u64, amount: u64, duration: u64) -> Instruction {
+    let order_id = order_id.to_le_bytes();
+    let (order_pool, pool_bump) = order_pool_address(&ID, &pool.config);
+    let (order, order_bump) = Address::find_program_address(
+        &[VirtualOrder::SEED, pool.config.as_ref(), user.as_ref(), &order_id],
+        &ID,
+    );
+    let (user_x, _, _) = pool.user_token_accounts(user);
+
+    let mut data = vec![15, 1];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&duration.to_le_bytes());
+    data.extend_from_slice(&order_id);
+    data.extend_from_slice(&[order_bump, pool_bump]);
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(order_pool, false),
+            AccountMeta::new(order, false),
+            AccountMeta::new(pool.vault_x, false),
+            AccountMeta::new(pool.vault_y, false),
+            AccountMeta::new(user_x, false),
+            AccountMeta::new_readonly(pool.token_program_x, false),
+            AccountMeta::new_readonly(pool.mint_x, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// 构建 PlaceLimitOrder 指令：用户以 price 卖出 X
+fn place_limit_order(pool: &Pool, user: &Address, amount: u64, price: u128) -> Instruction {
+    let (order_book, book_bump) = order_book_address(&ID, &pool.config);
+    let (user_x, _, _) = pool.user_token_accounts(user);
+
+    let mut data = vec![19, 1];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&price.to_le_bytes());
+    data.push(book_bump);
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(order_book, false),
+            AccountMeta::new(pool.vault_x, false),
+            AccountMeta::new(user_x, false),
+            AccountMeta::new_readonly(pool.token_program_x, false),
+            AccountMeta::new_readonly(pool.mint_x, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// 构建 ExecuteVirtualOrders（16）或 FillLimitOrders（20）指令
+fn crank(pool: &Pool, discriminator: u8, orders: &Address) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(*orders, false),
+            AccountMeta::new_readonly(pool.vault_x, false),
+            AccountMeta::new_readonly(pool.vault_y, false),
+        ],
+        data: vec![discriminator],
+    }
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn paused_pool_halts_order_crank() {
+    let mut harness = Harness::new();
+    let user = harness.user;
+    let (order_pool, _) = order_pool_address(&ID, &harness.pool.config);
+    let (order_book, _) = order_book_address(&ID, &harness.pool.config);
+
+    // 长期订单和价格已被越过的限价单（现货价格 4，限价 1）
+    harness.process(&place_virtual_order(&harness.pool, &user, 0, 360_000, 3_600)).expect_ok();
+    harness.process(&place_limit_order(&harness.pool, &user, 10_000, PRICE_PRECISION)).expect_ok();
+    harness.pool = harness.pool.clone().with_virtual_orders(true).with_limit_orders(true);
+    let pool = harness.pool.clone();
+
+    // 暂停：SetPoolState 先把暂停前的时段结算完
+    harness.ctx.mollusk.sysvars.clock.unix_timestamp = NOW + 600;
+    harness
+        .process(&instructions::set_pool_state(&pool, &user, AmmState::Disabled))
+        .expect_ok();
+    let paused = harness.reserves();
+    let paused_config = harness.ctx.account_store.borrow()[&pool.config].data.clone();
+
+    // 暂停期间长期订单结算不卖出，限价单撮合被拒绝
+    harness.ctx.mollusk.sysvars.clock.unix_timestamp = NOW + 1_800;
+    harness.process(&crank(&pool, 16, &order_pool)).expect_ok();
+    harness
+        .process(&crank(&pool, 20, &order_book))
+        .expect_err(amm_error(AmmError::PoolDisabled));
+    assert_eq!(harness.reserves(), paused);
+    assert_eq!(harness.ctx.account_store.borrow()[&pool.config].data, paused_config);
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn order_crank_rejects_non_canonical_vaults() {
+    let mut harness = Harness::new();
+    let user = harness.user;
+    let (order_pool, _) = order_pool_address(&ID, &harness.pool.config);
+    let (order_book, _) = order_book_address(&ID, &harness.pool.config);
+
+    // 长期订单和价格已被越过的限价单（现货价格 4，限价 1）
+    harness.process(&place_virtual_order(&harness.pool, &user, 0, 360_000, 3_600)).expect_ok();
+    harness.process(&place_limit_order(&harness.pool, &user, 10_000, PRICE_PRECISION)).expect_ok();
+    harness.pool = harness.pool.clone().with_virtual_orders(true).with_limit_orders(true);
+    let pool = harness.pool.clone();
+    harness.ctx.mollusk.sysvars.clock.unix_timestamp = NOW + 600;
+
+    // owner 为 config 的另一个 Y 代币账户，余额远小于真实金库
+    let fake_vault = Address::new_from_array([6; 32]);
+    {
+        let rent = harness.ctx.mollusk.sysvars.rent.clone();
+        let account = token_account(&rent, &pool.mint_y, &pool.config, 1);
+        harness.ctx.account_store.borrow_mut().insert(fake_vault, account);
+    }
+
+    for (discriminator, orders) in [(16, order_pool), (20, order_book)] {
+        let mut instruction = crank(&pool, discriminator, &orders);
+        instruction.accounts[3].pubkey = fake_vault;
+        harness.process(&instruction).expect_err(amm_error(AmmError::InvalidVault));
+        harness.process(&crank(&pool, discriminator, &orders)).expect_ok();
+    }
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn virtual_orders_require_minimum_size() {
+    let mut harness = Harness::new();
+    let user = harness.user;
+
+    // decimals 为 6 时最小订单为 1000 个最小单位
+    harness
+        .process(&place_virtual_order(&harness.pool, &user, 0, 999, 3_600))
+        .expect_err(amm_error(AmmError::OrderTooSmall));
+
+    // 每个到期时间一个最小订单，占满全部槽位后新的到期时间返回 OrderSlotsFull
+    for order_id in 0..MAX_ORDER_EXPIRIES as u64 {
+        let duration = ORDER_INTERVAL as u64 * (order_id + 1);
+        harness
+            .process(&place_virtual_order(&harness.pool, &user, order_id, 1_000, duration))
+            .expect_ok();
+    }
+    let duration = ORDER_INTERVAL as u64 * (MAX_ORDER_EXPIRIES as u64 + 1);
+    harness
+        .process(&place_virtual_order(&harness.pool, &user, 100, 1_000, duration))
+        .expect_err(amm_error(AmmError::OrderSlotsFull));
+
+    // 已有的到期时间仍可加入新订单
+    harness
+        .process(&place_virtual_order(&harness.pool, &user, 101, 1_000, ORDER_INTERVAL as u64))
+        .expect_ok();
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn limit_orders_in_fee_band_stay_cheap() {
+    let mut harness = Harness::new();
+    let user = harness.user;
+
+    // 现货价格 4、费率 0.3%：限价 3.99 的卖出 X 订单现货价格已越过，但扣费后永远无法成交
+    let price = 399 * PRICE_PRECISION / 100;
+    harness.process(&place_limit_order(&harness.pool, &user, 1_000, price)).expect_ok();
+    harness.pool = harness.pool.clone().with_limit_orders(true);
+    let swap = |harness: &Harness| {
+        instructions::swap(
+            &harness.pool,
+            &harness.user,
+            &SwapArgs { is_x: true, amount: 1_000, min: 1, expiration: NOW },
+        )
+    };
+
+    // 订单簿只有一个订单时的交换开销
+    harness.ctx.mollusk.warp_to_slot(1);
+    let result = harness.ctx.process_instruction(&swap(&harness));
+    assert_eq!(result.program_result, ProgramResult::Success);
+    let single = result.compute_units_consumed;
+
+    // 占满订单簿后，每个无法成交的订单只增加一次价格比较的开销
+    for _ in 1..MAX_LIMIT_ORDERS {
+        harness.process(&place_limit_order(&harness.pool, &user, 1_000, price)).expect_ok();
+    }
+    harness
+        .process(&place_limit_order(&harness.pool, &user, 1_000, price))
+        .expect_err(amm_error(AmmError::OrderSlotsFull));
+
+    harness.ctx.mollusk.warp_to_slot(2);
+    let result = harness.ctx.process_instruction(&swap(&harness));
+    assert_eq!(result.program_result, ProgramResult::Success);
+    let full = result.compute_units_consumed;
+    println!("swap CU: 1 order {single}, {MAX_LIMIT_ORDERS} orders {full}");
+    assert!(full - single < 1_500 * (MAX_LIMIT_ORDERS as u64 - 1), "swap CU {single} -> {full}");
+
+    // 订单保持挂单，没有成交
+    let (order_book, _) = order_book_address(&ID, &harness.pool.config);
+    let store = harness.ctx.account_store.borrow();
+    let book = LimitOrderBook::load(&store[&order_book].data).unwrap();
+    assert!(book.orders.iter().all(|order| order.amount() == 1_000 && order.proceeds() == 0));
+}
+
+/// 把 Config 账户改写为无账户头的旧布局，只保留前 len 字节
+fn downgrade_config(harness: &mut Harness, len: usize) {
+    let mut store = harness.ctx.account_store.borrow_mut();
+    let account = store.get_mut(&harness.pool.config).unwrap();
+    account.data = account.data[Config::HEADER_LEN..Config::HEADER_LEN + len].to_vec();
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn migrate_config_upgrades_legacy_layouts() {
+    let mut harness = Harness::new();
+    let pool = harness.pool.clone();
+
+    // 与 Config 等长的无版本布局可以原地使用
+    downgrade_config(&mut harness, Config::LEN);
+    harness.swap(true, 1_000, 1, NOW).expect_ok();
+
+    // 最早的 108 字节布局：交换照常进行，其余指令需要先升级
+    downgrade_config(&mut harness, Config::LEGACY_MIN_LEN);
+    harness.swap(true, 1_000, 1, NOW).expect_ok();
+    harness
+        .process(&instructions::set_pool_state(&pool, &harness.user, AmmState::Disabled))
+        .expect_err(amm_error(AmmError::ConfigOutdated));
+
+    harness.process(&instructions::migrate_config(&pool, &harness.user)).expect_ok();
+    {
+        let store = harness.ctx.account_store.borrow();
+        let data = &store[&pool.config].data;
+        assert_eq!(data.len(), Config::ACCOUNT_LEN);
+        assert_eq!(data[..Config::HEADER_LEN], [Config::DISCRIMINATOR, Config::VERSION]);
+        assert_eq!(Config::load(data).unwrap().fee(), FEE);
+        assert_eq!(Config::load(data).unwrap().mint_lp, *pool.mint_lp.as_array());
+    }
+    harness.swap(true, 1_000, 1, NOW).expect_ok();
+    harness.withdraw(1_000, 1, 1, NOW).expect_ok();
+
+    // 已是当前版本时不做修改
+    harness.process(&instructions::migrate_config(&pool, &harness.user)).expect_ok();
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn baseline_layout_pool_withdraws_without_migration() {
+    let mut harness = Harness::new();
+    let (user_x, user_y, user_lp) = harness.pool.user_token_accounts(&harness.user);
+
+    // 最早的 108 字节布局：缺少的字段按关闭处理，LP 不需要先迁移就能取回全部资金
+    downgrade_config(&mut harness, Config::LEGACY_MIN_LEN);
+    let (reserve_x, reserve_y) = harness.reserves();
+    let (before_x, before_y) = (harness.balance(&user_x), harness.balance(&user_y));
+    harness.withdraw(INITIAL_LP, 1, 1, NOW).expect_ok();
+
+    assert_eq!(harness.balance(&user_lp), 0);
+    assert_eq!(harness.balance(&user_x), before_x + reserve_x);
+    assert_eq!(harness.balance(&user_y), before_y + reserve_y);
+    assert_eq!(harness.ctx.account_store.borrow()[&harness.pool.config].data.len(), Config::LEGACY_MIN_LEN);
+}
+
+/// LP Mint 账户数据中是否包含该字符串（TokenMetadata 扩展以 borsh String 存储）
+fn lp_mint_contains(harness: &Harness, value: &str) -> bool {
+    let mut expected = (value.len() as u32).to_le_bytes().to_vec();
+    expected.extend_from_slice(value.as_bytes());
+    let store = harness.ctx.account_store.borrow();
+    store[&harness.pool.mint_lp].data.windows(expected.len()).any(|window| window == expected)
+}
+
+#[test]
+#[ignore = "需要 cargo build-sbf 生成的程序，使用 cargo test --test svm -- --ignored 运行"]
+fn lp_metadata_is_created_and_updated_by_authority() {
+    let metadata = LpMetadata::for_pair("X", "Y", "https://example.com/x-y.json");
+    let mut harness = Harness::with_lp_metadata(Some(metadata));
+    let pool = harness.pool.clone();
+
+    // LP Mint 由 Token-2022 创建并带有元数据，存入、交换和提取照常进行
+    assert_eq!(harness.ctx.account_store.borrow()[&pool.mint_lp].owner, TOKEN_2022_ID);
+    assert!(lp_mint_contains(&harness, "X-Y LP"));
+    assert!(lp_mint_contains(&harness, "X-Y"));
+    assert_eq!(harness.lp_supply(), INITIAL_LP);
+    harness.swap(true, 1_000, 1, NOW).expect_ok();
+    harness.withdraw(1_000, 1, 1, NOW).expect_ok();
+
+    // 只有池子管理权限可以修改元数据
+    let stranger = Address::new_from_array([4; 32]);
+    harness.ctx.account_store.borrow_mut().insert(stranger, Account::new(1_000_000_000, 0, &SYSTEM_PROGRAM_ID));
+    harness
+        .process(&instructions::update_lp_metadata(&pool, &stranger, MetadataField::Name, "Fake LP"))
+        .expect_err(ProgramError::IncorrectAuthority);
+
+    // 更长的名称使账户扩容，租金由管理权限补足
+    let name = "Token X - Token Y Liquidity";
+    harness
+        .process(&instructions::update_lp_metadata(&pool, &harness.user, MetadataField::Name, name))
+        .expect_ok();
+    assert!(lp_mint_contains(&harness, name));
+    assert!(!lp_mint_contains(&harness, "X-Y LP"));
+}
@@ -0,0 +1,208 @@
+//! 长期订单（TWAMM）结算测试
+//!
+//! 直接在内存中的 Config、VirtualOrderPool 和 VirtualOrder 上按指令的顺序
+//! 结算、领取和取消订单，检查退回数量来自实际卖出的结果，
+//! 且所有订单关闭后 Config 中属于订单的数量归零，池子暂停期间订单不卖出
+
+use blueshift_native_amm::{AmmState, Config, VirtualOrder, VirtualOrderPool, RATE_PRECISION};
+use solana_address::Address;
+
+/// 测试开始时的 unix 时间戳
+const NOW: i64 = 1_700_000_000;
+/// 池子费率（基点）
+const FEE: u16 = 30;
+
+/// 金库余额，包含池子储备和属于订单的部分
+struct Vaults {
+    x: u64,
+    y: u64,
+}
+
+/// 内存中的池子和长期订单池
+struct Twamm {
+    config: Vec<u8>,
+    pool: Vec<u8>,
+    vaults: Vaults,
+}
+
+impl Twamm {
+    fn new(reserve_x: u64, reserve_y: u64) -> Self {
+        let mut config = vec![0u8; Config::ACCOUNT_LEN];
+        Config::init(&mut config)
+            .unwrap()
+            .set_inner(0, &Address::default(), &Address::default(), &Address::default(), FEE, 0);
+        let mut pool = vec![0u8; VirtualOrderPool::LEN];
+        VirtualOrderPool::load_mut(&mut pool).unwrap().last_executed = NOW.to_le_bytes();
+
+        Self { config, pool, vaults: Vaults { x: reserve_x, y: reserve_y } }
+    }
+
+    fn config(&self) -> &Config {
+        Config::load(&self.config).unwrap()
+    }
+
+    fn pool(&self) -> &VirtualOrderPool {
+        VirtualOrderPool::load(&self.pool).unwrap()
+    }
+
+    /// 先按原状态结算到 now，再切换池子状态（SetPoolState）
+    fn set_state(&mut self, state: AmmState, now: i64) {
+        self.execute(now);
+        Config::load_mut(&mut self.config).unwrap().state = state as u8;
+    }
+
+    /// 结算到 now（ExecuteVirtualOrders）
+    fn execute(&mut self, now: i64) {
+        let config = Config::load_mut(&mut self.config).unwrap();
+        VirtualOrderPool::load_mut(&mut self.pool)
+            .unwrap()
+            .execute(config, self.vaults.x, self.vaults.y, now)
+            .unwrap();
+    }
+
+    /// 在 now 时刻下单（PlaceVirtualOrder）
+    fn place(&mut self, is_x: bool, amount: u64, now: i64, end_at: i64) -> Vec<u8> {
+        self.execute(now);
+        if is_x {
+            self.vaults.x += amount;
+        } else {
+            self.vaults.y += amount;
+        }
+
+        let sell_rate = amount as u128 * RATE_PRECISION / (end_at - now) as u128;
+        let config = Config::load_mut(&mut self.config).unwrap();
+        let (earnings_start, sold_start) = VirtualOrderPool::load_mut(&mut self.pool)
+            .unwrap()
+            .add_order(config, is_x, amount, sell_rate, end_at)
+            .unwrap();
+
+        let mut order = vec![0u8; VirtualOrder::LEN];
+        let state = VirtualOrder::load_mut(&mut order).unwrap();
+        state.is_x = is_x as u8;
+        state.amount = amount.to_le_bytes();
+        state.sell_rate = sell_rate.to_le_bytes();
+        state.earnings_start = earnings_start.to_le_bytes();
+        state.sold_start = sold_start.to_le_bytes();
+        state.end_at = end_at.to_le_bytes();
+        order
+    }
+
+    /// 在 now 时刻领取或取消订单，返回 (成交所得, 退回数量)
+    fn settle(&mut self, order: &mut [u8], now: i64, cancel: bool) -> (u64, u64) {
+        self.execute(now);
+
+        let order = VirtualOrder::load_mut(order).unwrap();
+        let is_x = order.is_x();
+        let close = cancel || now >= order.end_at();
+        let config = Config::load_mut(&mut self.config).unwrap();
+        let (proceeds, refund) = VirtualOrderPool::load_mut(&mut self.pool)
+            .unwrap()
+            .settle_order(config, order, close)
+            .unwrap();
+
+        if is_x {
+            self.vaults.y -= proceeds;
+            self.vaults.x -= refund;
+        } else {
+            self.vaults.x -= proceeds;
+            self.vaults.y -= refund;
+        }
+        (proceeds, refund)
+    }
+}
+
+#[test]
+fn reserved_returns_to_zero_after_all_orders_close() {
+    let mut twamm = Twamm::new(1_000_000_000, 4_000_000_000);
+
+    // 数量和持续时间都不整除，速率取整会留下余数
+    let mut a = twamm.place(true, 1_000_003, NOW, NOW + 7_200);
+    let mut b = twamm.place(false, 777_777, NOW + 13, NOW + 3_600);
+    let mut c = twamm.place(true, 12_345, NOW + 29, NOW + 10_800);
+
+    twamm.execute(NOW + 1_000);
+    let (proceeds, refund) = twamm.settle(&mut a, NOW + 4_000, false);
+    assert!(proceeds > 0);
+    assert_eq!(refund, 0);
+
+    // 取消未到期的订单：退回存入数量中实际没有卖出的部分
+    let (_, refund) = twamm.settle(&mut c, NOW + 5_000, true);
+    let sold = 12_345 * (5_000 - 29) / (10_800 - 29);
+    assert!(refund.abs_diff(12_345 - sold) <= 1, "refund {refund}");
+
+    // 到期后领取：速率和每段卖出量取整留下的余数随订单退回
+    let (proceeds, refund) = twamm.settle(&mut b, NOW + 6_000, false);
+    assert!(proceeds > 0);
+    assert!(refund <= 10, "refund {refund}");
+    let (proceeds, refund) = twamm.settle(&mut a, NOW + 8_000, false);
+    assert!(proceeds > 0);
+    assert!(refund <= 10, "refund {refund}");
+
+    let config = twamm.config();
+    assert_eq!((config.reserved_x(), config.reserved_y()), (0, 0));
+    assert!(!twamm.pool().has_orders());
+    assert_eq!((twamm.pool().unsold(true), twamm.pool().unsold(false)), (0, 0));
+    assert_eq!((twamm.pool().owed(true), twamm.pool().owed(false)), (0, 0));
+    assert_eq!(config.reserves(twamm.vaults.x, twamm.vaults.y), (twamm.vaults.x, twamm.vaults.y));
+}
+
+#[test]
+fn empty_pool_refunds_unsold_amount() {
+    // 池子没有流动性时订单无法成交，到期后全部退回
+    let mut twamm = Twamm::new(0, 0);
+    let mut order = twamm.place(true, 500_001, NOW, NOW + 3_600);
+
+    twamm.execute(NOW + 1_800);
+    assert_eq!(twamm.pool().unsold(true), 500_001);
+
+    assert_eq!(twamm.settle(&mut order, NOW + 3_600, false), (0, 500_001));
+    assert_eq!((twamm.config().reserved_x(), twamm.config().reserved_y()), (0, 0));
+    assert_eq!(twamm.vaults.x, 0);
+}
+
+#[test]
+fn cancel_refunds_only_what_was_not_sold() {
+    // 前半段池子为空，后半段才有流动性：取消时退回的数量包含空池时段
+    let mut twamm = Twamm::new(0, 0);
+    let mut order = twamm.place(false, 3_600_000, NOW, NOW + 3_600);
+
+    twamm.execute(NOW + 1_800);
+    twamm.vaults.x += 1_000_000_000;
+    twamm.vaults.y += 1_000_000_000;
+
+    let (proceeds, refund) = twamm.settle(&mut order, NOW + 2_700, true);
+    assert!(refund.abs_diff(2_700_000) <= 1, "refund {refund}");
+    assert!(proceeds > 0 && proceeds < 900_000);
+    assert_eq!((twamm.config().reserved_x(), twamm.config().reserved_y()), (0, 0));
+    assert_eq!(twamm.config().reserves(twamm.vaults.x, twamm.vaults.y), (twamm.vaults.x, twamm.vaults.y));
+}
+
+#[test]
+fn paused_pool_does_not_sell() {
+    let mut twamm = Twamm::new(1_000_000_000, 1_000_000_000);
+    let mut order = twamm.place(true, 3_600_000, NOW, NOW + 3_600);
+
+    // 暂停前的时段正常卖出
+    twamm.set_state(AmmState::Disabled, NOW + 600);
+    let sold = 3_600_000 - twamm.pool().unsold(true);
+    assert!(sold.abs_diff(600_000) <= 1, "sold {sold}");
+
+    // 暂停期间结算只推进时间，不卖出
+    let reserves = twamm.config().reserves(twamm.vaults.x, twamm.vaults.y);
+    twamm.execute(NOW + 1_800);
+    assert_eq!(twamm.pool().last_executed(), NOW + 1_800);
+    assert_eq!(3_600_000 - twamm.pool().unsold(true), sold);
+    assert_eq!(twamm.config().reserves(twamm.vaults.x, twamm.vaults.y), reserves);
+
+    // 恢复交易后不补卖暂停期间的时段
+    twamm.set_state(AmmState::Initialized, NOW + 2_400);
+    twamm.execute(NOW + 3_000);
+    let resumed = 3_600_000 - twamm.pool().unsold(true) - sold;
+    assert!(resumed.abs_diff(600_000) <= 1, "resumed {resumed}");
+
+    // 到期后退回暂停期间没有卖出的部分
+    let (proceeds, refund) = twamm.settle(&mut order, NOW + 3_600, false);
+    assert!(proceeds > 0);
+    assert!(refund.abs_diff(1_800_000) <= 10, "refund {refund}");
+    assert_eq!((twamm.config().reserved_x(), twamm.config().reserved_y()), (0, 0));
+}